@@ -2,6 +2,8 @@ use anyhow::anyhow;
 
 use format_serde_error::SerdeError;
 
+// Only shows the shape of the input, the error is created by hand
+#[allow(dead_code)]
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 struct Config {
     values: Vec<String>,
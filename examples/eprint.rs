@@ -0,0 +1,20 @@
+use format_serde_error::SerdeError;
+
+fn main() -> Result<(), std::io::Error> {
+    let config_str = "values:
+  - 'first'
+  - 'second'
+  - third:";
+
+    let err = SerdeError::new(
+        config_str.to_string(),
+        (
+            "values[2]: invalid type: map, expected a string at line 4 column 9".into(),
+            Some(4),
+            Some(9),
+        ),
+    );
+
+    err.print()?;
+    err.eprint()
+}
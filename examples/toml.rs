@@ -1,5 +1,7 @@
 use format_serde_error::SerdeError;
 
+// The values are only printed through `Debug`
+#[allow(dead_code)]
 #[derive(Debug, serde::Deserialize)]
 struct Config {
     values: Vec<String>,
@@ -0,0 +1,105 @@
+//! Find the top-level section of the document an error line belongs to. See
+//! [`SerdeError::set_show_anchor_line`](crate::SerdeError::set_show_anchor_line).
+
+use crate::source::Source;
+
+/// Line starting at 1 of the top-level key the given line is nested under.
+/// Inputs that start with `{` are treated as JSON, everything else as YAML.
+/// Returns [`None`] if the line is not nested.
+pub(crate) fn anchor_line(input: &Source, line: usize) -> Option<usize> {
+    let first = input
+        .lines()
+        .map(str::trim_start)
+        .find(|text| !text.is_empty());
+
+    if first.is_some_and(|text| text.starts_with('{')) {
+        json(input, line)
+    } else {
+        yaml(input, line)
+    }
+}
+
+/// Lines starting at 1 of the nearest lines before the given line that are
+/// less indented than every line after them up to the given line, in the
+/// order of the input. These are the keys of the mappings the line is nested
+/// in. At most `limit` lines closest to the given line are returned. Blank
+/// lines and comments are skipped.
+pub(crate) fn ancestor_lines(input: &Source, line: usize, limit: usize) -> Vec<usize> {
+    let indentation = |text: &str| text.chars().take_while(|c| c.is_whitespace()).count();
+
+    let Some(mut current) = input.line(line).map(indentation) else {
+        return Vec::new();
+    };
+
+    let before = input.lines().take(line - 1).collect::<Vec<_>>();
+
+    let mut ancestors = before
+        .iter()
+        .enumerate()
+        .rev()
+        .filter(|(_, text)| !text.trim().is_empty() && !text.trim_start().starts_with('#'))
+        .filter_map(|(index, text)| {
+            let indentation = indentation(text);
+
+            (indentation < current).then(|| {
+                current = indentation;
+                index + 1
+            })
+        })
+        .take(limit)
+        .collect::<Vec<_>>();
+
+    ancestors.reverse();
+
+    ancestors
+}
+
+/// Nearest line before the given line that is not indented. Blank lines,
+/// comments and document markers are skipped.
+fn yaml(input: &Source, line: usize) -> Option<usize> {
+    let text = input.line(line)?;
+
+    if !text.starts_with(char::is_whitespace) {
+        return None;
+    }
+
+    input
+        .lines()
+        .take(line - 1)
+        .enumerate()
+        .filter(|(_, text)| !text.starts_with(char::is_whitespace))
+        .filter(|(_, text)| !text.is_empty() && !text.starts_with('#'))
+        .filter(|(_, text)| !text.starts_with("---") && !text.starts_with("..."))
+        .last()
+        .map(|(index, _)| index + 1)
+}
+
+/// Line of the key in the root object whose value is still open at the start
+/// of the given line. The nesting is tracked by counting brackets outside of
+/// strings.
+fn json(input: &Source, line: usize) -> Option<usize> {
+    let mut depth = 0_usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut anchor = None;
+
+    for (index, text) in input.lines().take(line.checked_sub(1)?).enumerate() {
+        // Back in the root object so every key before has been closed
+        if depth == 1 {
+            anchor = text.trim_start().starts_with('"').then_some(index + 1);
+        }
+
+        for c in text.chars() {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' if in_string => escaped = true,
+                '"' => in_string = !in_string,
+                '{' | '[' if !in_string => depth += 1,
+                '}' | ']' if !in_string => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+        }
+    }
+
+    anchor.filter(|_| depth > 1)
+}
@@ -0,0 +1,93 @@
+//! Removal of ANSI escape sequences from messages and inputs that have been
+//! styled before they were handed to the crate.
+
+use std::borrow::Cow;
+
+const ESCAPE: char = '\u{1b}';
+const BELL: char = '\u{7}';
+
+/// Characters of the text together with if they are part of an escape
+/// sequence. Handles control sequences like `\x1b[1;31m`, operating system
+/// commands like hyperlinks that end with a bell or `\x1b\\` and the short
+/// two character sequences.
+fn classify(text: &str) -> Vec<(char, bool)> {
+    let mut classified = Vec::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != ESCAPE {
+            classified.push((c, false));
+            continue;
+        }
+
+        classified.push((c, true));
+
+        match chars.next() {
+            // Control sequence: parameters and intermediates until a final byte
+            Some('[') => {
+                classified.push(('[', true));
+
+                for c in chars.by_ref() {
+                    classified.push((c, true));
+
+                    if ('\u{40}'..='\u{7e}').contains(&c) {
+                        break;
+                    }
+                }
+            }
+
+            // Operating system command: until a bell or a string terminator
+            Some(']') => {
+                classified.push((']', true));
+
+                while let Some(c) = chars.next() {
+                    classified.push((c, true));
+
+                    if c == BELL {
+                        break;
+                    }
+
+                    if c == ESCAPE && chars.peek() == Some(&'\\') {
+                        classified.push(('\\', true));
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+
+            Some(c) => classified.push((c, true)),
+            None => {}
+        }
+    }
+
+    classified
+}
+
+/// Remove all escape sequences from the text.
+pub(crate) fn strip(text: &str) -> Cow<'_, str> {
+    if !text.contains(ESCAPE) {
+        return Cow::Borrowed(text);
+    }
+
+    Cow::Owned(
+        classify(text)
+            .into_iter()
+            .filter(|(_, escape)| !escape)
+            .map(|(c, _)| c)
+            .collect(),
+    )
+}
+
+/// Map a column in characters of the text to the column in the text after the
+/// escape sequences have been removed with [`strip`].
+pub(crate) fn strip_column(text: &str, column: usize) -> usize {
+    if !text.contains(ESCAPE) {
+        return column;
+    }
+
+    classify(text)
+        .into_iter()
+        .take(column)
+        .filter(|(_, escape)| !escape)
+        .count()
+}
@@ -0,0 +1,104 @@
+//! Consistency checks of the location of an error against its input. See
+//! [`SerdeError::format_checked`](crate::SerdeError::format_checked).
+
+use std::fmt;
+
+use crate::source::Source;
+
+/// Reason why an error can't be rendered with an excerpt of its input.
+/// Returned by
+/// [`SerdeError::format_checked`](crate::SerdeError::format_checked).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatError {
+    /// The line of the error is 0 or behind the end of the input.
+    LineOutOfRange {
+        /// Line of the error starting at 1.
+        line: usize,
+        /// Amount of lines in the input.
+        lines: usize,
+    },
+
+    /// The column of the error is behind the end of its line.
+    ColumnOutOfRange {
+        /// Line of the error starting at 1.
+        line: usize,
+        /// Column of the error starting at 0.
+        column: usize,
+        /// Amount of characters in the line.
+        length: usize,
+    },
+
+    /// Writing the rendered output failed.
+    Fmt,
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LineOutOfRange { line: 0, .. } => {
+                write!(f, "line 0 is not in the input as lines start at 1")
+            }
+
+            Self::LineOutOfRange { line, lines } => {
+                write!(
+                    f,
+                    "line {line} is behind the end of the input with {lines} lines"
+                )
+            }
+
+            Self::ColumnOutOfRange {
+                line,
+                column,
+                length,
+            } => write!(
+                f,
+                "column {column} is behind the end of line {line} with {length} characters"
+            ),
+
+            Self::Fmt => f.write_str("writing the rendered output failed"),
+        }
+    }
+}
+
+impl std::error::Error for FormatError {}
+
+impl From<fmt::Error> for FormatError {
+    fn from(_: fmt::Error) -> Self {
+        Self::Fmt
+    }
+}
+
+/// Check that the location points into the input. The position right behind
+/// the last character of a line and the line behind the last line are
+/// accepted as parsers report errors at the end of the input there.
+pub(crate) fn check_location(
+    input: &Source,
+    line: Option<usize>,
+    column: Option<usize>,
+) -> Result<(), FormatError> {
+    let Some(line) = line else {
+        return Ok(());
+    };
+
+    let lines = input.lines().count();
+
+    if line == 0 || line > lines.saturating_add(1) {
+        return Err(FormatError::LineOutOfRange { line, lines });
+    }
+
+    let (Some(column), Some(text)) = (column, input.line(line)) else {
+        return Ok(());
+    };
+
+    let length = text.chars().count();
+
+    if column > length {
+        return Err(FormatError::ColumnOutOfRange {
+            line,
+            column,
+            length,
+        });
+    }
+
+    Ok(())
+}
@@ -1,9 +1,16 @@
+use std::sync::{
+    Mutex,
+    PoisonError,
+};
+
+use crate::DefaultsGuard;
+
 /// Different behaviors for the crate to allow overriding the colored output
 /// behaviors. Creating the environment variable `NO_COLOR` (value is not
 /// relevant) will disable all coloring. There is also some detection going on
 /// to decide what kind of terminal type is used and if coloring should be used
 /// or not. See [`colored::control`] for more information.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ColoringMode {
     /// Output will always use color regardless of environment variable or
     /// terminal type.
@@ -17,31 +24,48 @@ pub enum ColoringMode {
     UseEnvironment,
 }
 
+/// Coloring mode that was set last. [`colored`] doesn't allow reading it back.
+static COLORING_MODE: Mutex<ColoringMode> = Mutex::new(ColoringMode::UseEnvironment);
+
 /// Change coloring mode across the library. See [`ColoringMode`] for more
 /// information. By default the library will detect if the output should use
-/// color or not [`ColoringMode::UseEnvironment`].
-pub fn set_coloring_mode(control: &ColoringMode) {
+/// color or not [`ColoringMode::UseEnvironment`]. Returns the previous mode.
+pub fn set_coloring_mode(control: &ColoringMode) -> ColoringMode {
+    let mut mode = COLORING_MODE.lock().unwrap_or_else(PoisonError::into_inner);
+
     match control {
         ColoringMode::AlwaysColor => colored::control::set_override(true),
         ColoringMode::NeverColor => colored::control::set_override(false),
         ColoringMode::UseEnvironment => colored::control::unset_override(),
     }
+
+    std::mem::replace(&mut mode, *control)
+}
+
+/// Change the coloring mode until the returned guard is dropped. See
+/// [`set_coloring_mode`].
+pub fn push_coloring_mode(control: &ColoringMode) -> DefaultsGuard {
+    let previous = set_coloring_mode(control);
+
+    DefaultsGuard::new(move || {
+        set_coloring_mode(&previous);
+    })
 }
 
 /// Set coloring mode to never use color in the output
 /// ([`ColoringMode::NeverColor`]).
 pub fn never_color() {
-    set_coloring_mode(&ColoringMode::NeverColor)
+    set_coloring_mode(&ColoringMode::NeverColor);
 }
 
 /// Set coloring mode to always use color in the output
 /// ([`ColoringMode::AlwaysColor`]).
 pub fn always_color() {
-    set_coloring_mode(&ColoringMode::AlwaysColor)
+    set_coloring_mode(&ColoringMode::AlwaysColor);
 }
 
 /// Set coloring mode detect if color should be used in the output or not
 /// ([`ColoringMode::UseEnvironment`]).
 pub fn use_environment() {
-    set_coloring_mode(&ColoringMode::UseEnvironment)
+    set_coloring_mode(&ColoringMode::UseEnvironment);
 }
@@ -0,0 +1,154 @@
+//! Deserializer that attaches the input to its errors.
+
+use serde::de::{
+    self,
+    Visitor,
+};
+
+use crate::SerdeError;
+
+/// Wrapper around the deserializers of the supported formats that returns
+/// [`SerdeError`]s with the input already attached. This removes the need to
+/// pass the input to every place an error is handled.
+///
+/// ```rust
+/// # #[cfg(feature = "serde_yaml")]
+/// # {
+/// use format_serde_error::Deserializer;
+/// use serde::Deserialize;
+///
+/// #[derive(Debug, Deserialize)]
+/// struct Config {
+///     values: Vec<String>,
+/// }
+///
+/// let input = "values:\n  - 'first'\n  - invalid: 'dont'";
+/// let err = Config::deserialize(Deserializer::from_str_yaml(input)).unwrap_err();
+///
+/// assert_eq!(Some(3), err.line());
+/// # }
+/// ```
+pub struct Deserializer<'de> {
+    input: &'de str,
+    inner: Inner<'de>,
+}
+
+enum Inner<'de> {
+    #[cfg(feature = "serde_yaml")]
+    Yaml(serde_yaml::Deserializer<'de>),
+
+    #[cfg(feature = "serde_json")]
+    Json(serde_json::Deserializer<serde_json::de::StrRead<'de>>),
+}
+
+impl std::fmt::Debug for Deserializer<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let format = match self.inner {
+            #[cfg(feature = "serde_yaml")]
+            Inner::Yaml(_) => "yaml",
+
+            #[cfg(feature = "serde_json")]
+            Inner::Json(_) => "json",
+        };
+
+        f.debug_struct("Deserializer")
+            .field("format", &format)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'de> Deserializer<'de> {
+    /// Create a deserializer for YAML input.
+    #[cfg(feature = "serde_yaml")]
+    #[must_use]
+    pub fn from_str_yaml(input: &'de str) -> Self {
+        Self {
+            input,
+            inner: Inner::Yaml(serde_yaml::Deserializer::from_str(input)),
+        }
+    }
+
+    /// Create a deserializer for JSON input. Like [`serde_json::from_str`] it
+    /// fails if there are characters left after the value.
+    #[cfg(feature = "serde_json")]
+    #[must_use]
+    pub fn from_str_json(input: &'de str) -> Self {
+        Self {
+            input,
+            inner: Inner::Json(serde_json::Deserializer::from_str(input)),
+        }
+    }
+}
+
+impl de::Error for SerdeError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        SerdeError::new(String::new(), (msg.to_string().into(), None, None))
+    }
+}
+
+/// Forward the deserialize methods to the deserializer of the format and
+/// attach the input to errors.
+macro_rules! forward {
+    ($($method:ident($($arg:ident: $ty:ty),*)),* $(,)?) => {
+        $(
+            fn $method<V: Visitor<'de>>(
+                self,
+                $($arg: $ty,)*
+                visitor: V,
+            ) -> Result<V::Value, SerdeError> {
+                let input = self.input;
+
+                match self.inner {
+                    #[cfg(feature = "serde_yaml")]
+                    Inner::Yaml(de) => de
+                        .$method($($arg,)* visitor)
+                        .map_err(|err| SerdeError::new(input.to_string(), err)),
+
+                    #[cfg(feature = "serde_json")]
+                    Inner::Json(mut de) => (&mut de)
+                        .$method($($arg,)* visitor)
+                        .and_then(|value| de.end().map(|()| value))
+                        .map_err(|err| SerdeError::new(input.to_string(), err)),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer<'de> {
+    type Error = SerdeError;
+
+    forward! {
+        deserialize_any(),
+        deserialize_bool(),
+        deserialize_i8(),
+        deserialize_i16(),
+        deserialize_i32(),
+        deserialize_i64(),
+        deserialize_i128(),
+        deserialize_u8(),
+        deserialize_u16(),
+        deserialize_u32(),
+        deserialize_u64(),
+        deserialize_u128(),
+        deserialize_f32(),
+        deserialize_f64(),
+        deserialize_char(),
+        deserialize_str(),
+        deserialize_string(),
+        deserialize_bytes(),
+        deserialize_byte_buf(),
+        deserialize_option(),
+        deserialize_unit(),
+        deserialize_unit_struct(name: &'static str),
+        deserialize_newtype_struct(name: &'static str),
+        deserialize_seq(),
+        deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize),
+        deserialize_map(),
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]),
+        deserialize_identifier(),
+        deserialize_ignored_any(),
+    }
+}
@@ -0,0 +1,54 @@
+//! Detection of keys that are defined more than once in a JSON object.
+
+use crate::{
+    enhance::Label,
+    locate::{
+        self,
+        Location,
+    },
+    SerdeError,
+};
+
+/// Check JSON input for keys that are defined more than once in the same
+/// object. `serde_json` silently uses the last definition in that case which
+/// can hide mistakes. Every duplicate is returned as its own error pointing at
+/// the repeated key with a label at the first definition. Keys with the same
+/// name in different objects are not reported. Only the part of the input
+/// before the first syntax error is checked.
+///
+/// ```rust
+/// use format_serde_error::check_duplicate_keys;
+///
+/// let input = r#"{"name": "first", "name": "second"}"#;
+/// let errors = check_duplicate_keys(input);
+///
+/// assert_eq!(1, errors.len());
+/// assert_eq!("duplicate key `name`", errors[0].message());
+/// ```
+#[must_use]
+pub fn check_duplicate_keys(input: &str) -> Vec<SerdeError> {
+    locate::json_duplicate_keys(input)
+        .into_iter()
+        .filter_map(|duplicate| {
+            let first = Location::from_byte_offset(input, duplicate.first)?;
+            let repeated = Location::from_byte_offset(input, duplicate.duplicate)?;
+
+            let mut err = SerdeError::new(
+                input.to_string(),
+                (
+                    format!("duplicate key `{}`", duplicate.key).into(),
+                    Some(repeated.line),
+                    Some(repeated.column),
+                ),
+            );
+
+            err.labels.push(Label {
+                line: first.line,
+                column: first.column,
+                message: "first defined here".to_string(),
+            });
+
+            Some(err)
+        })
+        .collect()
+}
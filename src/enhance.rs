@@ -0,0 +1,1353 @@
+//! Enhancements that are applied to an error right before it is rendered.
+//! They can move the location of the error and attach notes explaining it.
+
+use crate::{
+    hints,
+    Category,
+    ErrorAdapter,
+    Labels,
+    SerdeError,
+};
+
+/// Kind of a note that is shown below the snippet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum NoteKind {
+    Help,
+    Note,
+}
+
+/// Additional information that is shown below the snippet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Note {
+    pub(crate) kind: NoteKind,
+    pub(crate) message: String,
+}
+
+impl Note {
+    /// The note with the label for its kind in front.
+    pub(crate) fn render(&self, labels: &Labels) -> String {
+        let label = match self.kind {
+            NoteKind::Help => &labels.help,
+            NoteKind::Note => &labels.note,
+        };
+
+        format!("{}: {}", label, self.message)
+    }
+}
+
+/// Secondary marker pointing at a position related to the error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Label {
+    pub(crate) line: usize,
+    pub(crate) column: usize,
+    pub(crate) message: String,
+}
+
+/// Range of the input that is underlined. Lines start at 1 and columns are in
+/// characters starting at 0. The end is inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Span {
+    pub(crate) start_line: usize,
+    pub(crate) start_column: usize,
+    pub(crate) end_line: usize,
+    pub(crate) end_column: usize,
+}
+
+impl Span {
+    fn from_byte_offsets(input: &str, start: usize, end: usize) -> Option<Self> {
+        let start = crate::Location::from_byte_offset(input, start)?;
+        let end = crate::Location::from_byte_offset(input, end)?;
+
+        Some(Self {
+            start_line: start.line,
+            start_column: start.column,
+            end_line: end.line,
+            end_column: end.column,
+        })
+    }
+
+    /// First and last column of the span on the given line. Lines inside the
+    /// span are covered from their first to their last character that is not
+    /// whitespace.
+    pub(crate) fn columns_on(&self, line: usize, text: &str) -> Option<(usize, usize)> {
+        if line < self.start_line || line > self.end_line {
+            return None;
+        }
+
+        let content_start = text.chars().take_while(|c| c.is_whitespace()).count();
+        let content_end = text.trim_end().chars().count().checked_sub(1)?;
+
+        let start = if line == self.start_line {
+            self.start_column
+        } else {
+            content_start
+        };
+
+        let end = if line == self.end_line {
+            self.end_column
+        } else {
+            content_end
+        };
+
+        Some((start, end.max(start)))
+    }
+}
+
+/// Location of the error, labels and notes after all enabled enhancements have
+/// been applied.
+#[derive(Debug)]
+pub(crate) struct Annotated {
+    pub(crate) line: Option<usize>,
+    pub(crate) column: Option<usize>,
+    pub(crate) span: Option<Span>,
+    pub(crate) labels: Vec<Label>,
+    pub(crate) notes: Vec<Note>,
+}
+
+impl Annotated {
+    pub(crate) fn new(err: &SerdeError) -> Self {
+        let mut annotated = Self {
+            line: err.line,
+            column: err.column,
+            span: None,
+            labels: err.labels.clone(),
+            notes: err.notes.clone(),
+        };
+
+        if err.settings.relocate_missing_field {
+            annotated.relocate_missing_field(err);
+        }
+
+        if err.settings.mark_key {
+            annotated.mark_key(err);
+        }
+
+        if err.settings.categorize {
+            annotated.categorize(err);
+        }
+
+        if err.ends_early() && annotated.line.is_some() {
+            annotated.end_of_content(err);
+        }
+
+        annotated.behind_last_line(err);
+
+        if err.settings.show_value_preview
+            || (err.settings.categorize && err.category == Some(Category::Data))
+        {
+            annotated.value_preview(err);
+        }
+
+        if let (Some(line), Some(column), Some((start_line, start_column))) =
+            (annotated.line, annotated.column, err.span_start)
+        {
+            annotated.span = Some(Span {
+                start_line,
+                start_column,
+                end_line: line,
+                end_column: column,
+            });
+        } else if err.settings.underline_sequence {
+            annotated.underline_sequence(err);
+        }
+
+        annotated.underline_whole_line(err);
+
+        if let Some(line) = annotated.line {
+            if err.source_map.is_some() && err.mapped_line(line).is_none() {
+                annotated.note(format!(
+                    "line {line} is part of generated text that has no source"
+                ));
+            }
+        }
+
+        for hint in hints::matching(&err.message) {
+            annotated.help(hint);
+        }
+
+        if err.settings.hints {
+            annotated.tab_indentation(err);
+            annotated.yaml_scalar(err);
+            annotated.quote_scalar(err);
+            annotated.trailing_comma(err);
+            annotated.json_quotes(err);
+            annotated.integer_range(err);
+            annotated.unknown_variant(err);
+            #[cfg(feature = "serde_yaml")]
+            annotated.yaml_duplicate_key(err);
+        }
+
+        annotated
+    }
+
+    fn help(&mut self, message: String) {
+        self.notes.push(Note {
+            kind: NoteKind::Help,
+            message,
+        });
+    }
+
+    fn note(&mut self, message: String) {
+        self.notes.push(Note {
+            kind: NoteKind::Note,
+            message,
+        });
+    }
+
+    /// Move the location of missing field errors from the end of the object to
+    /// the start of the object.
+    fn relocate_missing_field(&mut self, err: &SerdeError) {
+        let Some(field) = quoted_after(&err.message, "missing field `") else {
+            return;
+        };
+
+        let start = match (self.line, self.column) {
+            (Some(line), Some(column)) => json_object_start(err.input.text(), line, column)
+                .or_else(|| yaml_mapping_start(err.input.text(), line)),
+            // Without a location the field is missing from the top level
+            _ => first_content_line(err.input.text()),
+        };
+
+        let Some((line, column)) = start else {
+            return;
+        };
+
+        self.line = Some(line);
+        self.column = Some(column);
+        self.help(format!("add a `{field}` key to this object"));
+    }
+
+    /// Explain the message YAML parsers show for tabs in the indentation and
+    /// point at the first tab if the line of the error is indented with one.
+    fn tab_indentation(&mut self, err: &SerdeError) {
+        if !err
+            .message
+            .contains("found character that cannot start any token")
+        {
+            return;
+        }
+
+        let Some(line) = self.line else {
+            return;
+        };
+
+        let Some(text) = err.input.lines().nth(line.saturating_sub(1)) else {
+            return;
+        };
+
+        let Some(tab) = text
+            .chars()
+            .take_while(|c| c.is_whitespace())
+            .position(|c| c == '\t')
+        else {
+            return;
+        };
+
+        if self.column != Some(tab) {
+            self.label(line, tab, "tab");
+        }
+
+        self.help(
+            "YAML does not allow tabs for indentation; replace the tab with spaces".to_string(),
+        );
+    }
+
+    /// Explain type mismatches caused by plain scalars that YAML 1.1 parsers
+    /// read as booleans or numbers like `no` or `012`.
+    fn yaml_scalar(&mut self, err: &SerdeError) {
+        let (Some(line), Some(column)) = (self.line, self.column) else {
+            return;
+        };
+
+        if matches!(
+            err.adapter,
+            ErrorAdapter::Json | ErrorAdapter::Toml | ErrorAdapter::Csv
+        ) || !err.message.contains("invalid type:")
+        {
+            return;
+        }
+
+        let Some(value) = byte_offset(err.input.text(), line, column)
+            .and_then(|offset| value_at(err.input.text(), offset))
+        else {
+            return;
+        };
+
+        // Plain scalars end in front of a comment
+        let token = value.split(" #").next().unwrap_or_default().trim();
+
+        let Some(kind) = yaml_implicit_type(token) else {
+            return;
+        };
+
+        // The fix for values that should be strings is shown by `quote_scalar`
+        if expects_string(&err.message) {
+            self.help(format!("unquoted `{token}` is parsed as {kind} in YAML"));
+        } else {
+            self.help(format!(
+                "unquoted `{token}` is parsed as {kind} in YAML; quote it as \"{token}\""
+            ));
+        }
+    }
+
+    /// Underline the unquoted value where a string was expected and suggest
+    /// the line with the value wrapped in double quotes.
+    fn quote_scalar(&mut self, err: &SerdeError) {
+        let (Some(line), Some(column)) = (self.line, self.column) else {
+            return;
+        };
+
+        // Only scalars are quoted, maps and sequences are likely a mistake in the
+        // structure of the document
+        if matches!(err.adapter, ErrorAdapter::Toml | ErrorAdapter::Csv)
+            || !expects_string(&err.message)
+            || err.message.contains("invalid type: map")
+            || err.message.contains("invalid type: sequence")
+        {
+            return;
+        }
+
+        let Some(value) = byte_offset(err.input.text(), line, column)
+            .and_then(|offset| value_at(err.input.text(), offset))
+        else {
+            return;
+        };
+
+        // Plain scalars in YAML end in front of a comment, JSON has no comments
+        let token = if matches!(err.adapter, ErrorAdapter::Json) {
+            value.as_str()
+        } else {
+            value.split(" #").next().unwrap_or_default().trim()
+        };
+
+        if token.starts_with(['"', '\'', '[', '{']) {
+            return;
+        }
+
+        let (Some((start, end)), Some(line_start)) = (
+            self.token_offsets(err, line, token),
+            byte_offset(err.input.text(), line, 0),
+        ) else {
+            return;
+        };
+
+        self.underline_token(err, line, token);
+
+        let text = err.input.line(line).unwrap_or_default();
+        let quoted = format!("\"{}\"", token.replace('\\', "\\\\").replace('"', "\\\""));
+        let corrected = format!(
+            "{}{quoted}{}",
+            text[..start - line_start].trim_start(),
+            text[end - line_start..].trim_end()
+        );
+
+        // Minified JSON can be a single long line so only the value is shown then
+        if corrected.chars().count() > QUOTED_LINE_LENGTH {
+            self.help(format!("quote the value to keep it a string: `{quoted}`"));
+        } else {
+            self.help(format!(
+                "quote the value to keep it a string: `{corrected}`"
+            ));
+        }
+    }
+
+    /// Move the location of trailing comma errors from the closing bracket
+    /// behind the comma onto the comma.
+    fn trailing_comma(&mut self, err: &SerdeError) {
+        let (Some(line), Some(column)) = (self.line, self.column) else {
+            return;
+        };
+
+        if err.adapter != ErrorAdapter::Json || !err.message.contains("trailing comma") {
+            return;
+        }
+
+        let Some(offset) = byte_offset(err.input.text(), line, column) else {
+            return;
+        };
+
+        let before = err.input.text()[..offset].trim_end();
+        let before = before.strip_suffix([']', '}']).unwrap_or(before).trim_end();
+
+        let Some(comma) = before.strip_suffix(',').map(str::len) else {
+            return;
+        };
+
+        let Some(location) = crate::Location::from_byte_offset(err.input.text(), comma) else {
+            return;
+        };
+
+        self.line = Some(location.line);
+        self.column = Some(location.column);
+        self.span = None;
+        self.help("JSON does not allow a comma after the last element; remove it".to_string());
+    }
+
+    /// Explain strings in single quotes, keys without quotes and comments in
+    /// JSON. The caret is moved onto the quote, the first character of the key
+    /// or the start of the comment.
+    fn json_quotes(&mut self, err: &SerdeError) {
+        let (Some(line), Some(column)) = (self.line, self.column) else {
+            return;
+        };
+
+        if err.adapter != ErrorAdapter::Json
+            || !(err.message.contains("key must be a string")
+                || err.message.contains("expected value")
+                || err.message.contains("expected `,`"))
+        {
+            return;
+        }
+
+        // serde_json counts columns starting at 1
+        let Some(column) = column.checked_sub(1) else {
+            return;
+        };
+
+        let Some(offset) = byte_offset(err.input.text(), line, column) else {
+            return;
+        };
+
+        let rest = err.input.text()[offset..]
+            .split('\n')
+            .next()
+            .unwrap_or_default();
+
+        let help = match rest.chars().next() {
+            Some('/') if rest[1..].starts_with(['/', '*']) => {
+                "JSON does not allow comments; remove it".to_string()
+            }
+            _ if err.message.contains("expected `,`") => return,
+            Some('\'') => {
+                let fix = single_quoted(rest)
+                    .map(|inner| format!("; write \"{inner}\" instead of '{inner}'"))
+                    .unwrap_or_default();
+
+                format!("JSON strings must use double quotes{fix}")
+            }
+            Some(c) if is_identifier_start(c) && err.message.contains("key must be a string") => {
+                let key = rest
+                    .split(|c: char| !is_identifier_continue(c))
+                    .next()
+                    .unwrap_or_default();
+
+                format!("JSON keys must be quoted; write \"{key}\" instead of {key}")
+            }
+            _ => return,
+        };
+
+        self.column = Some(column);
+        self.span = None;
+        self.help(help);
+    }
+
+    /// Point duplicate key errors of YAML parsers at the repeated key and add
+    /// a label to its first definition. The error is left as is if the
+    /// definitions can not be found unambiguously.
+    #[cfg(feature = "serde_yaml")]
+    fn yaml_duplicate_key(&mut self, err: &SerdeError) {
+        if err.adapter != ErrorAdapter::Yaml {
+            return;
+        }
+
+        // Derived structs report duplicate fields, newer versions of
+        // serde_yaml report duplicate entries for all mappings
+        let Some(key) = quoted_after(&err.message, "duplicate field `").or_else(|| {
+            let (_, rest) = err.message.split_once("duplicate entry with key \"")?;
+            rest.split('"').next()
+        }) else {
+            return;
+        };
+
+        let duplicates = crate::locate::yaml_duplicate_keys(err.input.text())
+            .into_iter()
+            .filter_map(|duplicate| {
+                if duplicate.key != key {
+                    return None;
+                }
+
+                let first = crate::Location::from_byte_offset(err.input.text(), duplicate.first)?;
+                let repeated =
+                    crate::Location::from_byte_offset(err.input.text(), duplicate.duplicate)?;
+
+                Some((first, repeated))
+            })
+            .collect::<Vec<_>>();
+
+        // With the same key repeated in multiple mappings the one on the line
+        // of the error is used
+        let found = match duplicates.as_slice() {
+            [only] => Some(only),
+            _ => duplicates.iter().find(|(first, repeated)| {
+                self.line == Some(first.line) || self.line == Some(repeated.line)
+            }),
+        };
+
+        let Some((first, repeated)) = found else {
+            return;
+        };
+
+        self.line = Some(repeated.line);
+        self.column = Some(repeated.column);
+        self.span = None;
+        self.label(first.line, first.column, "first defined here");
+        self.help(
+            "later occurrences override earlier ones in some parsers; remove one".to_string(),
+        );
+    }
+
+    /// Explain the valid range of the integer type in messages about integers
+    /// that don't fit into it.
+    fn integer_range(&mut self, err: &SerdeError) {
+        let Some(value) = quoted_after(&err.message, "invalid value: integer `") else {
+            return;
+        };
+
+        let Some((_, expected)) = err.message.split_once("`, expected ") else {
+            return;
+        };
+
+        let Some((min, max)) = expected
+            .split_whitespace()
+            .next()
+            .and_then(integer_type_range)
+        else {
+            return;
+        };
+
+        if value.starts_with('-') && min == "0" {
+            self.help("this field cannot be negative".to_string());
+        } else {
+            self.help(format!(
+                "this field accepts whole numbers from {min} to {max}"
+            ));
+        }
+    }
+
+    /// Underline the unknown variant and suggest the closest of the variants
+    /// listed in the message. Nothing is suggested if no variant is clearly
+    /// closer than the others or a schema already suggested one.
+    fn unknown_variant(&mut self, err: &SerdeError) {
+        let Some(unknown) = quoted_after(&err.message, "unknown variant `") else {
+            return;
+        };
+
+        if let Some(line) = self.line {
+            self.underline_token(err, line, unknown);
+        }
+
+        let suggested = self
+            .notes
+            .iter()
+            .any(|note| note.kind == NoteKind::Help && note.message.starts_with("did you mean"));
+        if suggested {
+            return;
+        }
+
+        // serde lists the variants as `a`, `a` or `b` and one of `a`, `b`, `c`
+        let Some((_, expected)) = strip_position_suffix(&err.message).split_once("`, expected ")
+        else {
+            return;
+        };
+
+        let candidates = expected.split('`').skip(1).step_by(2).collect::<Vec<_>>();
+
+        if let Some(variant) = clearly_closest(unknown, &candidates) {
+            self.help(format!("did you mean `{variant}`?"));
+        }
+    }
+
+    /// Underline the occurrence of the token on the line that is closest to
+    /// the column of the error.
+    fn underline_token(&mut self, err: &SerdeError, line: usize, token: &str) {
+        let Some(last) = token.chars().last() else {
+            return;
+        };
+
+        let Some((start, end)) = self.token_offsets(err, line, token) else {
+            return;
+        };
+
+        if let Some(span) = Span::from_byte_offsets(err.input.text(), start, end - last.len_utf8())
+        {
+            self.span = Some(span);
+        }
+    }
+
+    /// Byte offsets of the start and the end of the occurrence of the token on
+    /// the line that is closest to the column of the error.
+    fn token_offsets(&self, err: &SerdeError, line: usize, token: &str) -> Option<(usize, usize)> {
+        if token.is_empty() {
+            return None;
+        }
+
+        let line_start = byte_offset(err.input.text(), line, 0)?;
+        let text = err.input.line(line).unwrap_or_default();
+        let column = self.column.unwrap_or_default();
+
+        let (start, _) = text.match_indices(token).min_by_key(|(start, _)| {
+            let start_column = text[..*start].chars().count();
+            let end_column = start_column + token.chars().count();
+
+            start_column.saturating_sub(column) + column.saturating_sub(end_column)
+        })?;
+
+        let start = line_start + start;
+
+        Some((start, start + token.len()))
+    }
+
+    fn label(&mut self, line: usize, column: usize, message: &str) {
+        self.labels.push(Label {
+            line,
+            column,
+            message: message.to_string(),
+        });
+    }
+
+    /// Add a label to the key of the value the error is about.
+    fn mark_key(&mut self, err: &SerdeError) {
+        let (Some(line), Some(column)) = (self.line, self.column) else {
+            return;
+        };
+
+        if !is_value_error(&err.message) {
+            return;
+        }
+
+        let text = err.input.line(line).unwrap_or_default();
+        if let Some(key_column) = key_before(text, column) {
+            self.label(line, key_column, "key here");
+        }
+    }
+
+    /// Adjust the location and add notes depending on the category of the
+    /// error.
+    fn categorize(&mut self, err: &SerdeError) {
+        match err.category {
+            // The location of read errors has nothing to do with the input that
+            // was read so far
+            Some(Category::Io) => {
+                self.line = None;
+                self.column = None;
+            }
+
+            Some(Category::Eof) => {
+                self.end_of_content(err);
+
+                match unclosed(err.input.text()) {
+                    Some((delimiter, line)) => self.note(format!(
+                        "the document ended unexpectedly while the `{delimiter}` from line {line} \
+                         was still open"
+                    )),
+                    None => self.note("the document ended unexpectedly".to_string()),
+                }
+            }
+
+            Some(Category::Data) => self.note("type mismatch".to_string()),
+
+            Some(Category::Syntax) | None => {}
+        }
+    }
+
+    /// Point right after the last character of the input instead of an empty
+    /// line after it.
+    fn end_of_content(&mut self, err: &SerdeError) {
+        if let Some((line, text)) = err
+            .input
+            .lines()
+            .enumerate()
+            .filter(|(_, text)| !text.trim().is_empty())
+            .last()
+        {
+            self.line = Some(line + 1);
+            self.column = Some(text.trim_end().chars().count());
+        }
+    }
+
+    /// Move errors on the line behind the last line, which parsers report for
+    /// inputs ending with a line break, behind the last character of the last
+    /// line. No line is shown that is not part of the input.
+    fn behind_last_line(&mut self, err: &SerdeError) {
+        let Some(line) = self.line else {
+            return;
+        };
+
+        let lines = err.input.lines().count();
+        if lines == 0 || line != lines + 1 {
+            return;
+        }
+
+        let text = err.input.line(lines).unwrap_or_default();
+
+        self.line = Some(lines);
+        self.column = Some(text.chars().count());
+    }
+
+    /// Underline the whole sequence for errors about the length of a
+    /// sequence.
+    fn underline_sequence(&mut self, err: &SerdeError) {
+        let (Some(line), Some(column)) = (self.line, self.column) else {
+            return;
+        };
+
+        if !err.message.contains("invalid length") {
+            return;
+        }
+
+        let Some(offset) = byte_offset(err.input.text(), line, column) else {
+            return;
+        };
+
+        let span = json_sequence(err.input.text(), offset)
+            .or_else(|| yaml_sequence(err.input.text(), offset))
+            .and_then(|(start, end)| Span::from_byte_offsets(err.input.text(), start, end));
+
+        let Some(span) = span else {
+            return;
+        };
+
+        // Tell the full extent of the sequence if not all of its lines are
+        // shown
+        let context = if err.settings.contextualize {
+            err.settings.context_lines
+        } else {
+            0
+        };
+
+        if span.start_line + context < line || span.end_line > line + context {
+            self.note(format!(
+                "the array spans lines {}–{}",
+                span.start_line, span.end_line
+            ));
+        }
+
+        self.span = Some(span);
+    }
+
+    /// Underline the whole line for errors that have a line but no column.
+    fn underline_whole_line(&mut self, err: &SerdeError) {
+        let (Some(line), None) = (self.line, self.column) else {
+            return;
+        };
+
+        let text = err.input.line(line).unwrap_or_default();
+        let Some(end_column) = text.trim_end().chars().count().checked_sub(1) else {
+            return;
+        };
+
+        self.span = Some(Span {
+            start_line: line,
+            start_column: text.chars().take_while(|c| c.is_whitespace()).count(),
+            end_line: line,
+            end_column,
+        });
+    }
+
+    /// Add a note showing the source of the value the error is about.
+    fn value_preview(&mut self, err: &SerdeError) {
+        let (Some(line), Some(column)) = (self.line, self.column) else {
+            return;
+        };
+
+        if !is_value_error(&err.message) {
+            return;
+        }
+
+        let preview = byte_offset(err.input.text(), line, column)
+            .and_then(|offset| value_at(err.input.text(), offset))
+            .map(|value| shorten(&value, VALUE_PREVIEW_LENGTH));
+
+        if let Some(preview) = preview {
+            self.note(format!("the value here is `{preview}`"));
+        }
+    }
+}
+
+/// Maximum amount of characters shown for the value preview.
+const VALUE_PREVIEW_LENGTH: usize = 40;
+
+/// Returns true if the message is about the value of a field having the wrong
+/// type or value.
+fn is_value_error(message: &str) -> bool {
+    ["invalid type:", "invalid value:", "invalid length"]
+        .iter()
+        .any(|shape| message.contains(shape))
+}
+
+/// If the message is about a value of the wrong type where a string was
+/// expected.
+fn expects_string(message: &str) -> bool {
+    message.contains("invalid type:")
+        && strip_position_suffix(message).ends_with("expected a string")
+}
+
+/// Maximum length of the corrected line shown by `quote_scalar`.
+const QUOTED_LINE_LENGTH: usize = 80;
+
+/// Plain scalars that YAML 1.1 resolves to booleans.
+const YAML_BOOLEANS: [&str; 16] = [
+    "y", "Y", "yes", "Yes", "YES", "n", "N", "no", "No", "NO", "on", "On", "ON", "off", "Off",
+    "OFF",
+];
+
+/// Type a plain scalar that reads like text is implicitly resolved to by YAML
+/// 1.1 parsers like `a boolean` for `no`. Returns [`None`] for all other
+/// scalars.
+fn yaml_implicit_type(token: &str) -> Option<&'static str> {
+    if YAML_BOOLEANS.contains(&token) {
+        return Some("a boolean");
+    }
+
+    if matches!(token, "~" | "null" | "Null" | "NULL") {
+        return Some("null");
+    }
+
+    let digits = token.strip_prefix("0o").unwrap_or(token);
+
+    if token.len() > 1
+        && token.starts_with('0')
+        && !digits.is_empty()
+        && digits.chars().all(|c| c.is_ascii_digit())
+    {
+        return Some("an octal number");
+    }
+
+    if token.contains('.') && token.parse::<f64>().is_ok() {
+        return Some("a number");
+    }
+
+    None
+}
+
+/// The candidate that is closer to the text than all others. Candidates
+/// starting with the text count as close if no candidate is within the edit
+/// distance of [`closest`].
+fn clearly_closest<'a>(text: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    match closest(text, candidates.iter().copied()).as_slice() {
+        [single] => Some(*single),
+        [first, second, ..] => {
+            (edit_distance(text, first) < edit_distance(text, second)).then_some(*first)
+        }
+        [] => {
+            let text = text.to_lowercase();
+            let mut prefixed = candidates.iter().filter(|candidate| {
+                !text.is_empty() && candidate.to_lowercase().starts_with(&text)
+            });
+
+            match (prefixed.next(), prefixed.next()) {
+                (Some(single), None) => Some(*single),
+                _ => None,
+            }
+        }
+    }
+}
+
+/// Smallest and largest value of the primitive integer type with the given
+/// name.
+fn integer_type_range(name: &str) -> Option<(String, String)> {
+    let (min, max) = match name {
+        "u8" => (u8::MIN.to_string(), u8::MAX.to_string()),
+        "u16" => (u16::MIN.to_string(), u16::MAX.to_string()),
+        "u32" => (u32::MIN.to_string(), u32::MAX.to_string()),
+        "u64" => (u64::MIN.to_string(), u64::MAX.to_string()),
+        "u128" => (u128::MIN.to_string(), u128::MAX.to_string()),
+        "usize" => (usize::MIN.to_string(), usize::MAX.to_string()),
+        "i8" => (i8::MIN.to_string(), i8::MAX.to_string()),
+        "i16" => (i16::MIN.to_string(), i16::MAX.to_string()),
+        "i32" => (i32::MIN.to_string(), i32::MAX.to_string()),
+        "i64" => (i64::MIN.to_string(), i64::MAX.to_string()),
+        "i128" => (i128::MIN.to_string(), i128::MAX.to_string()),
+        "isize" => (isize::MIN.to_string(), isize::MAX.to_string()),
+        _ => return None,
+    };
+
+    Some((min, max))
+}
+
+/// Content of the single quoted string at the start of the text if it ends on
+/// the same line and can be written in double quotes without escaping.
+fn single_quoted(text: &str) -> Option<&str> {
+    let inner = text.strip_prefix('\'')?;
+    let inner = &inner[..inner.find('\'')?];
+
+    if inner.contains(['"', '\\']) {
+        None
+    } else {
+        Some(inner)
+    }
+}
+
+fn is_identifier_start(c: char) -> bool {
+    c.is_alphabetic() || matches!(c, '_' | '$')
+}
+
+fn is_identifier_continue(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '$' | '-')
+}
+
+/// Collapse all whitespace to single spaces and shorten the text to the given
+/// amount of characters.
+fn shorten(text: &str, length: usize) -> String {
+    let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    if collapsed.chars().count() <= length {
+        return collapsed;
+    }
+
+    let mut shortened = collapsed
+        .chars()
+        .take(length.saturating_sub(crate::ELLIPSE.len()))
+        .collect::<String>();
+    shortened.push_str(crate::ELLIPSE);
+
+    shortened
+}
+
+/// Extract the source of the value at the byte offset. Parsers point either
+/// at the start of a value or right after it so both are handled. Returns
+/// [`None`] if no value could be found.
+fn value_at(input: &str, offset: usize) -> Option<String> {
+    let rest = input.get(offset..)?;
+    let first = rest.chars().next();
+
+    let value = match first {
+        Some('{' | '[') => &rest[..matching_close(rest)?],
+        Some(quote @ ('"' | '\'')) => {
+            let end = rest[1..]
+                .char_indices()
+                .scan(false, |escaped, (index, c)| {
+                    let closes = c == quote && !*escaped;
+                    *escaped = c == '\\' && !*escaped;
+                    Some((index, closes))
+                })
+                .find(|(_, closes)| *closes)?
+                .0;
+
+            &rest[..end + 2]
+        }
+        // A colon means the value is a block mapping starting with the key in
+        // front of the colon
+        Some(':') => {
+            let line_start = input[..offset].rfind('\n').map_or(0, |index| index + 1);
+            let key_start = input[line_start..offset]
+                .char_indices()
+                .rev()
+                .find(|(_, c)| c.is_whitespace() || *c == '-')
+                .map_or(line_start, |(index, c)| line_start + index + c.len_utf8());
+            let line_end = rest.find('\n').map_or(input.len(), |index| offset + index);
+
+            &input[key_start..line_end]
+        }
+        Some(c) if !c.is_whitespace() && !matches!(c, ',' | ']' | '}') => {
+            let end = rest.find(['\n', ',', ']', '}']).unwrap_or(rest.len());
+
+            &rest[..end]
+        }
+        // The location is right after the value
+        _ => {
+            let before = input[..offset].trim_end();
+            let start = match before.chars().last()? {
+                '"' | '\'' => before[..before.len() - 1].rfind(['"', '\''])?,
+                '}' | ']' => matching_open(before)?,
+                _ => before
+                    .char_indices()
+                    .rev()
+                    .find(|(_, c)| c.is_whitespace() || matches!(c, ':' | ',' | '[' | '{'))
+                    .map_or(0, |(index, c)| index + c.len_utf8()),
+            };
+
+            &before[start..]
+        }
+    };
+
+    let value = value.trim();
+    if value.is_empty() {
+        None
+    } else {
+        Some(value.to_string())
+    }
+}
+
+/// Innermost bracket, brace or string that is not closed at the end of the
+/// input together with the line it was opened on.
+fn unclosed(input: &str) -> Option<(char, usize)> {
+    let mut stack = Vec::new();
+    let mut string_start = None;
+    let mut escaped = false;
+    let mut line = 1;
+
+    for c in input.chars() {
+        match (string_start.is_some(), escaped, c) {
+            (_, _, '\n') => {
+                line += 1;
+                escaped = false;
+            }
+            (true, true, _) => escaped = false,
+            (true, false, '\\') => escaped = true,
+            (true, false, '"') => string_start = None,
+            (false, _, '"') => string_start = Some(line),
+            (false, _, '{' | '[') => stack.push((c, line)),
+            (false, _, '}' | ']') => {
+                stack.pop();
+            }
+            _ => {}
+        }
+    }
+
+    string_start.map(|line| ('"', line)).or_else(|| stack.pop())
+}
+
+/// If the location is right after a closing bracket return the byte offsets of
+/// the matching opening bracket and the closing bracket.
+fn json_sequence(input: &str, offset: usize) -> Option<(usize, usize)> {
+    let close = input
+        .get(..offset)?
+        .trim_end()
+        .char_indices()
+        .last()
+        .filter(|(_, c)| *c == ']')?
+        .0;
+
+    let mut stack = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (index, c) in input[..close].char_indices() {
+        match (in_string, escaped, c) {
+            (true, true, _) => escaped = false,
+            (true, false, '\\') => escaped = true,
+            (_, false, '"') => in_string = !in_string,
+            (false, _, '[') => stack.push(index),
+            (false, _, ']') => {
+                stack.pop();
+            }
+            _ => {}
+        }
+    }
+
+    Some((stack.pop()?, close))
+}
+
+/// If the location is at the start of a YAML sequence return the byte offsets
+/// of the start and the last character of the sequence. Flow sequences end at
+/// the matching bracket, block sequences with the last line that is indented
+/// deeper than the first dash or continues it with another dash.
+fn yaml_sequence(input: &str, offset: usize) -> Option<(usize, usize)> {
+    let rest = input.get(offset..)?;
+
+    match rest.chars().next()? {
+        '[' => Some((offset, offset + matching_close(rest)? - 1)),
+        '-' => {
+            let line_start = input[..offset].rfind('\n').map_or(0, |index| index + 1);
+            let dash_column = input[line_start..offset].chars().count();
+            let first_line_end = rest.find('\n').map_or(input.len(), |index| offset + index);
+            let last_character = |text: &str| {
+                text.trim_end()
+                    .char_indices()
+                    .last()
+                    .map_or(0, |(index, _)| index)
+            };
+
+            let mut end = offset + last_character(&input[offset..first_line_end]);
+            let mut position = first_line_end + 1;
+
+            while let Some(text) = input.get(position..) {
+                let text = text.split('\n').next().unwrap_or_default();
+                let content = text.trim_start();
+                let indentation = text.len() - content.len();
+
+                if !content.is_empty() {
+                    let belongs = indentation > dash_column
+                        || (indentation == dash_column && content.starts_with('-'));
+                    if !belongs {
+                        break;
+                    }
+
+                    end = position + last_character(text);
+                }
+
+                position += text.len() + 1;
+            }
+
+            Some((offset, end))
+        }
+        _ => None,
+    }
+}
+
+/// Byte offset after the bracket closing the bracket the text starts with.
+fn matching_close(text: &str) -> Option<usize> {
+    let mut depth = 0_usize;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (index, c) in text.char_indices() {
+        match (in_string, escaped, c) {
+            (true, true, _) => escaped = false,
+            (true, false, '\\') => escaped = true,
+            (_, false, '"') => in_string = !in_string,
+            (false, _, '{' | '[') => depth += 1,
+            (false, _, '}' | ']') => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    return Some(index + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Byte offset of the bracket opening the bracket the text ends with.
+fn matching_open(text: &str) -> Option<usize> {
+    let mut depth = 0_usize;
+
+    for (index, c) in text.char_indices().rev() {
+        match c {
+            '}' | ']' => depth += 1,
+            '{' | '[' => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    return Some(index);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+/// Find the column of the last key in front of the column. A key is either a
+/// quoted string or a plain word that is followed by a colon which itself is
+/// followed by whitespace or the start of a value.
+fn key_before(text: &str, column: usize) -> Option<usize> {
+    let chars = text.chars().take(column).collect::<Vec<_>>();
+
+    let mut key = None;
+    let mut quote = None;
+    let mut quote_start = 0;
+    let mut last_quoted = None;
+
+    for (index, c) in chars.iter().enumerate() {
+        match (quote, c) {
+            (Some(q), c) if q == *c => {
+                quote = None;
+                last_quoted = Some((quote_start, index));
+            }
+            (None, '"' | '\'') => {
+                quote = Some(*c);
+                quote_start = index;
+            }
+            (None, ':') => {
+                let next = text.chars().nth(index + 1);
+                if !matches!(next, None | Some(' ' | '\t' | '{' | '[' | '"')) {
+                    continue;
+                }
+
+                // Quoted key directly in front of the colon
+                let before = chars[..index].iter().rposition(|c| !c.is_whitespace());
+                match (before, last_quoted) {
+                    (Some(before), Some((start, end))) if before == end => key = Some(start),
+                    (Some(before), _) => {
+                        let start = chars[..=before]
+                            .iter()
+                            .rposition(|c| c.is_whitespace() || matches!(c, '{' | ',' | '[' | '-'))
+                            .map_or(0, |position| position + 1);
+
+                        key = Some(start);
+                    }
+                    (None, _) => {}
+                }
+            }
+            _ => {}
+        }
+    }
+
+    key
+}
+
+/// Returns the text between the given prefix and the next backtick.
+pub(crate) fn quoted_after<'a>(message: &'a str, prefix: &str) -> Option<&'a str> {
+    let start = message.find(prefix)? + prefix.len();
+    let length = message[start..].find('`')?;
+
+    Some(&message[start..start + length])
+}
+
+/// Message without the ` at line N column M` suffix `serde_json`,
+/// `serde_yaml` and `toml` append to it.
+pub(crate) fn strip_position_suffix(message: &str) -> &str {
+    let Some(index) = message.rfind(" at line ") else {
+        return message;
+    };
+
+    let mut parts = message[index + " at line ".len()..].split(' ');
+    let is_position = matches!(
+        (parts.next(), parts.next(), parts.next(), parts.next()),
+        (Some(line), Some("column"), Some(column), None)
+            if [line, column].iter().all(|number| {
+                !number.is_empty() && number.chars().all(|c| c.is_ascii_digit())
+            })
+    );
+
+    if is_position {
+        &message[..index]
+    } else {
+        message
+    }
+}
+
+/// Number of single character insertions, deletions, substitutions and
+/// transpositions of adjacent characters needed to turn one text into the
+/// other.
+pub(crate) fn edit_distance(from: &str, to: &str) -> usize {
+    let from = from.chars().collect::<Vec<_>>();
+    let to = to.chars().collect::<Vec<_>>();
+
+    let mut distances = vec![vec![0; to.len() + 1]; from.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j;
+    }
+
+    for i in 1..=from.len() {
+        for j in 1..=to.len() {
+            let substitution = distances[i - 1][j - 1] + usize::from(from[i - 1] != to[j - 1]);
+            let deletion = distances[i - 1][j] + 1;
+            let insertion = distances[i][j - 1] + 1;
+
+            let mut distance = substitution.min(deletion).min(insertion);
+            if i > 1 && j > 1 && from[i - 1] == to[j - 2] && from[i - 2] == to[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+
+            distances[i][j] = distance;
+        }
+    }
+
+    distances[from.len()][to.len()]
+}
+
+/// Maximum amount of suggestions returned by [`closest`].
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Candidates that are close enough to the text to likely be what was meant,
+/// closest first.
+pub(crate) fn closest<'a>(text: &str, candidates: impl Iterator<Item = &'a str>) -> Vec<&'a str> {
+    let threshold = (text.chars().count() / 3).max(1);
+
+    let mut close = candidates
+        .map(|candidate| (edit_distance(text, candidate), candidate))
+        .filter(|(distance, _)| *distance <= threshold)
+        .collect::<Vec<_>>();
+    close.sort_by_key(|(distance, _)| *distance);
+
+    close
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+/// Byte offset of the given line (starting at 1) and column (in characters
+/// starting at 0).
+pub(crate) fn byte_offset(input: &str, line: usize, column: usize) -> Option<usize> {
+    let line_start = if line <= 1 {
+        0
+    } else {
+        input.match_indices('\n').nth(line - 2)?.0 + 1
+    };
+
+    let text = input[line_start..].split('\n').next().unwrap_or_default();
+    let column_offset = text
+        .char_indices()
+        .nth(column)
+        .map_or(text.len(), |(offset, _)| offset);
+
+    Some(line_start + column_offset)
+}
+
+/// If the location is right after or at a closing brace return the line and
+/// column of the matching opening brace.
+fn json_object_start(input: &str, line: usize, column: usize) -> Option<(usize, usize)> {
+    let offset = byte_offset(input, line, column)?;
+
+    // serde_json points right after the closing brace, walk back over it and
+    // any whitespace in front of the location.
+    let end = input[offset..]
+        .chars()
+        .next()
+        .map_or(input.len(), |c| offset + c.len_utf8());
+
+    let close = input[..end]
+        .char_indices()
+        .rev()
+        .find(|(_, c)| !c.is_whitespace())
+        .filter(|(_, c)| *c == '}')
+        .map(|(index, _)| index)?;
+
+    let mut stack = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (index, c) in input[..close].char_indices() {
+        match (in_string, escaped, c) {
+            (true, true, _) => escaped = false,
+            (true, false, '\\') => escaped = true,
+            (true, false, '"') | (false, _, '"') => in_string = !in_string,
+            (false, _, '{') => stack.push(index),
+            (false, _, '}') => {
+                stack.pop();
+            }
+            _ => {}
+        }
+    }
+
+    let open = stack.pop()?;
+    let location = crate::Location::from_byte_offset(input, open)?;
+
+    Some((location.line, location.column))
+}
+
+/// Walk up from the line to the key of the mapping the line belongs to.
+fn yaml_mapping_start(input: &str, line: usize) -> Option<(usize, usize)> {
+    let lines = input.lines().collect::<Vec<_>>();
+    let indentation = |text: &str| text.chars().take_while(|c| *c == ' ').count();
+
+    let error_indentation = indentation(lines.get(line.checked_sub(1)?)?);
+
+    let parent = lines[..line - 1]
+        .iter()
+        .enumerate()
+        .rev()
+        .filter(|(_, text)| !text.trim().is_empty())
+        .find(|(_, text)| indentation(text) < error_indentation)
+        .map(|(index, text)| (index + 1, indentation(text)));
+
+    // Mappings at the top level have no parent so the object starts with the
+    // document
+    match parent {
+        None if error_indentation == 0 => first_content_line(input),
+        parent => parent,
+    }
+}
+
+/// Line and column of the first line that is not empty.
+fn first_content_line(input: &str) -> Option<(usize, usize)> {
+    input
+        .lines()
+        .enumerate()
+        .find(|(_, text)| !text.trim().is_empty())
+        .map(|(index, text)| {
+            (
+                index + 1,
+                text.chars().take_while(|c| c.is_whitespace()).count(),
+            )
+        })
+}
@@ -0,0 +1,51 @@
+use std::fmt;
+
+/// Restores a global default to the value it had before it was changed when
+/// dropped. Returned by the `push_default_*` functions like
+/// [`push_default_context_lines`](crate::push_default_context_lines). Nested
+/// guards restore the defaults in the reverse order they were created in as
+/// long as they are dropped in that order, which is the case for guards that
+/// are bound to variables in nested scopes. The default is also restored when
+/// a panic unwinds through the scope of the guard.
+///
+/// ```rust
+/// use format_serde_error::{
+///     get_default_context_lines,
+///     push_default_context_lines,
+/// };
+///
+/// let before = get_default_context_lines();
+///
+/// {
+///     let _guard = push_default_context_lines(0);
+///     assert_eq!(get_default_context_lines(), 0);
+/// }
+///
+/// assert_eq!(get_default_context_lines(), before);
+/// ```
+#[must_use = "the previous default is restored as soon as the guard is dropped"]
+pub struct DefaultsGuard {
+    restore: Option<Box<dyn FnOnce() + Send>>,
+}
+
+impl DefaultsGuard {
+    pub(crate) fn new(restore: impl FnOnce() + Send + 'static) -> Self {
+        Self {
+            restore: Some(Box::new(restore)),
+        }
+    }
+}
+
+impl Drop for DefaultsGuard {
+    fn drop(&mut self) {
+        if let Some(restore) = self.restore.take() {
+            restore();
+        }
+    }
+}
+
+impl fmt::Debug for DefaultsGuard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DefaultsGuard").finish_non_exhaustive()
+    }
+}
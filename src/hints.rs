@@ -0,0 +1,86 @@
+//! Registry of hints that are shown below errors with matching messages.
+//!
+//! The registry is shared by the whole process. Every registered hint whose
+//! pattern is part of the original message of an error is shown as a `help:`
+//! note when the error is rendered:
+//!
+//! ```rust
+//! use format_serde_error::{
+//!     hints,
+//!     SerdeError,
+//! };
+//!
+//! hints::scoped(|| {
+//!     hints::register("expected u32", "timeouts are given in seconds like `30`");
+//!
+//!     let err = SerdeError::new(
+//!         "timeout: soon".to_string(),
+//!         ("invalid type: string \"soon\", expected u32".into(), Some(1), Some(9)),
+//!     );
+//!
+//!     assert!(err
+//!         .to_string()
+//!         .contains("help: timeouts are given in seconds like `30`"));
+//! });
+//! ```
+
+use std::sync::{
+    PoisonError,
+    RwLock,
+};
+
+use crate::DefaultsGuard;
+
+/// Hint shown for messages that contain the pattern.
+#[derive(Debug, Clone)]
+struct Hint {
+    pattern: String,
+    hint: String,
+}
+
+static HINTS: RwLock<Vec<Hint>> = RwLock::new(Vec::new());
+
+/// Show the hint below every error whose message contains the pattern. Hints
+/// are shown in the order they were registered and more than one hint can
+/// match the same message.
+pub fn register(pattern: &str, hint: &str) {
+    HINTS
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .push(Hint {
+            pattern: pattern.to_string(),
+            hint: hint.to_string(),
+        });
+}
+
+/// Remove all registered hints.
+pub fn clear() {
+    HINTS
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clear();
+}
+
+/// Run the closure and restore the hints that were registered before
+/// afterwards, also if the closure panics. Hints registered in the closure are
+/// only shown while it runs. The registry is still shared with other threads
+/// in the meantime so tests that run in parallel should use distinct patterns.
+pub fn scoped<T>(f: impl FnOnce() -> T) -> T {
+    let saved = HINTS.read().unwrap_or_else(PoisonError::into_inner).clone();
+    let _restore = DefaultsGuard::new(move || {
+        *HINTS.write().unwrap_or_else(PoisonError::into_inner) = saved;
+    });
+
+    f()
+}
+
+/// Hints of all patterns that are part of the message.
+pub(crate) fn matching(message: &str) -> Vec<String> {
+    HINTS
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .filter(|hint| message.contains(&hint.pattern))
+        .map(|hint| hint.hint.clone())
+        .collect()
+}
@@ -0,0 +1,65 @@
+//! Terminal hyperlinks that open the file of an error in an editor.
+
+use std::path::Path;
+
+/// Format of the hyperlink target used for the line number of the error. See
+/// [`SerdeError::set_hyperlink_scheme`](crate::SerdeError::set_hyperlink_scheme).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum HyperlinkScheme {
+    /// Plain `file://` link that opens the file with the default application.
+    #[default]
+    File,
+
+    /// `vscode://file/` link that opens the file at the line and column in
+    /// Visual Studio Code.
+    VsCode,
+
+    /// `idea://open` link that opens the file at the line and column in
+    /// `JetBrains` IDEs like `IntelliJ IDEA`.
+    Idea,
+}
+
+impl HyperlinkScheme {
+    /// Build the link target for the position in the file. Line and column
+    /// start at 1.
+    pub(crate) fn uri(self, path: &Path, line: usize, column: usize) -> String {
+        // Editors need absolute paths to find the file
+        let path = if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            std::env::current_dir().map_or_else(|_| path.to_path_buf(), |dir| dir.join(path))
+        };
+
+        let path = encode(&path.to_string_lossy());
+        let path = path.strip_prefix('/').unwrap_or(&path);
+
+        match self {
+            Self::File => format!("file:///{path}"),
+            Self::VsCode => format!("vscode://file/{path}:{line}:{column}"),
+            Self::Idea => format!("idea://open?file=/{path}&line={line}&column={column}"),
+        }
+    }
+}
+
+/// Wrap the text in an OSC 8 escape sequence so terminals show it as a link
+/// to the target.
+#[cfg_attr(not(feature = "colored"), allow(dead_code))]
+pub(crate) fn wrap(text: &str, target: &str) -> String {
+    format!("\x1b]8;;{target}\x1b\\{text}\x1b]8;;\x1b\\")
+}
+
+/// Percent encode everything in the path that is not allowed in an URI.
+fn encode(path: &str) -> String {
+    path.bytes()
+        .map(|byte| match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                char::from(byte).to_string()
+            }
+            // Windows paths use backslashes
+            b'\\' => "/".to_string(),
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
@@ -0,0 +1,107 @@
+use std::borrow::Cow;
+
+/// Fixed strings the crate adds to the rendered output. Allows translating the
+/// output together with the messages of the application. The messages of the
+/// errors themselves and the notes explaining them are not part of the labels.
+/// The [`Default`] implementation uses the English labels.
+///
+/// ```rust
+/// use format_serde_error::{
+///     Labels,
+///     SerdeError,
+/// };
+///
+/// let labels = Labels {
+///     help: "Hilfe".into(),
+///     note: "Hinweis".into(),
+///     ..Labels::default()
+/// };
+///
+/// let mut err = SerdeError::new("a: b".to_string(), ("error".into(), Some(1), Some(3)));
+/// err.set_labels(labels);
+/// # let _ = err;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct Labels {
+    /// Prefix of notes suggesting a fix, `help` by default.
+    pub help: Cow<'static, str>,
+
+    /// Prefix of notes explaining the error, `note` by default.
+    pub note: Cow<'static, str>,
+
+    /// Name of [`Severity::Error`](crate::Severity::Error), `error` by
+    /// default.
+    pub error: Cow<'static, str>,
+
+    /// Name of [`Severity::Warning`](crate::Severity::Warning), `warning` by
+    /// default.
+    pub warning: Cow<'static, str>,
+
+    /// Words in front of the line of the error in the statistics header,
+    /// `at line` by default.
+    pub at_line: Cow<'static, str>,
+
+    /// Word between the line of the error and the total amount of lines in the
+    /// statistics header, `of` by default.
+    pub of: Cow<'static, str>,
+
+    /// Word in front of column numbers, `column` by default.
+    pub column: Cow<'static, str>,
+
+    /// Word in front of byte offsets, `byte` by default.
+    pub byte: Cow<'static, str>,
+
+    /// Word behind the size of the input in the statistics header, `document`
+    /// by default.
+    pub document: Cow<'static, str>,
+
+    /// Word behind the total amount of lines next to the caret, `lines` by
+    /// default.
+    pub lines: Cow<'static, str>,
+
+    /// Prefix of the original message, `raw message` by default.
+    pub raw_message: Cow<'static, str>,
+
+    /// Words in front of the location the error was created at,
+    /// `constructed at` by default.
+    pub constructed_at: Cow<'static, str>,
+
+    /// Marker for lines that are left out, `...` by default.
+    pub lines_omitted: Cow<'static, str>,
+
+    /// Text in front of the message of errors about the input ending early,
+    /// `expected more` by default. See
+    /// [`SerdeError::set_eof_marker`](crate::SerdeError::set_eof_marker).
+    pub expected_more: Cow<'static, str>,
+}
+
+impl Labels {
+    /// The English labels.
+    #[must_use]
+    pub const fn english() -> Self {
+        Self {
+            help: Cow::Borrowed("help"),
+            note: Cow::Borrowed("note"),
+            error: Cow::Borrowed("error"),
+            warning: Cow::Borrowed("warning"),
+            at_line: Cow::Borrowed("at line"),
+            of: Cow::Borrowed("of"),
+            column: Cow::Borrowed("column"),
+            byte: Cow::Borrowed("byte"),
+            document: Cow::Borrowed("document"),
+            lines: Cow::Borrowed("lines"),
+            raw_message: Cow::Borrowed("raw message"),
+            constructed_at: Cow::Borrowed("constructed at"),
+            lines_omitted: Cow::Borrowed("..."),
+            expected_more: Cow::Borrowed("expected more"),
+        }
+    }
+}
+
+impl Default for Labels {
+    fn default() -> Self {
+        Self::english()
+    }
+}
@@ -75,6 +75,12 @@
 //! also be changed for a single error using
 //! [`SerdeError::set_context_characters`].
 //!
+//! # Multiple Errors
+//!
+//! When validating multiple files the errors can be collected in a [`Report`]
+//! which prints the errors grouped by file followed by a summary. The errors
+//! are also available as a list of [`Diagnostic`]s for further processing.
+//!
 //! # Crate Features
 //! ## `serde_yaml`
 //! *Enabled by default:* yes
@@ -98,7 +104,8 @@
 //! Enables support for color output to a terminal using the [`colored`] crate.
 //! Also enables the functions [`always_color`], [`never_color`],
 //! [`set_coloring_mode`], [`use_environment`] and the enum [`ColoringMode`]
-//! which allow changing the behavior of [`colored`].
+//! which allow changing the behavior of [`colored`]. In the browser
+//! (`wasm32-unknown-unknown`) the output is never colored.
 //!
 //! ## `graphemes_support`
 //! *Enabled by default:* yes
@@ -108,39 +115,186 @@
 //! [`std::str::Chars`]. This can mean that certain error messages won't get
 //! formatted properly when a string contains unicode grapheme clusters. You can
 //! check the test `test::context_long_line::graphemes_string` for an example.
+//!
+//! ## `schemars`
+//! *Enabled by default:* no
+//!
+//! Enables [`SerdeError::with_schema`] which uses a schema generated by
+//! [`schemars`] to suggest valid keys and variants for unknown field and
+//! unknown variant errors.
+//!
+//! ## `unicode_width`
+//! *Enabled by default:* yes
+//!
+//! Measures wrapped messages and the context of long lines shortened with
+//! [`ContextUnit::Columns`] in display columns using the [`unicode_width`]
+//! crate so wide characters count double. Without this feature every character
+//! counts as one column.
+//!
+//! ## `terminal_size`
+//! *Enabled by default:* no
+//!
+//! Detects the width of the terminal with the [`terminal_size`] crate for
+//! [`MaxMessageWidth::Terminal`]. Without this feature only the `COLUMNS`
+//! environment variable is used.
+//!
+//! ## `log`
+//! *Enabled by default:* no
+//!
+//! Enables [`SerdeError::log`] and [`SerdeError::log_with_format`] which emit
+//! the error through the [`log`] crate. By default the error is emitted as a
+//! single line as multi-line output is awkward in most log backends.
+//!
+//! ## `testing`
+//! *Enabled by default:* no
+//!
+//! Enables [`SerdeError::render_normalized`] which renders the error without
+//! colors and trailing whitespace for comparisons in tests.
+//!
+//! ## `backtrace`
+//! *Enabled by default:* no
+//!
+//! Captures a backtrace when an error is created if backtraces are enabled
+//! with `RUST_BACKTRACE`. It is available from [`SerdeError::backtrace`] and
+//! can be shown with [`SerdeError::set_show_backtrace`].
+//!
+//! ## `csv`
+//! *Enabled by default:* no
+//!
+//! Enables support for errors emitted by the `csv` crate. Extends the
+//! [`ErrorTypes`] enum by [`ErrorTypes::Csv`]. The record and field of the
+//! error are mapped to the line and column of the field in the input.
+//!
+//! ## `serde`
+//! *Enabled by default:* no
+//!
+//! Implements `Serialize` and `Deserialize` for [`RenderSettings`] and the
+//! types it is made of so the rendering can be configured in the config file
+//! of an application. Missing fields use their default and the variants of
+//! enums are written in `snake_case` like `long_line_mode = "wrap"`.
 
 #![deny(missing_docs)]
 #![forbid(unsafe_code)]
 #![warn(clippy::pedantic)]
 #![warn(clippy::unwrap_used)]
 #![warn(rust_2018_idioms, unused_lifetimes, missing_debug_implementations)]
+// The crate docs keep their list items without continuation indent
+#![allow(clippy::doc_lazy_continuation)]
 
 #[cfg(feature = "colored")]
 use colored::Colorize;
+#[cfg(feature = "colored")]
+use paint::Paint;
 
+use enhance::Annotated;
+use source::Source;
+use stats::InputStats;
 use std::{
+    borrow::Cow,
+    cell::RefCell,
+    collections::HashMap,
     fmt,
-    sync::atomic::{
-        AtomicBool,
-        AtomicUsize,
-        Ordering,
+    io::{
+        self,
+        Write,
+    },
+    path::{
+        Path,
+        PathBuf,
+    },
+    sync::{
+        atomic::{
+            AtomicBool,
+            AtomicUsize,
+            Ordering,
+        },
+        Arc,
+        Mutex,
+        OnceLock,
+        PoisonError,
+        RwLock,
     },
 };
+use trace::{
+    RenderTrace,
+    Tracer,
+};
 
+mod anchor;
+mod ansi;
+mod checked;
 #[cfg(feature = "colored")]
 mod control;
+#[cfg(any(feature = "serde_yaml", feature = "serde_json"))]
+mod deserializer;
+#[cfg(feature = "serde_json")]
+mod duplicate_keys;
+mod enhance;
+mod guard;
+pub mod hints;
+mod hyperlink;
+mod labels;
+pub mod locate;
+#[cfg(feature = "log")]
+mod logging;
+#[cfg(feature = "colored")]
+mod paint;
+mod reflow;
+mod report;
+#[cfg(feature = "schemars")]
+mod schema;
+mod settings;
+mod source;
+mod stats;
+mod trace;
+mod typed;
 
 #[cfg(test)]
 mod test;
 
+pub use checked::FormatError;
 #[cfg(feature = "colored")]
 pub use control::{
     always_color,
     never_color,
+    push_coloring_mode,
     set_coloring_mode,
     use_environment,
     ColoringMode,
 };
+#[cfg(any(feature = "serde_yaml", feature = "serde_json"))]
+pub use deserializer::Deserializer;
+#[cfg(feature = "serde_json")]
+pub use duplicate_keys::check_duplicate_keys;
+pub use guard::DefaultsGuard;
+pub use hyperlink::HyperlinkScheme;
+pub use labels::Labels;
+pub use locate::Location;
+#[cfg(feature = "log")]
+pub use logging::LogFormat;
+pub use report::{
+    Diagnostic,
+    Report,
+    ReportBuilder,
+    SortKey,
+};
+pub use settings::{
+    AnnotationStyle,
+    ContextMode,
+    ContextUnit,
+    IndentWs,
+    LineNumbering,
+    LongLineMode,
+    MarkerStyle,
+    MaxMessageWidth,
+    RenderSettings,
+    RenderStyle,
+    TextDirection,
+};
+pub use typed::{
+    ErrorLocation,
+    SerdeErrorOf,
+};
 
 /// If the output should be contextualized or not.
 pub const CONTEXTUALIZE_DEFAULT: bool = true;
@@ -149,8 +303,19 @@ static CONTEXTUALIZE: AtomicBool = AtomicBool::new(CONTEXTUALIZE_DEFAULT);
 /// Set the default if contextualization should be enabled or not. Default value
 /// is [`CONTEXTUALIZE_DEFAULT`]. If you want to change the amount of context
 /// shown for a single error use [`SerdeError::set_contextualize`] instead.
-pub fn set_default_contextualize(should_contextualize: bool) {
-    CONTEXTUALIZE.store(should_contextualize, Ordering::Relaxed);
+/// Returns the previous default.
+pub fn set_default_contextualize(should_contextualize: bool) -> bool {
+    CONTEXTUALIZE.swap(should_contextualize, Ordering::Relaxed)
+}
+
+/// Set the default if contextualization should be enabled or not until the
+/// returned guard is dropped. See [`set_default_contextualize`].
+pub fn push_default_contextualize(should_contextualize: bool) -> DefaultsGuard {
+    let previous = set_default_contextualize(should_contextualize);
+
+    DefaultsGuard::new(move || {
+        set_default_contextualize(previous);
+    })
 }
 
 /// Get the current default if contextualization should be enabled or not.
@@ -165,9 +330,20 @@ static CONTEXT_LINES: AtomicUsize = AtomicUsize::new(CONTEXT_LINES_DEFAULT);
 
 /// Set the default amount of context lines shown. Default amount of context is
 /// [`CONTEXT_LINES_DEFAULT`]. If you want to change the amount of context shown
-/// for a single error use [`SerdeError::set_context_lines`] instead.
-pub fn set_default_context_lines(amount_of_context: usize) {
-    CONTEXT_LINES.store(amount_of_context, Ordering::Relaxed);
+/// for a single error use [`SerdeError::set_context_lines`] instead. Returns
+/// the previous default.
+pub fn set_default_context_lines(amount_of_context: usize) -> usize {
+    CONTEXT_LINES.swap(amount_of_context, Ordering::Relaxed)
+}
+
+/// Set the default amount of context lines shown until the returned guard is
+/// dropped. See [`set_default_context_lines`].
+pub fn push_default_context_lines(amount_of_context: usize) -> DefaultsGuard {
+    let previous = set_default_context_lines(amount_of_context);
+
+    DefaultsGuard::new(move || {
+        set_default_context_lines(previous);
+    })
 }
 
 /// Get the current default amount of context lines shown. Default amount of
@@ -184,9 +360,19 @@ static CONTEXT_CHARACTERS: AtomicUsize = AtomicUsize::new(CONTEXT_CHARACTERS_DEF
 /// Set the default amount of context characters shown. Default amount of
 /// context is [`CONTEXT_CHARACTERS_DEFAULT`]. If you want to change the amount
 /// context shown for a single error use [`SerdeError::set_context_characters`]
-/// instead.
-pub fn set_default_context_characters(amount_of_context: usize) {
-    CONTEXT_CHARACTERS.store(amount_of_context, Ordering::Relaxed);
+/// instead. Returns the previous default.
+pub fn set_default_context_characters(amount_of_context: usize) -> usize {
+    CONTEXT_CHARACTERS.swap(amount_of_context, Ordering::Relaxed)
+}
+
+/// Set the default amount of context characters shown until the returned
+/// guard is dropped. See [`set_default_context_characters`].
+pub fn push_default_context_characters(amount_of_context: usize) -> DefaultsGuard {
+    let previous = set_default_context_characters(amount_of_context);
+
+    DefaultsGuard::new(move || {
+        set_default_context_characters(previous);
+    })
 }
 
 /// Get the current default amount of context characters shown. Default amount
@@ -195,23 +381,381 @@ pub fn get_default_context_characters() -> usize {
     CONTEXT_CHARACTERS.load(Ordering::Relaxed)
 }
 
+/// Amount of spaces a tab is replaced with in the output.
+pub const TAB_WIDTH_DEFAULT: usize = 1;
+
+static LABELS: RwLock<Labels> = RwLock::new(Labels::english());
+
+/// Set the default labels the crate adds to the output. Default are the
+/// English labels of [`Labels::english`]. If you want to change the labels for
+/// a single error use [`SerdeError::set_labels`] instead.
+pub fn set_default_labels(labels: Labels) {
+    *LABELS.write().unwrap_or_else(PoisonError::into_inner) = labels;
+}
+
+/// Get the current default labels the crate adds to the output. Default are
+/// the English labels of [`Labels::english`].
+pub fn get_default_labels() -> Labels {
+    LABELS
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+static MESSAGE_REWRITER: RwLock<Option<MessageRewriter>> = RwLock::new(None);
+
+/// Set the default message rewriter of new errors. See
+/// [`SerdeError::set_message_rewriter`]. If you want to change the rewriter
+/// for a single error use [`SerdeError::set_message_rewriter`] instead.
+pub fn set_default_message_rewriter(
+    message_rewriter: impl Fn(&str) -> Option<String> + Send + Sync + 'static,
+) {
+    *MESSAGE_REWRITER
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) =
+        Some(MessageRewriter(Arc::new(message_rewriter)));
+}
+
+/// Remove the default message rewriter so new errors show the original
+/// messages again.
+pub fn remove_default_message_rewriter() {
+    *MESSAGE_REWRITER
+        .write()
+        .unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+/// Column (counted in characters) of the start of the grapheme cluster the
+/// given column of the text is part of.
+#[cfg(feature = "graphemes_support")]
+fn grapheme_start(text: &str, column: usize) -> usize {
+    use unicode_segmentation::UnicodeSegmentation;
+
+    let mut start = 0;
+
+    for grapheme in text.graphemes(true) {
+        let end = start + grapheme.chars().count();
+
+        if column < end {
+            return start;
+        }
+
+        start = end;
+    }
+
+    column
+}
+
+/// Text with a space in front if it starts with a combining mark that has no
+/// character to attach to, for example in files that were cut apart. The mark
+/// would be drawn onto the separator in front of it otherwise. Together the
+/// space and the mark take up the single column the mark is counted as so
+/// the caret stays aligned.
+fn detach_leading_mark(text: &str) -> Cow<'_, str> {
+    match text.chars().next() {
+        Some(c) if is_combining_mark(c) => Cow::Owned(format!(" {text}")),
+        _ => Cow::Borrowed(text),
+    }
+}
+
+/// If the character extends the grapheme cluster in front of it instead of
+/// starting its own.
+#[cfg(feature = "graphemes_support")]
+fn is_combining_mark(c: char) -> bool {
+    use unicode_segmentation::UnicodeSegmentation;
+
+    let mut buffer = [0; 4];
+    let attached = format!(" {}", c.encode_utf8(&mut buffer));
+
+    attached.graphemes(true).count() == 1
+}
+
+/// If the character is in one of the blocks of combining diacritical marks.
+#[cfg(not(feature = "graphemes_support"))]
+fn is_combining_mark(c: char) -> bool {
+    matches!(
+        c,
+        '\u{300}'..='\u{36f}'
+            | '\u{1ab0}'..='\u{1aff}'
+            | '\u{1dc0}'..='\u{1dff}'
+            | '\u{20d0}'..='\u{20ff}'
+            | '\u{fe20}'..='\u{fe2f}'
+    )
+}
+
 /// Separator used between the line numbering and the lines.
 const SEPARATOR: &str = " | ";
 
+/// Separator between the gutter and the text as it is passed to the functions
+/// that print the lines. Blue and bold if colors are enabled.
+#[cfg(feature = "colored")]
+type Separator = paint::Painted;
+
+/// Separator between the gutter and the text as it is passed to the functions
+/// that print the lines.
+#[cfg(not(feature = "colored"))]
+type Separator = str;
+
+#[cfg(feature = "colored")]
+fn styled_separator(separator: &str) -> paint::Painted {
+    separator.blue().bold().paint()
+}
+
+#[cfg(not(feature = "colored"))]
+fn styled_separator(separator: &str) -> String {
+    separator.to_string()
+}
+
+/// Split the text into runs of whitespace markers shown with
+/// [`SerdeError::set_show_whitespace`] and runs of other text. The flag is
+/// true for runs of markers.
+#[cfg(feature = "colored")]
+fn whitespace_runs(text: &str) -> Vec<(&str, bool)> {
+    let mut runs = Vec::new();
+    let mut start = 0;
+    let mut marker = false;
+    let mut in_code_point = false;
+
+    for (index, c) in text.char_indices() {
+        let is_marker = match c {
+            '⟨' => {
+                in_code_point = true;
+                true
+            }
+            '⟩' => std::mem::replace(&mut in_code_point, false),
+            '·' | '→' => true,
+            _ => in_code_point,
+        };
+
+        if is_marker != marker && index > start {
+            runs.push((&text[start..index], marker));
+            start = index;
+        }
+
+        marker = is_marker;
+    }
+
+    if start < text.len() {
+        runs.push((&text[start..], marker));
+    }
+
+    runs
+}
+
 /// Ellipse used to indicated if a long line has been contextualized.
 const ELLIPSE: &str = "...";
 
+/// Shown between the error line and a message behind it. See
+/// [`SerdeError::set_annotation_style`].
+const INLINE_MARKER: &str = "  <-- ";
+
+/// Least amount of columns left for a message behind the error line before it
+/// is shown below the error line instead of being shortened.
+const INLINE_MIN_MESSAGE_WIDTH: usize = 20;
+
+/// Shown instead of spaces with [`SerdeError::set_show_whitespace`].
+const WHITESPACE_SPACE: &str = "·";
+
+/// Shown instead of tabs with [`SerdeError::set_show_whitespace`].
+const WHITESPACE_TAB: &str = "→";
+
+/// Shown instead of the caret behind the last line for errors about the input
+/// ending early. See [`SerdeError::set_eof_marker`].
+const EOF_MARKER: &str = "↵";
+
+/// Shown in the gutter in front of the continuation of a wrapped line.
+const CONTINUATION: &str = "↪";
+
+/// Layout of lines formatted by [`SerdeError::format_line_reflowed`].
+struct Wrapping {
+    /// Space for the text behind the gutter.
+    width: usize,
+
+    /// Gutter label of the rows after the first row of a line.
+    continuation: String,
+
+    /// If context lines are wrapped as well instead of being cut off.
+    context: bool,
+}
+
 /// Struct for formatting the error together with the source file to give a
 /// nicer output.
 #[derive(Debug)]
 pub struct SerdeError {
-    input: String,
+    input: Source,
     message: String,
     line: Option<usize>,
     column: Option<usize>,
-    contextualize: bool,
-    context_lines: usize,
-    context_characters: usize,
+    settings: RenderSettings,
+    notes: Vec<enhance::Note>,
+    labels: Vec<enhance::Label>,
+    span_start: Option<(usize, usize)>,
+    line_statuses: HashMap<usize, char>,
+    source_map: Option<SourceMap>,
+    message_rewriter: Option<MessageRewriter>,
+    line_number_formatter: Option<LineNumberFormatter>,
+    path: Option<PathBuf>,
+    category: Option<Category>,
+    language: Option<&'static str>,
+    constructed_at: &'static std::panic::Location<'static>,
+    #[cfg(feature = "backtrace")]
+    backtrace: std::backtrace::Backtrace,
+    kind: ErrorKind,
+    adapter: ErrorAdapter,
+    severity: Severity,
+    stats: OnceLock<InputStats>,
+    last_rendered_error_line: Mutex<Option<RenderedErrorLine>>,
+}
+
+/// Shown part of the error line, the column of the error in it and if the line
+/// was shortened before and after that part. See
+/// [`SerdeError::last_rendered_error_line`].
+type RenderedErrorLine = (String, usize, bool, bool);
+
+/// Category of an error. Currently only known for errors from `serde_json`.
+/// See [`SerdeError::category`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    /// Reading the input failed.
+    Io,
+    /// The input is not syntactically valid.
+    Syntax,
+    /// The input is syntactically valid but doesn't match the expected type.
+    Data,
+    /// The input ended before a value was complete.
+    Eof,
+}
+
+/// Kind of failure of an error. See [`SerdeError::kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+    /// Reading the input failed.
+    Io,
+    /// The input is not syntactically valid.
+    Syntax,
+    /// The input is syntactically valid but doesn't match the expected type.
+    Data,
+    /// The input ended before a value was complete.
+    Eof,
+    /// The kind could not be determined.
+    Unknown,
+}
+
+/// Format adapter that produced an error. See [`SerdeError::error_kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorAdapter {
+    /// The error came from `serde_json`.
+    Json,
+    /// The error came from `serde_yaml`.
+    Yaml,
+    /// The error came from `toml`.
+    Toml,
+    /// The error came from `csv`.
+    Csv,
+    /// The error was created from a custom error.
+    Custom,
+}
+
+impl From<Category> for ErrorKind {
+    fn from(category: Category) -> Self {
+        match category {
+            Category::Io => Self::Io,
+            Category::Syntax => Self::Syntax,
+            Category::Data => Self::Data,
+            Category::Eof => Self::Eof,
+        }
+    }
+}
+
+impl ErrorKind {
+    /// Best effort guess of the kind based on the message for errors that are
+    /// not classified by the library that produced them.
+    fn from_message(message: &str) -> Self {
+        const EOF: [&str; 3] = ["EOF while parsing", "end of stream", "unexpected end of"];
+        const DATA: [&str; 7] = [
+            "invalid type",
+            "invalid value",
+            "invalid length",
+            "unknown field",
+            "unknown variant",
+            "missing field",
+            "duplicate field",
+        ];
+        const SYNTAX: [&str; 4] = ["while parsing", "while scanning", "expected", "unexpected"];
+
+        let contains = |patterns: &[&str]| patterns.iter().any(|pattern| message.contains(pattern));
+
+        // Data errors also mention what was expected so they are checked before
+        // syntax errors
+        if contains(&EOF) {
+            Self::Eof
+        } else if contains(&DATA) {
+            Self::Data
+        } else if contains(&SYNTAX) {
+            Self::Syntax
+        } else {
+            Self::Unknown
+        }
+    }
+}
+
+/// Severity of an error. Used to order the errors in a [`Report`]. See
+/// [`SerdeError::set_severity`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Severity {
+    /// The input can not be used.
+    #[default]
+    Error,
+    /// The input can be used but is likely not what was intended.
+    Warning,
+}
+
+#[cfg(feature = "serde_json")]
+impl From<serde_json::error::Category> for Category {
+    fn from(category: serde_json::error::Category) -> Self {
+        match category {
+            serde_json::error::Category::Io => Self::Io,
+            serde_json::error::Category::Syntax => Self::Syntax,
+            serde_json::error::Category::Data => Self::Data,
+            serde_json::error::Category::Eof => Self::Eof,
+        }
+    }
+}
+
+/// Callback translating lines of the parsed input to lines of the file they
+/// originally came from. See [`SerdeError::set_source_map`].
+struct SourceMap(Box<dyn Fn(usize) -> Option<(PathBuf, usize)> + Send + Sync>);
+
+impl fmt::Debug for SourceMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SourceMap(..)")
+    }
+}
+
+/// Callback replacing the message of an error with a text for the user. See
+/// [`SerdeError::set_message_rewriter`].
+#[derive(Clone)]
+struct MessageRewriter(Arc<RewriteMessage>);
+
+type RewriteMessage = dyn Fn(&str) -> Option<String> + Send + Sync;
+
+impl fmt::Debug for MessageRewriter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("MessageRewriter(..)")
+    }
+}
+
+/// Callback formatting the line numbers in the gutter. See
+/// [`SerdeError::set_line_number_formatter`].
+#[derive(Clone)]
+struct LineNumberFormatter(Arc<FormatLineNumber>);
+
+type FormatLineNumber = dyn Fn(usize) -> String + Send + Sync;
+
+impl fmt::Debug for LineNumberFormatter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("LineNumberFormatter(..)")
+    }
 }
 
 /// Contains the error that will be used by [`SerdeError`] to format the output.
@@ -232,14 +776,21 @@ pub enum ErrorTypes {
     /// Contains [`toml::de::Error`].
     Toml(toml::de::Error),
 
-    /// Used for custom errors that don't come from serde_yaml or
-    /// serde_json.
+    #[cfg(feature = "csv")]
+    /// Contains [`csv::Error`]. The error points at the field it is about, see
+    /// [`locate::csv_field`].
+    Csv(csv::Error),
+
+    /// Used for custom errors that don't come from `serde_yaml` or
+    /// `serde_json`.
     Custom {
         /// Error message that should be displayed.
         error: Box<dyn std::error::Error>,
-        /// Line the error occurred at.
+        /// Line the error occurred at. Only the message is shown without a
+        /// line.
         line: Option<usize>,
-        /// Column the error occurred at.
+        /// Column the error occurred at starting at 0. The whole line is
+        /// underlined without a column.
         column: Option<usize>,
     },
 }
@@ -248,7 +799,43 @@ impl std::error::Error for SerdeError {}
 
 impl fmt::Display for SerdeError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        self.format(f)
+        self.render(f)
+    }
+}
+
+/// Renders the error with colors, see [`SerdeError::render`].
+struct Styled<'a, 't> {
+    error: &'a SerdeError,
+    annotated: &'a Annotated,
+    tracer: RefCell<&'t mut Tracer>,
+}
+
+impl fmt::Display for Styled<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.error
+            .render_styled(f, self.annotated, &mut self.tracer.borrow_mut())
+    }
+}
+
+/// Renders the error while collecting the decisions of the render, see
+/// [`SerdeError::render_debug`].
+struct Traced<'a> {
+    error: &'a SerdeError,
+    tracer: RefCell<Tracer>,
+}
+
+impl fmt::Display for Traced<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.error.render_traced(f, &mut self.tracer.borrow_mut())
+    }
+}
+
+/// Keeps the [`SerdeError`] as the inner error of an
+/// [`io::ErrorKind::InvalidData`] error so it is still rendered with the
+/// excerpt of the input.
+impl From<SerdeError> for io::Error {
+    fn from(err: SerdeError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, err)
     }
 }
 
@@ -273,6 +860,135 @@ impl From<toml::de::Error> for ErrorTypes {
     }
 }
 
+#[cfg(feature = "csv")]
+impl From<csv::Error> for ErrorTypes {
+    fn from(err: csv::Error) -> Self {
+        Self::Csv(err)
+    }
+}
+
+/// Error of the `csv` crate with the position of its field in the input. See
+/// [`ErrorTypes::Csv`].
+#[cfg(feature = "csv")]
+struct CsvError {
+    error: csv::Error,
+    line: Option<usize>,
+    column: Option<usize>,
+}
+
+#[cfg(feature = "csv")]
+impl CsvError {
+    fn new(input: &str, error: csv::Error) -> Self {
+        use std::convert::TryFrom;
+
+        let (position, field) = match error.kind() {
+            csv::ErrorKind::Deserialize {
+                pos: Some(position),
+                err,
+            } => (Some(position), err.field()),
+            csv::ErrorKind::Utf8 {
+                pos: Some(position),
+                err,
+            } => (Some(position), Some(err.field() as u64)),
+            csv::ErrorKind::UnequalLengths {
+                pos: Some(position),
+                ..
+            } => (Some(position), None),
+            _ => (None, None),
+        };
+
+        let record_start = position.and_then(|position| usize::try_from(position.byte()).ok());
+        let field_start = record_start.zip(field).and_then(|(record_start, field)| {
+            locate::csv_field_offset(input, record_start, usize::try_from(field).ok()?)
+        });
+
+        // Without a field the whole line of the record is underlined
+        let (line, column) = match (field_start, record_start) {
+            (Some(offset), _) => {
+                let location = Location::from_byte_offset(input, offset);
+                (
+                    location.map(|location| location.line),
+                    location.map(|location| location.column),
+                )
+            }
+            (None, Some(offset)) => (
+                Location::from_byte_offset(input, offset).map(|location| location.line),
+                None,
+            ),
+            (None, None) => (None, None),
+        };
+
+        Self {
+            error,
+            line,
+            column,
+        }
+    }
+}
+
+#[cfg(feature = "csv")]
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+#[cfg(feature = "csv")]
+impl ErrorLocation for CsvError {
+    fn location(&self) -> (Option<usize>, Option<usize>) {
+        (self.line, self.column)
+    }
+
+    fn language(&self) -> Option<&'static str> {
+        Some("csv")
+    }
+
+    fn adapter(&self) -> ErrorAdapter {
+        ErrorAdapter::Csv
+    }
+}
+
+/// Contents of [`ErrorTypes::Custom`].
+struct CustomError {
+    error: Box<dyn std::error::Error>,
+    line: Option<usize>,
+    column: Option<usize>,
+}
+
+impl fmt::Display for CustomError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl ErrorLocation for CustomError {
+    fn location(&self) -> (Option<usize>, Option<usize>) {
+        (self.line, self.column)
+    }
+}
+
+/// Borrowed error of a format that is not supported by the crate with the
+/// position extracted by the caller. See [`SerdeError::from_dyn_error`].
+struct DynError<'a> {
+    error: &'a (dyn std::error::Error + 'static),
+    position: Option<(usize, usize)>,
+}
+
+impl fmt::Display for DynError<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.error, f)
+    }
+}
+
+impl ErrorLocation for DynError<'_> {
+    fn location(&self) -> (Option<usize>, Option<usize>) {
+        match self.position {
+            Some((line, column)) => (Some(line), Some(column)),
+            None => (None, None),
+        }
+    }
+}
+
 impl From<(Box<dyn std::error::Error>, Option<usize>, Option<usize>)> for ErrorTypes {
     fn from(value: (Box<dyn std::error::Error>, Option<usize>, Option<usize>)) -> Self {
         Self::Custom {
@@ -285,84 +1001,333 @@ impl From<(Box<dyn std::error::Error>, Option<usize>, Option<usize>)> for ErrorT
 
 impl SerdeError {
     /// Create a new [`SerdeError`] from compatible serde errors. See
-    /// [`ErrorTypes`] for more information.
+    /// [`ErrorTypes`] for more information. The error uses the current global
+    /// defaults for rendering.
+    #[track_caller]
     pub fn new(input: String, err: impl Into<ErrorTypes>) -> SerdeError {
-        let error = err.into();
+        Self::with_config(input, err, RenderSettings::from_global_defaults())
+    }
+
+    /// Create a new [`SerdeError`] from input that was already split into
+    /// lines, for example the lines of an editor buffer. The lines are kept
+    /// and the error line and its context are rendered from them directly.
+    /// Lines may still end with the `\r` of a `\r\n` line ending. Lines and
+    /// columns of the error are counted the same way as for
+    /// [`SerdeError::new`].
+    #[track_caller]
+    pub fn from_lines(lines: Vec<String>, err: impl Into<ErrorTypes>) -> SerdeError {
+        Self::with_source(lines.into(), err, RenderSettings::from_global_defaults())
+    }
+
+    /// Create a new [`SerdeError`] from a custom error that is
+    /// [`Send`] and [`Sync`] like the errors of `anyhow` or async frameworks.
+    /// Works like [`ErrorTypes::Custom`] which only accepts errors without
+    /// these bounds.
+    #[must_use]
+    #[track_caller]
+    pub fn from_custom(
+        input: String,
+        error: Box<dyn std::error::Error + Send + Sync>,
+        line: Option<usize>,
+        column: Option<usize>,
+    ) -> SerdeError {
+        Self::from_error(
+            input,
+            &CustomError {
+                error,
+                line,
+                column,
+            },
+            RenderSettings::from_global_defaults(),
+        )
+    }
+
+    /// Create a new [`SerdeError`] from the error of any parser. The closure
+    /// gets the error and returns its line starting at 1 and column starting
+    /// at 0, usually by downcasting it to the concrete type of the parser.
+    /// Only the message is shown if it returns `None`.
+    ///
+    /// ```rust
+    /// use std::fmt;
+    ///
+    /// use format_serde_error::SerdeError;
+    ///
+    /// #[derive(Debug)]
+    /// struct IniError {
+    ///     line: usize,
+    ///     column: usize,
+    /// }
+    ///
+    /// impl fmt::Display for IniError {
+    ///     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    ///         f.write_str("expected `=`")
+    ///     }
+    /// }
+    ///
+    /// impl std::error::Error for IniError {}
+    ///
+    /// let err = IniError { line: 1, column: 5 };
+    /// let err = SerdeError::from_dyn_error("name value".to_string(), &err, |err| {
+    ///     err.downcast_ref::<IniError>()
+    ///         .map(|err| (err.line, err.column))
+    /// });
+    ///
+    /// assert!(err.to_string().contains("^ expected `=`"));
+    /// ```
+    #[must_use]
+    #[track_caller]
+    pub fn from_dyn_error(
+        input: String,
+        err: &(dyn std::error::Error + 'static),
+        extract: impl Fn(&(dyn std::error::Error + 'static)) -> Option<(usize, usize)>,
+    ) -> SerdeError {
+        Self::from_error(
+            input,
+            &DynError {
+                error: err,
+                position: extract(err),
+            },
+            RenderSettings::from_global_defaults(),
+        )
+    }
+
+    /// Create a new [`SerdeError`] with the message at the given position, for
+    /// example one found with the functions of [`locate`].
+    #[must_use]
+    #[track_caller]
+    pub fn at(input: String, message: impl Into<String>, location: Location) -> SerdeError {
+        Self::from_custom(
+            input,
+            message.into().into(),
+            Some(location.line),
+            Some(location.column),
+        )
+    }
+
+    /// Create a new [`SerdeError`] from compatible serde errors that is
+    /// rendered using the given settings instead of the global defaults. The
+    /// default message rewriter is used like with [`SerdeError::new`].
+    #[track_caller]
+    pub fn with_config(
+        input: String,
+        err: impl Into<ErrorTypes>,
+        settings: RenderSettings,
+    ) -> SerdeError {
+        Self::with_source(input.into(), err, settings)
+    }
 
-        let (message, line, column) = match error {
+    #[track_caller]
+    fn with_source(
+        input: Source,
+        err: impl Into<ErrorTypes>,
+        settings: RenderSettings,
+    ) -> SerdeError {
+        match err.into() {
             #[cfg(feature = "serde_json")]
-            ErrorTypes::Json(e) => (e.to_string(), Some(e.line()), Some(e.column())),
+            ErrorTypes::Json(e) => Self::from_error(input, &e, settings),
 
             #[cfg(feature = "serde_yaml")]
-            ErrorTypes::Yaml(e) => match e.location() {
-                // Don't set line/column if we don't have a location
-                None => (e.to_string(), None, None),
-
-                Some(location) => (
-                    e.to_string(),
-                    Some(location.line()),
-                    Some(location.column() - 1),
-                ),
-            },
+            ErrorTypes::Yaml(e) => Self::from_error(input, &e, settings),
 
             #[cfg(feature = "toml")]
-            ErrorTypes::Toml(e) => match e.line_col() {
-                // Don't set line/column if we do not have the values
-                None => (e.to_string(), None, None),
+            ErrorTypes::Toml(e) => Self::from_error(input, &e, settings),
 
-                Some((line, column)) => (e.to_string(), Some(line + 1), Some(column)),
-            },
+            #[cfg(feature = "csv")]
+            ErrorTypes::Csv(e) => {
+                let err = CsvError::new(input.text(), e);
+                Self::from_error(input, &err, settings)
+            }
 
             ErrorTypes::Custom {
                 error,
                 line,
                 column,
-            } => (error.to_string(), line, column),
+            } => Self::from_error(
+                input,
+                &CustomError {
+                    error,
+                    line,
+                    column,
+                },
+                settings,
+            ),
+        }
+    }
+
+    /// Create a new [`SerdeError`] from any error that knows its location
+    /// without taking ownership of it.
+    #[track_caller]
+    pub(crate) fn from_error(
+        input: impl Into<Source>,
+        err: &impl ErrorLocation,
+        settings: RenderSettings,
+    ) -> SerdeError {
+        let input = input.into();
+        let message = err.to_string();
+        let (line, column) = err.location();
+
+        // A column inside of a grapheme cluster would put the caret between
+        // the parts of a single visible character
+        #[cfg(feature = "graphemes_support")]
+        let column = match (line, column) {
+            (Some(line), Some(column)) => {
+                Some(grapheme_start(input.line(line).unwrap_or_default(), column))
+            }
+            _ => column,
         };
 
+        let category = err.category();
+        let language = err.language();
+        let adapter = err.adapter();
+
+        let kind = category.map_or_else(|| ErrorKind::from_message(&message), ErrorKind::from);
+
         Self {
             input,
             message,
             line,
             column,
-            contextualize: CONTEXTUALIZE.load(Ordering::Relaxed),
-            context_lines: CONTEXT_LINES.load(Ordering::Relaxed),
-            context_characters: CONTEXT_CHARACTERS.load(Ordering::Relaxed),
+            settings,
+            notes: Vec::new(),
+            labels: Vec::new(),
+            span_start: None,
+            line_statuses: HashMap::new(),
+            source_map: None,
+            // The default rewriter is used by every constructor so the settings
+            // given to `with_config` render like the ones `new` uses
+            message_rewriter: MESSAGE_REWRITER
+                .read()
+                .unwrap_or_else(PoisonError::into_inner)
+                .clone(),
+            line_number_formatter: None,
+            path: None,
+            category,
+            language,
+            constructed_at: std::panic::Location::caller(),
+            #[cfg(feature = "backtrace")]
+            backtrace: std::backtrace::Backtrace::capture(),
+            kind,
+            adapter,
+            severity: Severity::default(),
+            stats: OnceLock::new(),
+            last_rendered_error_line: Mutex::new(None),
         }
     }
 
-    /// Set if the output should be contextualized or not.
-    /// By default contextualization is set to [`CONTEXTUALIZE_DEFAULT`].
-    pub fn set_contextualize(&mut self, should_contextualize: bool) -> &mut Self {
-        self.contextualize = should_contextualize;
+    /// Suggest the closest valid keys or variants for unknown field and
+    /// unknown variant errors. The candidates are taken from the schema of
+    /// `T` at the path of the error so they are also correct for nested
+    /// objects. Other errors are left unchanged.
+    ///
+    /// ```rust
+    /// # #[cfg(all(feature = "serde_json", feature = "schemars"))]
+    /// # {
+    /// use format_serde_error::SerdeError;
+    ///
+    /// #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+    /// #[serde(deny_unknown_fields)]
+    /// struct Config {
+    ///     version: u32,
+    /// }
+    ///
+    /// let input = r#"{"verison": 1}"#;
+    /// let err = serde_json::from_str::<Config>(input).unwrap_err();
+    /// let err = SerdeError::new(input.to_string(), err).with_schema::<Config>();
+    ///
+    /// assert!(err.to_string().contains("help: did you mean `version`?"));
+    /// # }
+    /// ```
+    #[cfg(feature = "schemars")]
+    #[must_use]
+    pub fn with_schema<T: schemars::JsonSchema>(mut self) -> Self {
+        let root = schemars::schema_for!(T);
+
+        if let Some(message) = schema::suggestion(&self, &root) {
+            self.notes.push(enhance::Note {
+                kind: enhance::NoteKind::Help,
+                message,
+            });
+        }
+
         self
     }
 
-    /// Get if the output should be contextualized or not.
-    /// By default contextualization is set to [`CONTEXTUALIZE_DEFAULT`].
-    #[must_use]
+    /// Replace all render settings of this error.
+    pub fn set_settings(&mut self, settings: RenderSettings) -> &mut Self {
+        self.settings = settings;
+        self
+    }
+
+    /// Get the render settings of this error.
+    #[must_use]
+    pub fn get_settings(&self) -> &RenderSettings {
+        &self.settings
+    }
+
+    /// Set if the output should be contextualized or not.
+    /// By default contextualization is set to [`CONTEXTUALIZE_DEFAULT`].
+    pub fn set_contextualize(&mut self, should_contextualize: bool) -> &mut Self {
+        self.settings.contextualize = should_contextualize;
+        self
+    }
+
+    /// Get if the output should be contextualized or not.
+    /// By default contextualization is set to [`CONTEXTUALIZE_DEFAULT`].
+    #[must_use]
     pub fn get_contextualize(&self) -> bool {
-        self.contextualize
+        self.settings.contextualize
     }
 
     /// Set the amount of lines that should be shown before and after the error.
     /// By default the amount of context is set to [`CONTEXT_LINES_DEFAULT`].
     pub fn set_context_lines(&mut self, amount_of_context: usize) -> &mut Self {
-        self.context_lines = amount_of_context;
+        self.settings.context_lines = amount_of_context;
         self
     }
 
     /// Get the amount of lines that should be shown before and after the error.
     #[must_use]
     pub fn get_context_lines(&self) -> usize {
-        self.context_lines
+        self.settings.context_lines
+    }
+
+    /// Set which lines are shown around the error line. See [`ContextMode`].
+    /// By default the lines directly before and after the error line are
+    /// shown.
+    ///
+    /// ```rust
+    /// use format_serde_error::{
+    ///     ContextMode,
+    ///     SerdeError,
+    /// };
+    ///
+    /// let input = "server:\n  name: a\n  port: x".to_string();
+    /// let mut err = SerdeError::new(input, ("expected a number".into(), Some(3), Some(8)));
+    /// err.set_context_mode(ContextMode::Structural);
+    ///
+    /// assert_eq!(
+    ///     vec![(1, "server:", None), (3, "  port: x", Some(8))],
+    ///     err.lines_with_carets()
+    /// );
+    /// ```
+    pub fn set_context_mode(&mut self, context_mode: ContextMode) -> &mut Self {
+        self.settings.context_mode = context_mode;
+        self
+    }
+
+    /// Get which lines are shown around the error line.
+    #[must_use]
+    pub fn get_context_mode(&self) -> ContextMode {
+        self.settings.context_mode
     }
 
     /// Set the amount of characters that should be shown before and after the
     /// error. By default the amount of context is set to
-    /// [`CONTEXT_CHARACTERS_DEFAULT`].
+    /// [`CONTEXT_CHARACTERS_DEFAULT`]. If the start of the line is cut off the
+    /// column of the error is shown in front of the message like
+    /// `(column 910)` unless the message already contains it.
     pub fn set_context_characters(&mut self, amount_of_context: usize) -> &mut Self {
-        self.context_characters = amount_of_context;
+        self.settings.context_characters = amount_of_context;
         self
     }
 
@@ -370,286 +1335,3598 @@ impl SerdeError {
     /// error. Default value is [`CONTEXT_CHARACTERS_DEFAULT`].
     #[must_use]
     pub fn get_context_characters(&self) -> usize {
-        self.context_characters
+        self.settings.context_characters
+    }
+
+    /// Set the unit the amount of context characters set with
+    /// [`SerdeError::set_context_characters`] is measured in. With
+    /// [`ContextUnit::Columns`] wide characters count double so the shortened
+    /// line fits the amount of columns. Default is [`ContextUnit::Graphemes`].
+    pub fn set_context_chars_unit(&mut self, unit: ContextUnit) -> &mut Self {
+        self.settings.context_chars_unit = unit;
+        self
+    }
+
+    /// Get the unit the amount of context characters is measured in.
+    #[must_use]
+    pub fn get_context_chars_unit(&self) -> ContextUnit {
+        self.settings.context_chars_unit
+    }
+
+    /// Set where the error column is placed in the part of a long line that is
+    /// shown, as a fraction of the shown characters from the left. For example
+    /// `0.25` shows a quarter of the characters before the error and the rest
+    /// after it. Values are clamped to `0.0..=1.0`. By default the error is
+    /// centered.
+    pub fn set_caret_anchor(&mut self, anchor: f32) -> &mut Self {
+        self.settings.caret_anchor = Some(anchor.clamp(0.0, 1.0));
+        self
+    }
+
+    /// Get where the error column is placed in shortened lines if set.
+    #[must_use]
+    pub fn get_caret_anchor(&self) -> Option<f32> {
+        self.settings.caret_anchor
+    }
+
+    /// Set how the lines are numbered in the gutter. See [`LineNumbering`].
+    pub fn set_line_numbering(&mut self, line_numbering: LineNumbering) -> &mut Self {
+        self.settings.line_numbering = line_numbering;
+        self
+    }
+
+    /// Get how the lines are numbered in the gutter.
+    #[must_use]
+    pub fn get_line_numbering(&self) -> LineNumbering {
+        self.settings.line_numbering
+    }
+
+    /// Set if the line numbers should be shown in front of the lines. When
+    /// disabled only the separator is printed in front of the lines. By
+    /// default line numbers are shown.
+    pub fn set_show_line_numbers(&mut self, show_line_numbers: bool) -> &mut Self {
+        self.settings.show_line_numbers = show_line_numbers;
+        self
+    }
+
+    /// Get if the line numbers should be shown in front of the lines.
+    #[must_use]
+    pub fn get_show_line_numbers(&self) -> bool {
+        self.settings.show_line_numbers
+    }
+
+    /// Set the amount of spaces every tab in the input is replaced with. The
+    /// column of the error is moved accordingly so it still points at the same
+    /// character. By default the tab width is set to [`TAB_WIDTH_DEFAULT`].
+    pub fn set_tab_width(&mut self, tab_width: usize) -> &mut Self {
+        self.settings.tab_width = tab_width;
+        self
+    }
+
+    /// Get the amount of spaces every tab in the input is replaced with.
+    #[must_use]
+    pub fn get_tab_width(&self) -> usize {
+        self.settings.tab_width
+    }
+
+    /// Set if whitespace in the shown lines is made visible. Spaces are shown
+    /// as `·`, tabs as `→` followed by spaces up to the tab width and
+    /// non-breaking or zero-width characters as their code point like
+    /// `⟨U+00A0⟩`. The markers are dimmed if colors are shown and the caret
+    /// still points at the same character. Indentation is not removed in
+    /// this mode. By default this is disabled.
+    pub fn set_show_whitespace(&mut self, show_whitespace: bool) -> &mut Self {
+        self.settings.show_whitespace = show_whitespace;
+        self
+    }
+
+    /// Get if whitespace in the shown lines is made visible.
+    #[must_use]
+    pub fn get_show_whitespace(&self) -> bool {
+        self.settings.show_whitespace
+    }
+
+    /// Set if inputs with a single line are shown with the empty line in
+    /// front and the gutter with the line number like inputs with more lines.
+    /// By default a single line is shown without them as there are no other
+    /// lines to tell apart. Enable this if something is written in front of
+    /// the output on the same line, like the `Error: ` of `anyhow`.
+    pub fn set_frame_single_line(&mut self, frame_single_line: bool) -> &mut Self {
+        self.settings.frame_single_line = frame_single_line;
+        self
+    }
+
+    /// Get if inputs with a single line are shown with the full frame.
+    #[must_use]
+    pub fn get_frame_single_line(&self) -> bool {
+        self.settings.frame_single_line
+    }
+
+    /// Set if errors about the input ending early like `EOF while parsing an
+    /// object` are marked right behind the last character of the input with
+    /// `↵ expected more` instead of a caret on the empty line after it. See
+    /// [`Labels::expected_more`] to translate the text. By default this is
+    /// enabled.
+    pub fn set_eof_marker(&mut self, eof_marker: bool) -> &mut Self {
+        self.settings.eof_marker = eof_marker;
+        self
+    }
+
+    /// Get if errors about the input ending early are marked behind the last
+    /// character of the input.
+    #[must_use]
+    pub fn get_eof_marker(&self) -> bool {
+        self.settings.eof_marker
+    }
+
+    /// Set if built-in hints for common mistakes are shown below the excerpt.
+    /// For example YAML errors about a character that cannot start any token
+    /// on a line indented with a tab explain that tabs are not allowed and
+    /// point at the tab. Hints registered with [`hints::register`] are shown
+    /// either way. By default this is enabled.
+    pub fn set_hints(&mut self, hints: bool) -> &mut Self {
+        self.settings.hints = hints;
+        self
+    }
+
+    /// Get if built-in hints for common mistakes are shown.
+    #[must_use]
+    pub fn get_hints(&self) -> bool {
+        self.settings.hints
+    }
+
+    /// Set if errors about missing fields should point at the start of the
+    /// object the field is missing from instead of the end of it. A note
+    /// telling the user to add the field is added as well. By default this is
+    /// disabled.
+    pub fn set_relocate_missing_field(&mut self, relocate_missing_field: bool) -> &mut Self {
+        self.settings.relocate_missing_field = relocate_missing_field;
+        self
+    }
+
+    /// Get if errors about missing fields should point at the start of the
+    /// object the field is missing from.
+    #[must_use]
+    pub fn get_relocate_missing_field(&self) -> bool {
+        self.settings.relocate_missing_field
+    }
+
+    /// Set if errors about the value of a key should also mark the key with a
+    /// secondary label. By default this is disabled.
+    pub fn set_mark_key(&mut self, mark_key: bool) -> &mut Self {
+        self.settings.mark_key = mark_key;
+        self
+    }
+
+    /// Get if errors about the value of a key should also mark the key.
+    #[must_use]
+    pub fn get_mark_key(&self) -> bool {
+        self.settings.mark_key
+    }
+
+    /// Set a placeholder that is printed in front of the message when the input
+    /// is empty, for example `(empty input)`. By default no placeholder is
+    /// printed.
+    pub fn set_empty_input_placeholder(&mut self, placeholder: Option<String>) -> &mut Self {
+        self.settings.empty_input_placeholder = placeholder;
+        self
+    }
+
+    /// Get the placeholder that is printed when the input is empty.
+    #[must_use]
+    pub fn get_empty_input_placeholder(&self) -> Option<&str> {
+        self.settings.empty_input_placeholder.as_deref()
+    }
+
+    /// Set if a note with the source of the value the error is about should be
+    /// shown. This helps with messages like `invalid type: map, expected a
+    /// string` which don't contain the value itself. Long values are
+    /// shortened. By default this is disabled.
+    pub fn set_show_value_preview(&mut self, show_value_preview: bool) -> &mut Self {
+        self.settings.show_value_preview = show_value_preview;
+        self
+    }
+
+    /// Get if a note with the source of the value the error is about should
+    /// be shown.
+    #[must_use]
+    pub fn get_show_value_preview(&self) -> bool {
+        self.settings.show_value_preview
+    }
+
+    /// Set if the byte offset of the error in the input should be appended to
+    /// the message, e.g. `... at line 4 column 10 (byte 87)`. This helps with
+    /// correlating the error with hexdumps or other tools working on bytes.
+    /// By default this is disabled.
+    pub fn set_show_byte_offset(&mut self, show_byte_offset: bool) -> &mut Self {
+        self.settings.show_byte_offset = show_byte_offset;
+        self
+    }
+
+    /// Get if the byte offset of the error in the input should be appended to
+    /// the message.
+    #[must_use]
+    pub fn get_show_byte_offset(&self) -> bool {
+        self.settings.show_byte_offset
+    }
+
+    /// Set if errors about the length of a sequence should underline the whole
+    /// sequence instead of pointing at one end of it. If the sequence spans
+    /// more lines than are shown a note with its full extent is added. By
+    /// default this is disabled.
+    pub fn set_underline_sequence(&mut self, underline_sequence: bool) -> &mut Self {
+        self.settings.underline_sequence = underline_sequence;
+        self
+    }
+
+    /// Get if errors about the length of a sequence should underline the
+    /// whole sequence.
+    #[must_use]
+    pub fn get_underline_sequence(&self) -> bool {
+        self.settings.underline_sequence
+    }
+
+    /// Underline everything from the given line (starting at 1) and column
+    /// (starting at 0) up to the location of the error.
+    pub fn set_span_start(&mut self, line: usize, column: usize) -> &mut Self {
+        self.span_start = Some((line, column));
+        self
+    }
+
+    /// Get the line and column the underline starts at if one was set.
+    #[must_use]
+    pub fn get_span_start(&self) -> Option<(usize, usize)> {
+        self.span_start
+    }
+
+    /// Show a status glyph like `+` or `-` in the gutter behind the numbers of
+    /// the given lines (starting at 1), for example to mark lines that changed
+    /// compared to another version of the input. Lines without a status get a
+    /// space so the output stays aligned.
+    pub fn set_line_statuses(&mut self, line_statuses: HashMap<usize, char>) -> &mut Self {
+        self.line_statuses = line_statuses;
+        self
+    }
+
+    /// Get the status glyphs shown in the gutter by line.
+    #[must_use]
+    pub fn get_line_statuses(&self) -> &HashMap<usize, char> {
+        &self.line_statuses
+    }
+
+    /// Add a secondary marker with a message pointing at the given line
+    /// (starting at 1) and column (starting at 0), for example for another
+    /// error on the same line. The marker is shown as `- message` below the
+    /// line.
+    pub fn add_label(&mut self, line: usize, column: usize, message: &str) -> &mut Self {
+        self.labels.push(enhance::Label {
+            line,
+            column,
+            message: message.to_string(),
+        });
+        self
+    }
+
+    /// Combine another error for the same input into this one so both are
+    /// shown against one excerpt, for example when the input was parsed with
+    /// two different types. The message of the other error is added as a label
+    /// at its location or as a note if the location is unknown. Returns both
+    /// errors unchanged if their inputs differ.
+    ///
+    /// # Errors
+    ///
+    /// Returns both errors if they were created for different inputs.
+    // Both errors are handed back so nothing is lost when merging fails
+    #[allow(clippy::result_large_err)]
+    pub fn merge(mut self, other: SerdeError) -> Result<SerdeError, (SerdeError, SerdeError)> {
+        if self.input != other.input {
+            return Err((self, other));
+        }
+
+        let message = other.displayed_message().into_owned();
+
+        match other.line {
+            Some(line) => self.labels.push(enhance::Label {
+                line,
+                column: other.column.unwrap_or_default(),
+                message,
+            }),
+            None => self.notes.push(enhance::Note {
+                kind: enhance::NoteKind::Note,
+                message,
+            }),
+        }
+
+        self.severity = self.severity.min(other.severity);
+        self.notes.extend(other.notes);
+        self.labels.extend(other.labels);
+
+        Ok(self)
+    }
+
+    /// Set the separator used between the gutter and the lines with carets,
+    /// underlines and labels. This makes it possible to visually distinguish
+    /// annotations from the source, e.g. with `·`. Separators shorter than the
+    /// one in front of the lines are padded with spaces on both sides so the
+    /// output stays aligned. By default this is the same as the separator in
+    /// front of the lines.
+    pub fn set_annotation_separator(&mut self, separator: &str) -> &mut Self {
+        self.settings.annotation_separator = separator.to_string();
+        self
+    }
+
+    /// Get the separator used between the gutter and the lines with carets,
+    /// underlines and labels.
+    #[must_use]
+    pub fn get_annotation_separator(&self) -> &str {
+        &self.settings.annotation_separator
+    }
+
+    /// Set a callback that translates lines of the input to the file and line
+    /// they originally came from. This is useful if the input was generated,
+    /// e.g. by a templating step, before it was parsed. The snippet still
+    /// shows the input that was parsed but the line numbers and a `-->
+    /// path:line` header refer to the original file. If the callback
+    /// returns [`None`] for the error line a note tells that the line is
+    /// part of generated text.
+    ///
+    /// ```rust
+    /// use format_serde_error::SerdeError;
+    ///
+    /// // The first two lines were inserted by an include
+    /// let input = "included: true\nalso: included\nvalue: oops".to_string();
+    ///
+    /// let mut err = SerdeError::new(input, ("invalid value".into(), Some(3), Some(7)));
+    /// err.set_source_map(|line| {
+    ///     if line > 2 {
+    ///         Some(("config.yaml.j2".into(), line - 2))
+    ///     } else {
+    ///         None
+    ///     }
+    /// });
+    ///
+    /// assert!(err.to_string().contains("--> config.yaml.j2:1"));
+    /// ```
+    pub fn set_source_map(
+        &mut self,
+        source_map: impl Fn(usize) -> Option<(PathBuf, usize)> + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.source_map = Some(SourceMap(Box::new(source_map)));
+        self
+    }
+
+    /// Set a callback that replaces the message of the error with a text that
+    /// is easier to understand for the users of the application. The callback
+    /// gets the original message and the replacement is shown instead if it
+    /// returns [`Some`]. The replacement is used for the snippet, the
+    /// [`RenderStyle::Minimal`] output and [`Report::diagnostics`]. The
+    /// original message stays available with [`Self::message`] and
+    /// [`Self::set_show_raw_message`].
+    ///
+    /// ```rust
+    /// use format_serde_error::{
+    ///     RenderStyle,
+    ///     SerdeError,
+    /// };
+    ///
+    /// let mut err = SerdeError::new(
+    ///     "timeout: soon".to_string(),
+    ///     ("invalid type: string \"soon\", expected u32".into(), Some(1), Some(9)),
+    /// );
+    /// err.set_render_style(RenderStyle::Minimal)
+    ///     .set_message_rewriter(|message| {
+    ///         message
+    ///             .starts_with("invalid type")
+    ///             .then(|| "The value for `timeout` must be a number, e.g. 30".to_string())
+    ///     });
+    ///
+    /// assert_eq!(
+    ///     "line 1 column 10: The value for `timeout` must be a number, e.g. 30\n",
+    ///     err.to_string()
+    /// );
+    /// ```
+    pub fn set_message_rewriter(
+        &mut self,
+        message_rewriter: impl Fn(&str) -> Option<String> + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.message_rewriter = Some(MessageRewriter(Arc::new(message_rewriter)));
+        self
+    }
+
+    /// Set the path of the file the input was read from. When colors are
+    /// enabled the line number of the error becomes a terminal hyperlink
+    /// (OSC 8) to the file. See [`SerdeError::set_hyperlink_scheme`] for the
+    /// format of the link.
+    pub fn set_path(&mut self, path: impl Into<PathBuf>) -> &mut Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Get the path of the file the input was read from.
+    #[must_use]
+    pub fn get_path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Set the format of the hyperlink for the line number of the error. By
+    /// default this is [`HyperlinkScheme::File`].
+    pub fn set_hyperlink_scheme(&mut self, hyperlink_scheme: HyperlinkScheme) -> &mut Self {
+        self.settings.hyperlink_scheme = hyperlink_scheme;
+        self
+    }
+
+    /// Get the format of the hyperlink for the line number of the error.
+    #[must_use]
+    pub fn get_hyperlink_scheme(&self) -> HyperlinkScheme {
+        self.settings.hyperlink_scheme
+    }
+
+    /// Get the target of the hyperlink for the line number of the error.
+    /// Returns [`None`] if no path is set or the error has no line. If a
+    /// source map is set the link points to the original file.
+    ///
+    /// ```rust
+    /// use format_serde_error::{
+    ///     HyperlinkScheme,
+    ///     SerdeError,
+    /// };
+    ///
+    /// let mut err = SerdeError::new("a: b".to_string(), ("error".into(), Some(1), Some(3)));
+    /// err.set_path("/etc/app/config.yaml")
+    ///     .set_hyperlink_scheme(HyperlinkScheme::VsCode);
+    ///
+    /// assert_eq!(
+    ///     Some("vscode://file/etc/app/config.yaml:1:4".to_string()),
+    ///     err.hyperlink()
+    /// );
+    /// ```
+    #[must_use]
+    pub fn hyperlink(&self) -> Option<String> {
+        let annotated = Annotated::new(self);
+        let line = annotated.line?;
+        let column = annotated.column.unwrap_or_default() + 1;
+
+        let (path, line) = match self.mapped_line(line) {
+            Some((path, line)) => (path, line),
+            None => (self.path.clone()?, line),
+        };
+
+        Some(self.settings.hyperlink_scheme.uri(&path, line, column))
+    }
+
+    /// Get the lines that are shown around the error together with their line
+    /// number (starting at 1) and the column of the caret (in characters
+    /// starting at 0). Only the error line has a column. This allows custom
+    /// renderers to reconstruct the layout of the output. Returns no lines if
+    /// the error has no line. Context lines are only included if
+    /// contextualizing is enabled.
+    ///
+    /// ```rust
+    /// use format_serde_error::SerdeError;
+    ///
+    /// let input = "first: 1\nsecond: x\nthird: 3".to_string();
+    /// let mut err = SerdeError::new(input, ("expected a number".into(), Some(2), Some(8)));
+    /// err.set_context_lines(1);
+    ///
+    /// assert_eq!(
+    ///     vec![
+    ///         (1, "first: 1", None),
+    ///         (2, "second: x", Some(8)),
+    ///         (3, "third: 3", None),
+    ///     ],
+    ///     err.lines_with_carets()
+    /// );
+    /// ```
+    #[must_use]
+    pub fn lines_with_carets(&self) -> Vec<(usize, &str, Option<usize>)> {
+        let annotated = Annotated::new(self);
+        let Some(error_line) = annotated.line else {
+            return Vec::new();
+        };
+
+        self.shown_lines(error_line)
+            .into_iter()
+            .filter(|(line, _)| self.settings.contextualize || *line == error_line)
+            .map(|(line, text)| {
+                let column = if line == error_line {
+                    annotated.column
+                } else {
+                    None
+                };
+
+                (line, text, column)
+            })
+            .collect()
+    }
+
+    /// Get the given amount of lines before and after the error line as plain
+    /// text with the line number in front of every line. Like the context
+    /// lines more lines after the error line are shown if there are not enough
+    /// lines in front of it. There are no carets, colors or notes and the
+    /// render settings are ignored. This is useful to
+    /// embed the source around the error into a larger report. Returns an
+    /// empty string if the error has no line.
+    ///
+    /// ```rust
+    /// use format_serde_error::SerdeError;
+    ///
+    /// let input = "first: 1\nsecond: x\nthird: 3".to_string();
+    /// let err = SerdeError::new(input, ("expected a number".into(), Some(2), Some(8)));
+    ///
+    /// assert_eq!("1 | first: 1\n2 | second: x\n3 | third: 3\n", err.source_excerpt(1));
+    /// ```
+    #[must_use]
+    pub fn source_excerpt(&self, lines: usize) -> String {
+        use std::fmt::Write as _;
+
+        let Some(error_line) = self.line else {
+            return String::new();
+        };
+
+        let (skip, take) = Self::window_around(error_line, lines);
+        let shown = self
+            .input
+            .lines()
+            .enumerate()
+            .skip(skip)
+            .take(take)
+            .collect::<Vec<_>>();
+
+        let width = shown
+            .last()
+            .map_or(0, |(index, _)| (index + 1).to_string().len());
+
+        let mut excerpt = String::new();
+        for (index, text) in shown {
+            let _ = writeln!(excerpt, "{: >width$}{}{}", index + 1, SEPARATOR, text);
+        }
+
+        excerpt
+    }
+
+    /// Set if the rendering should depend on the [`Category`] of the error.
+    /// Errors while reading the input are shown without a snippet, errors
+    /// about the input ending early point at the end of the last line with a
+    /// note about what is still open and errors about the data get a note and
+    /// a preview of the value. By default this is disabled.
+    pub fn set_categorize(&mut self, categorize: bool) -> &mut Self {
+        self.settings.categorize = categorize;
+        self
+    }
+
+    /// Get if the rendering should depend on the [`Category`] of the error.
+    #[must_use]
+    pub fn get_categorize(&self) -> bool {
+        self.settings.categorize
+    }
+
+    /// Set the width in characters the whole report is fitted into. Long lines
+    /// are wrapped instead of shortened with an ellipse and carets, labels and
+    /// the message are moved below the wrapped part of the line they belong
+    /// to. Messages and notes are wrapped as well.
+    pub fn set_viewport_width(&mut self, width: usize) -> &mut Self {
+        self.settings.viewport_width = Some(width);
+        self
+    }
+
+    /// Get the width the report is fitted into if one is set.
+    #[must_use]
+    pub fn get_viewport_width(&self) -> Option<usize> {
+        self.settings.viewport_width
+    }
+
+    /// Set where the message is placed relative to the error line. With
+    /// [`AnnotationStyle::Inline`] short messages are shown behind the error
+    /// line like `port: x  <-- invalid type` instead of below it. By default
+    /// the message is shown below the error line.
+    pub fn set_annotation_style(&mut self, annotation_style: AnnotationStyle) -> &mut Self {
+        self.settings.annotation_style = annotation_style;
+        self
+    }
+
+    /// Get where the message is placed relative to the error line.
+    #[must_use]
+    pub fn get_annotation_style(&self) -> AnnotationStyle {
+        self.settings.annotation_style
+    }
+
+    /// Set if the message is shown behind the error line. Shorthand for
+    /// [`SerdeError::set_annotation_style`] with [`AnnotationStyle::Inline`]
+    /// or [`AnnotationStyle::CaretBelow`]. By default this is disabled.
+    pub fn set_inline_message(&mut self, inline_message: bool) -> &mut Self {
+        self.set_annotation_style(if inline_message {
+            AnnotationStyle::Inline
+        } else {
+            AnnotationStyle::CaretBelow
+        })
+    }
+
+    /// Get if short messages are shown behind the error line.
+    #[must_use]
+    pub fn get_inline_message(&self) -> bool {
+        self.settings.annotation_style == AnnotationStyle::Inline
+    }
+
+    /// Set a text that is highlighted wherever it occurs in the shown lines,
+    /// for example the name of a duplicate key. The text is matched exactly
+    /// and occurrences that are cut off by shortening or wrapping a line are
+    /// not highlighted. Only has an effect if colors are shown.
+    pub fn set_highlight_term(&mut self, term: impl Into<String>) -> &mut Self {
+        self.settings.highlight_term = Some(term.into());
+        self
+    }
+
+    /// Get the text that is highlighted in the shown lines if one is set.
+    #[must_use]
+    pub fn get_highlight_term(&self) -> Option<&str> {
+        self.settings.highlight_term.as_deref()
+    }
+
+    /// Set the function that formats the line numbers shown in the gutter, for
+    /// example to pad them with zeros. The gutter is as wide as the formatted
+    /// number. Only used with [`LineNumbering::Absolute`].
+    ///
+    /// ```rust
+    /// use format_serde_error::SerdeError;
+    ///
+    /// let width = 3;
+    /// let input = "a: 1\nb: x".to_string();
+    /// let mut err = SerdeError::new(input, ("error".into(), Some(2), Some(3)));
+    /// err.set_line_number_formatter(move |line| format!("{line:0width$}"));
+    ///
+    /// assert!(err.to_string().contains(" 002 | b: x\n"));
+    /// ```
+    pub fn set_line_number_formatter(
+        &mut self,
+        formatter: impl Fn(usize) -> String + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.line_number_formatter = Some(LineNumberFormatter(Arc::new(formatter)));
+        self
+    }
+
+    /// Get the function that formats the line numbers in the gutter if one is
+    /// set.
+    #[must_use]
+    pub fn get_line_number_formatter(&self) -> Option<&(dyn Fn(usize) -> String + Send + Sync)> {
+        self.line_number_formatter
+            .as_ref()
+            .map(|formatter| formatter.0.as_ref())
+    }
+
+    /// Set if the byte offset of the start of each shown line in the original
+    /// input is shown in the gutter behind the line number, for example
+    /// ` 114 @0x1a2f | `. Helps to find the lines with tools that work with
+    /// byte offsets like `xxd`.
+    pub fn set_show_byte_offsets(&mut self, show_byte_offsets: bool) -> &mut Self {
+        self.settings.show_byte_offsets = show_byte_offsets;
+        self
+    }
+
+    /// Get if the byte offsets of the lines are shown in the gutter.
+    #[must_use]
+    pub fn get_show_byte_offsets(&self) -> bool {
+        self.settings.show_byte_offsets
+    }
+
+    /// Set which characters count as indentation that is removed in front of
+    /// the shown lines. Default is [`IndentWs::Unicode`].
+    pub fn set_indentation_whitespace(&mut self, indentation_whitespace: IndentWs) -> &mut Self {
+        self.settings.indentation_whitespace = indentation_whitespace;
+        self
+    }
+
+    /// Get which characters count as indentation.
+    #[must_use]
+    pub fn get_indentation_whitespace(&self) -> IndentWs {
+        self.settings.indentation_whitespace
+    }
+
+    /// Set the amount of spaces every rendered line is indented with, for
+    /// example to nest the error below other output. The whole block is moved
+    /// so the carets stay aligned with the lines. Default is 0.
+    pub fn set_block_indent(&mut self, block_indent: usize) -> &mut Self {
+        self.settings.block_indent = block_indent;
+        self
+    }
+
+    /// Get the amount of spaces every rendered line is indented with.
+    #[must_use]
+    pub fn get_block_indent(&self) -> usize {
+        self.settings.block_indent
+    }
+
+    /// Set on which side of the caret the message is placed. With
+    /// [`TextDirection::Rtl`] the message is placed in front of the caret for
+    /// right-to-left languages. The caret itself stays at the column of the
+    /// error. Default is [`TextDirection::Ltr`].
+    pub fn set_text_direction(&mut self, text_direction: TextDirection) -> &mut Self {
+        self.settings.text_direction = text_direction;
+        self
+    }
+
+    /// Get on which side of the caret the message is placed.
+    #[must_use]
+    pub fn get_text_direction(&self) -> TextDirection {
+        self.settings.text_direction
+    }
+
+    /// Set how the error is marked on the error line. With [`MarkerStyle::Box`]
+    /// a box is drawn around the span of the error, for example the one set
+    /// with [`SerdeError::set_span_start`]. Default is [`MarkerStyle::Caret`].
+    pub fn set_marker_style(&mut self, marker_style: MarkerStyle) -> &mut Self {
+        self.settings.marker_style = marker_style;
+        self
     }
 
-    fn format(&self, f: &mut fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
-        // If line and column are not set we assume that we can't make a nice output
-        // so we will just print the original message in red and bold
-        if self.line.is_none() && self.column.is_none() {
-            #[cfg(feature = "colored")]
-            return writeln!(f, "{}", self.message.red().bold());
+    /// Get how the error is marked on the error line.
+    #[must_use]
+    pub fn get_marker_style(&self) -> MarkerStyle {
+        self.settings.marker_style
+    }
+
+    /// Set how much of the error is rendered. See [`RenderStyle`].
+    ///
+    /// ```rust
+    /// use format_serde_error::{
+    ///     RenderStyle,
+    ///     SerdeError,
+    /// };
+    ///
+    /// let mut err = SerdeError::new("a: b".to_string(), ("error".into(), Some(1), Some(3)));
+    /// err.set_path("config.yaml")
+    ///     .set_render_style(RenderStyle::Minimal);
+    ///
+    /// assert_eq!("config.yaml:1:4: error\n", err.to_string());
+    /// ```
+    pub fn set_render_style(&mut self, render_style: RenderStyle) -> &mut Self {
+        self.settings.render_style = render_style;
+        self
+    }
+
+    /// Get how much of the error is rendered.
+    #[must_use]
+    pub fn get_render_style(&self) -> RenderStyle {
+        self.settings.render_style
+    }
+
+    /// Set the labels the crate adds to the output like the `note` in front of
+    /// notes. See [`Labels`].
+    pub fn set_labels(&mut self, labels: Labels) -> &mut Self {
+        self.settings.labels = labels;
+        self
+    }
+
+    /// Get the labels the crate adds to the output.
+    #[must_use]
+    pub fn get_labels(&self) -> &Labels {
+        &self.settings.labels
+    }
+
+    /// Set if the column of the error in the input is printed between the caret
+    /// and the message like `^ (column 12) message`. The column starts at 1 and
+    /// is counted in characters of the original line before tabs are expanded
+    /// and whitespace is removed or the line is shortened.
+    pub fn set_show_column_number(&mut self, show_column_number: bool) -> &mut Self {
+        self.settings.show_column_number = show_column_number;
+        self
+    }
+
+    /// Get if the column of the error is printed next to the caret.
+    #[must_use]
+    pub fn get_show_column_number(&self) -> bool {
+        self.settings.show_column_number
+    }
+
+    /// Set if a shortened error line is split into multiple windows when labels
+    /// added with [`Self::add_label`] point outside of the part around the
+    /// error. Every window shows the context characters around its marker and
+    /// the windows are joined by an ellipse. Without this labels that are not
+    /// visible anymore are left out.
+    pub fn set_multiple_windows(&mut self, multiple_windows: bool) -> &mut Self {
+        self.settings.multiple_windows = multiple_windows;
+        self
+    }
+
+    /// Get if shortened error lines are split into multiple windows.
+    #[must_use]
+    pub fn get_multiple_windows(&self) -> bool {
+        self.settings.multiple_windows
+    }
+
+    /// Set if the top-level key the error is nested under is shown as the first
+    /// line of the snippet even if it is outside of the context lines. For YAML
+    /// this is the nearest line before the error line that is not indented.
+    /// For JSON it is the key of the root object whose value contains the
+    /// error. Left out lines between the key and the context lines are marked
+    /// with `...`.
+    pub fn set_show_anchor_line(&mut self, show_anchor_line: bool) -> &mut Self {
+        self.settings.show_anchor_line = show_anchor_line;
+        self
+    }
+
+    /// Get if the top-level key the error is nested under is always shown.
+    #[must_use]
+    pub fn get_show_anchor_line(&self) -> bool {
+        self.settings.show_anchor_line
+    }
+
+    /// Set if a header with the size of the input and the position of the error
+    /// in it is shown before the snippet, for example
+    /// `error at line 114 of 2,341 (column 12), 184 KiB document`. Only the
+    /// size is shown if the position is unknown. The statistics are computed
+    /// once and reused for later renders.
+    pub fn set_show_stats(&mut self, show_stats: bool) -> &mut Self {
+        self.settings.show_stats = show_stats;
+        self
+    }
+
+    /// Get if a header with statistics about the input is shown.
+    #[must_use]
+    pub fn get_show_stats(&self) -> bool {
+        self.settings.show_stats
+    }
+
+    /// Set if the total amount of lines of the input is appended to the
+    /// message next to the caret like `(of 2,000 lines)`. The lines are
+    /// counted once and reused for later renders.
+    pub fn set_show_total_lines(&mut self, show_total_lines: bool) -> &mut Self {
+        self.settings.show_total_lines = show_total_lines;
+        self
+    }
+
+    /// Get if the total amount of lines is shown next to the caret.
+    #[must_use]
+    pub fn get_show_total_lines(&self) -> bool {
+        self.settings.show_total_lines
+    }
+
+    /// Set if ANSI escape sequences are removed from the message and the input
+    /// before rendering. Messages of some libraries are already styled which
+    /// would otherwise be styled twice and the escape sequences would shift
+    /// the caret. Default is `true`.
+    pub fn set_strip_ansi(&mut self, strip_ansi: bool) -> &mut Self {
+        self.settings.strip_ansi = strip_ansi;
+        self
+    }
+
+    /// Get if ANSI escape sequences are removed from the message and the input.
+    #[must_use]
+    pub fn get_strip_ansi(&self) -> bool {
+        self.settings.strip_ansi
+    }
+
+    /// Set if the ` at line N column M` suffix is removed from the message. The
+    /// position is already visible in the rendered snippet.
+    pub fn set_strip_position_suffix(&mut self, strip_position_suffix: bool) -> &mut Self {
+        self.settings.strip_position_suffix = strip_position_suffix;
+        self
+    }
+
+    /// Get if the position suffix is removed from the message.
+    #[must_use]
+    pub fn get_strip_position_suffix(&self) -> bool {
+        self.settings.strip_position_suffix
+    }
+
+    /// Set if the original message is shown as a dimmed note below the message
+    /// that has been cleaned up, for example with
+    /// [`Self::set_strip_position_suffix`]. Helps with debugging the cleanup
+    /// itself.
+    pub fn set_show_raw_message(&mut self, show_raw_message: bool) -> &mut Self {
+        self.settings.show_raw_message = show_raw_message;
+        self
+    }
+
+    /// Get if the original message is shown below the cleaned one.
+    #[must_use]
+    pub fn get_show_raw_message(&self) -> bool {
+        self.settings.show_raw_message
+    }
+
+    /// Set if the location in the code the error was created at is shown as a
+    /// dimmed note below the message like `constructed at src/config.rs:87:5`.
+    /// Helps finding the call site that paired an error with the wrong input.
+    pub fn set_show_constructed_at(&mut self, show_constructed_at: bool) -> &mut Self {
+        self.settings.show_constructed_at = show_constructed_at;
+        self
+    }
+
+    /// Get if the location the error was created at is shown below the
+    /// message.
+    #[must_use]
+    pub fn get_show_constructed_at(&self) -> bool {
+        self.settings.show_constructed_at
+    }
+
+    /// Location in the code that created the error, for example the call of
+    /// [`SerdeError::new`].
+    #[must_use]
+    pub fn constructed_at(&self) -> &'static std::panic::Location<'static> {
+        self.constructed_at
+    }
+
+    /// Set if the backtrace captured when the error was created is shown
+    /// below the notes. Backtraces are only captured if enabled with the
+    /// `RUST_BACKTRACE` or `RUST_LIB_BACKTRACE` environment variables, see
+    /// [`std::backtrace::Backtrace::capture`].
+    #[cfg(feature = "backtrace")]
+    pub fn set_show_backtrace(&mut self, show_backtrace: bool) -> &mut Self {
+        self.settings.show_backtrace = show_backtrace;
+        self
+    }
+
+    /// Get if the backtrace captured when the error was created is shown.
+    #[cfg(feature = "backtrace")]
+    #[must_use]
+    pub fn get_show_backtrace(&self) -> bool {
+        self.settings.show_backtrace
+    }
+
+    /// Backtrace of the creation of the error. Check
+    /// [`Backtrace::status`](std::backtrace::Backtrace::status) to see if it
+    /// was captured.
+    #[cfg(feature = "backtrace")]
+    pub fn backtrace(&self) -> &std::backtrace::Backtrace {
+        &self.backtrace
+    }
+
+    /// Set how lines that are too long are shown. See [`LongLineMode`].
+    pub fn set_long_line_mode(&mut self, long_line_mode: LongLineMode) -> &mut Self {
+        self.settings.long_line_mode = long_line_mode;
+        self
+    }
+
+    /// Get how lines that are too long are shown.
+    #[must_use]
+    pub fn get_long_line_mode(&self) -> LongLineMode {
+        self.settings.long_line_mode
+    }
+
+    /// Set the width of the output lines are wrapped to with
+    /// [`LongLineMode::Wrap`] instead of the width of the terminal.
+    pub fn set_wrap_width(&mut self, width: usize) -> &mut Self {
+        self.settings.wrap_width = Some(width);
+        self
+    }
+
+    /// Get the width lines are wrapped to if one is set.
+    #[must_use]
+    pub fn get_wrap_width(&self) -> Option<usize> {
+        self.settings.wrap_width
+    }
+
+    /// Set if context lines are wrapped with [`LongLineMode::Wrap`] as well.
+    /// When false context lines are cut off at the width instead. Default is
+    /// true.
+    pub fn set_wrap_context_lines(&mut self, wrap_context_lines: bool) -> &mut Self {
+        self.settings.wrap_context_lines = wrap_context_lines;
+        self
+    }
+
+    /// Get if context lines are wrapped with [`LongLineMode::Wrap`].
+    #[must_use]
+    pub fn get_wrap_context_lines(&self) -> bool {
+        self.settings.wrap_context_lines
+    }
+
+    /// Set the maximum width of the message next to the caret. Longer messages
+    /// are wrapped between words with the following lines aligned below the
+    /// start of the message. Text quoted in backticks is kept together.
+    pub fn set_max_message_width(&mut self, width: usize) -> &mut Self {
+        self.settings.max_message_width = Some(MaxMessageWidth::Fixed(width));
+        self
+    }
+
+    /// Wrap the message next to the caret so it ends at the edge of the
+    /// terminal. See [`MaxMessageWidth::Terminal`].
+    pub fn set_max_message_width_terminal(&mut self) -> &mut Self {
+        self.settings.max_message_width = Some(MaxMessageWidth::Terminal);
+        self
+    }
+
+    /// Get the maximum width of the message next to the caret if one is set.
+    #[must_use]
+    pub fn get_max_message_width(&self) -> Option<MaxMessageWidth> {
+        self.settings.max_message_width
+    }
+
+    /// Set the severity of the error. Default is [`Severity::Error`].
+    pub fn set_severity(&mut self, severity: Severity) -> &mut Self {
+        self.severity = severity;
+        self
+    }
+
+    /// Get the severity of the error.
+    #[must_use]
+    pub fn get_severity(&self) -> Severity {
+        self.severity
+    }
+
+    /// Get the category of the error if it is known.
+    #[must_use]
+    pub fn category(&self) -> Option<Category> {
+        self.category
+    }
+
+    /// Get the kind of failure of the error. The kind is taken from the
+    /// category of `serde_json` errors and guessed from the message for all
+    /// other errors. Allows to decide between retrying and aborting without
+    /// matching on the message.
+    ///
+    /// ```rust
+    /// use format_serde_error::{
+    ///     ErrorKind,
+    ///     SerdeError,
+    /// };
+    ///
+    /// let err = SerdeError::new(
+    ///     "a: b".to_string(),
+    ///     ("invalid type: string \"b\", expected u32".into(), Some(1), Some(3)),
+    /// );
+    ///
+    /// assert_eq!(ErrorKind::Data, err.kind());
+    /// ```
+    #[must_use]
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// Get the format adapter that produced the error, for example to tag
+    /// parse failures by format in metrics.
+    ///
+    /// ```rust
+    /// use format_serde_error::{
+    ///     ErrorAdapter,
+    ///     SerdeError,
+    /// };
+    ///
+    /// let err = SerdeError::new("a: b".to_string(), ("error".into(), Some(1), Some(3)));
+    ///
+    /// assert_eq!(ErrorAdapter::Custom, err.error_kind());
+    /// ```
+    #[must_use]
+    pub fn error_kind(&self) -> ErrorAdapter {
+        self.adapter
+    }
+
+    /// Get the error line as it was shown the last time the error was
+    /// rendered: the shown part of the line, the column of the error in it and
+    /// if an ellipse was shown before and after it because the line was
+    /// shortened. Returns [`None`] if the error was not rendered yet or the
+    /// error line was not shown, for example because the error has no line or
+    /// the line was wrapped.
+    ///
+    /// ```rust
+    /// use format_serde_error::SerdeError;
+    ///
+    /// let input = format!("key: {}", "x".repeat(100));
+    /// let mut err = SerdeError::new(input, ("error".into(), Some(1), Some(50)));
+    /// err.set_context_characters(5);
+    ///
+    /// assert_eq!(None, err.last_rendered_error_line());
+    ///
+    /// let _ = err.to_string();
+    ///
+    /// assert_eq!(
+    ///     Some(("xxxxxxxxxxx".to_string(), 6, true, true)),
+    ///     err.last_rendered_error_line()
+    /// );
+    /// ```
+    #[must_use]
+    pub fn last_rendered_error_line(&self) -> Option<(String, usize, bool, bool)> {
+        self.last_rendered_error_line
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Get the message of the error.
+    #[must_use]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Replace the message of the error with the result of the closure, for
+    /// example to add context of the application. The location of the error
+    /// stays the same.
+    ///
+    /// ```rust
+    /// use format_serde_error::SerdeError;
+    ///
+    /// let err = SerdeError::new("a: b".to_string(), ("invalid type".into(), Some(1), Some(3)))
+    ///     .map_message(|message| format!("while loading tenant config: {message}"));
+    ///
+    /// assert_eq!("while loading tenant config: invalid type", err.message());
+    /// ```
+    #[must_use]
+    pub fn map_message(mut self, f: impl FnOnce(String) -> String) -> Self {
+        self.message = f(std::mem::take(&mut self.message));
+        self
+    }
+
+    /// Put the prefix in front of the message of the error. See
+    /// [`SerdeError::map_message`].
+    pub fn prepend_message(&mut self, prefix: &str) -> &mut Self {
+        self.message.insert_str(0, prefix);
+        self
+    }
+
+    /// Get the line the error occurred at if it is known.
+    #[must_use]
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+
+    /// Get the column the error occurred at in the input if it is known. The
+    /// column is not changed by rendering the error, even if the shown line
+    /// is shortened.
+    #[must_use]
+    pub fn column(&self) -> Option<usize> {
+        self.column
+    }
+
+    /// Get the position of the error with its byte offset in the input.
+    /// Returns [`None`] if the line or the column is not known or the position
+    /// is not in the input.
+    ///
+    /// ```rust
+    /// use format_serde_error::{
+    ///     Location,
+    ///     SerdeError,
+    /// };
+    ///
+    /// let err = SerdeError::new("a: b\nc: d".to_string(), ("error".into(), Some(2), Some(3)));
+    ///
+    /// assert_eq!(
+    ///     Some(Location {
+    ///         line: 2,
+    ///         column: 3,
+    ///         byte_offset: 8,
+    ///     }),
+    ///     err.location()
+    /// );
+    /// ```
+    #[must_use]
+    pub fn location(&self) -> Option<Location> {
+        Location::from_line_column(self.input.text(), self.line?, self.column?)
+    }
+
+    /// Print the error to stdout and flush it afterwards. The output honors
+    /// the current coloring mode.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to or flushing stdout fails.
+    pub fn print(&self) -> io::Result<()> {
+        let mut stdout = io::stdout().lock();
+        write!(stdout, "{self}")?;
+        stdout.flush()
+    }
+
+    /// Print the error to stderr and flush it afterwards. The output honors
+    /// the current coloring mode.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to or flushing stderr fails.
+    pub fn eprint(&self) -> io::Result<()> {
+        let mut stderr = io::stderr().lock();
+        write!(stderr, "{self}")?;
+        stderr.flush()
+    }
+
+    /// Check if rendering the error right now would emit colors. Takes the
+    /// coloring mode set with [`set_coloring_mode`] into account and falls
+    /// back to the environment like [`colored`] does. Always false in the
+    /// browser (`wasm32-unknown-unknown`) where colors can't be shown.
+    #[cfg(feature = "colored")]
+    #[must_use]
+    pub fn will_colorize(&self) -> bool {
+        !cfg!(all(target_arch = "wasm32", target_os = "unknown")) && paint::should_colorize()
+    }
+
+    /// Render the error only if its location is known and fits the input.
+    /// Returns [`None`] instead of falling back to the plain message so
+    /// callers can handle errors without a location themselves.
+    #[must_use]
+    pub fn try_render(&self) -> Option<String> {
+        let annotated = Annotated::new(self);
+
+        if annotated.line.is_none()
+            || checked::check_location(&self.input, annotated.line, annotated.column).is_err()
+        {
+            return None;
+        }
+
+        Some(self.to_string())
+    }
+
+    /// Render the error like [`fmt::Display`] but return an error instead of
+    /// falling back to the plain message if the location of the error doesn't
+    /// fit the input, for example if the column is behind the end of the line.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`FormatError`] describing the inconsistency if the line or
+    /// column of the error is outside of the input.
+    pub fn format_checked(&self) -> Result<String, FormatError> {
+        let annotated = Annotated::new(self);
+        checked::check_location(&self.input, annotated.line, annotated.column)?;
+
+        let mut rendered = String::new();
+        fmt::Write::write_fmt(&mut rendered, format_args!("{self}"))?;
+
+        Ok(rendered)
+    }
+
+    /// Render the error into bytes, for example for golden file tests or to
+    /// send it over the wire. Colors are switched on or off with `color` for
+    /// this render only, independent of the global coloring mode. Without
+    /// `color` all escape sequences are removed. Without the `colored` feature
+    /// the output never has colors.
+    #[must_use]
+    pub fn render_bytes(&self, color: bool) -> Vec<u8> {
+        #[cfg(feature = "colored")]
+        let rendered = paint::with_colors(color, || self.to_string());
+
+        #[cfg(not(feature = "colored"))]
+        let rendered = self.to_string();
+
+        if color {
+            rendered.into_bytes()
+        } else {
+            ansi::strip(&rendered).into_owned().into_bytes()
+        }
+    }
+
+    /// Render the error for humans and as structured JSON in one call, for
+    /// example to print it and write it to a file for CI tools. Both are
+    /// computed from the same location and shown lines. Lines start at 1 and
+    /// columns are in characters starting at 0. The location is `null` if it
+    /// is unknown or doesn't fit the input.
+    ///
+    /// ```rust
+    /// use format_serde_error::SerdeError;
+    ///
+    /// let err = SerdeError::new("port: x".to_string(), ("invalid type".into(), Some(1), Some(6)));
+    /// let (human, json) = err.render_dual();
+    ///
+    /// assert!(human.contains("invalid type"));
+    /// assert_eq!(json["line"], 1);
+    /// assert_eq!(json["column"], 6);
+    /// ```
+    #[cfg(feature = "serde_json")]
+    #[must_use]
+    pub fn render_dual(&self) -> (String, serde_json::Value) {
+        let human = self.to_string();
+        let annotated = Annotated::new(self);
+
+        let (line, column) =
+            match checked::check_location(&self.input, annotated.line, annotated.column) {
+                Ok(()) => (annotated.line, annotated.column),
+                Err(_) => (None, None),
+            };
+
+        let lines = line.map_or_else(Vec::new, |line| {
+            self.shown_lines(line)
+                .into_iter()
+                .map(|(line, text)| serde_json::json!({ "line": line, "text": text }))
+                .collect()
+        });
+
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+
+        let kind = match self.kind {
+            ErrorKind::Io => "io",
+            ErrorKind::Syntax => "syntax",
+            ErrorKind::Data => "data",
+            ErrorKind::Eof => "eof",
+            ErrorKind::Unknown => "unknown",
+        };
+
+        let adapter = match self.adapter {
+            ErrorAdapter::Json => "json",
+            ErrorAdapter::Yaml => "yaml",
+            ErrorAdapter::Toml => "toml",
+            ErrorAdapter::Csv => "csv",
+            ErrorAdapter::Custom => "custom",
+        };
+
+        let labels = annotated
+            .labels
+            .iter()
+            .map(|label| {
+                serde_json::json!({
+                    "line": label.line,
+                    "column": label.column,
+                    "message": label.message,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let notes = annotated
+            .notes
+            .iter()
+            .map(|note| {
+                let kind = match note.kind {
+                    enhance::NoteKind::Help => "help",
+                    enhance::NoteKind::Note => "note",
+                };
+
+                serde_json::json!({ "kind": kind, "message": note.message })
+            })
+            .collect::<Vec<_>>();
+
+        let structured = serde_json::json!({
+            "message": self.cleaned_message(),
+            "severity": severity,
+            "kind": kind,
+            "adapter": adapter,
+            "line": line,
+            "column": column,
+            "lines": lines,
+            "labels": labels,
+            "notes": notes,
+        });
+
+        (human, structured)
+    }
+
+    /// Render the error and list the decisions made for the error line before
+    /// the output without colors, for example to find out why a caret is not
+    /// where it is expected. The values are the computed columns, the amount
+    /// of removed indentation, if and where the line was shortened and the
+    /// column the caret is printed at. The format of the list is meant for
+    /// humans and may change.
+    ///
+    /// ```rust
+    /// use format_serde_error::SerdeError;
+    ///
+    /// let input = format!("key: {}", "x".repeat(100));
+    /// let mut err = SerdeError::new(input, ("error".into(), Some(1), Some(50)));
+    /// err.set_context_characters(5);
+    ///
+    /// let debug = err.render_debug();
+    ///
+    /// assert!(debug.contains("layout: shortened\n"));
+    /// assert!(debug.contains("skip: 44\n"));
+    /// ```
+    #[must_use]
+    pub fn render_debug(&self) -> String {
+        let (rendered, trace) = self.traced();
+
+        format!("{}\n{}", trace, ansi::strip(&rendered))
+    }
+
+    /// Render the error and collect the decisions made while rendering it.
+    fn traced(&self) -> (String, RenderTrace) {
+        let traced = Traced {
+            error: self,
+            tracer: RefCell::new(Tracer::on()),
+        };
+        let rendered = traced.to_string();
+
+        (rendered, traced.tracer.into_inner().finish())
+    }
+
+    /// Render the error without colors inside a Markdown code block, for
+    /// example for issue trackers. The name of the format is used as the
+    /// language of the code block if it is known. The fence is made longer
+    /// than any run of backticks in the output so it can't be closed early.
+    #[must_use]
+    pub fn to_markdown(&self) -> String {
+        let rendered = self.to_string();
+        let rendered = ansi::strip(&rendered);
+        let rendered = rendered.trim_start_matches('\n');
+
+        let longest_backticks = rendered
+            .split(|c| c != '`')
+            .map(str::len)
+            .max()
+            .unwrap_or_default();
+        let fence = "`".repeat((longest_backticks + 1).max(3));
+
+        format!(
+            "{}{}\n{}{}\n",
+            fence,
+            self.language.unwrap_or_default(),
+            rendered,
+            fence
+        )
+    }
+
+    /// Render the error without colors and without whitespace at the end of
+    /// the lines. Allows comparing the output in tests independent of the
+    /// current coloring mode.
+    #[cfg(feature = "testing")]
+    #[must_use]
+    pub fn render_normalized(&self) -> String {
+        ansi::strip(&self.to_string())
+            .lines()
+            .flat_map(|line| [line.trim_end(), "\n"])
+            .collect()
+    }
+
+    /// Render the error into the given formatter. This is the same output the
+    /// [`fmt::Display`] implementation produces and can be used by wrapper
+    /// types that want to embed the snippet in their own
+    /// [`fmt::Display`] implementation.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to the formatter fails.
+    pub fn render(&self, f: &mut fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        self.render_traced(f, &mut Tracer::off())
+    }
+
+    /// Render the error and record the decisions made while rendering the
+    /// error line with the tracer.
+    fn render_traced(&self, f: &mut fmt::Formatter<'_>, tracer: &mut Tracer) -> fmt::Result {
+        let annotated = Annotated::new(self);
+        let result = self.render_annotated(f, &annotated, tracer);
+
+        *self
+            .last_rendered_error_line
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = tracer.take_shown();
+
+        result
+    }
+
+    /// Render the error with the annotations that were added to it.
+    fn render_annotated(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        annotated: &Annotated,
+        tracer: &mut Tracer,
+    ) -> fmt::Result {
+        // A location that doesn't fit the input can't be shown in an excerpt
+
+        if checked::check_location(&self.input, annotated.line, annotated.column).is_err() {
+            self.format_message_only(f, annotated)?;
+            return self.format_notes(f, annotated);
+        }
+
+        // Colors can still be forced with the coloring mode in the browser so
+        // they have to be removed afterwards
+        #[cfg(all(feature = "colored", target_arch = "wasm32", target_os = "unknown"))]
+        if paint::should_colorize() {
+            let rendered = Styled {
+                error: self,
+                annotated,
+                tracer: RefCell::new(&mut *tracer),
+            }
+            .to_string();
+
+            return self.write_indented(f, &ansi::strip(&rendered));
+        }
+
+        if self.settings.block_indent == 0 {
+            return self.render_styled(f, annotated, tracer);
+        }
+
+        let rendered = Styled {
+            error: self,
+            annotated,
+            tracer: RefCell::new(tracer),
+        }
+        .to_string();
+
+        self.write_indented(f, &rendered)
+    }
+
+    /// Write the rendered error with every line indented by the block indent.
+    fn write_indented(&self, f: &mut fmt::Formatter<'_>, rendered: &str) -> fmt::Result {
+        let indent = " ".repeat(self.settings.block_indent);
+
+        rendered
+            .split_inclusive('\n')
+            .try_for_each(|line| write!(f, "{indent}{line}"))
+    }
+
+    /// Render the error with colors if the coloring mode allows them.
+    fn render_styled(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        annotated: &Annotated,
+        tracer: &mut Tracer,
+    ) -> Result<(), std::fmt::Error> {
+        if self.settings.render_style == RenderStyle::Minimal {
+            return writeln!(f, "{}", self.minimal());
+        }
+
+        if self.settings.render_style == RenderStyle::Assertion {
+            return writeln!(f, "{}", self.assertion());
+        }
+
+        self.format(f, annotated, tracer)?;
+        self.format_notes(f, annotated)?;
+
+        #[cfg(feature = "backtrace")]
+        self.format_backtrace(f)?;
+
+        Ok(())
+    }
+
+    /// Backtrace of the creation of the error below the notes if it was
+    /// captured and is enabled.
+    #[cfg(feature = "backtrace")]
+    fn format_backtrace(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.settings.show_backtrace
+            || self.backtrace.status() != std::backtrace::BacktraceStatus::Captured
+        {
+            return Ok(());
+        }
+
+        writeln!(f)?;
+        writeln!(f, "{}", self.backtrace)
+    }
+
+    fn format(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        annotated: &Annotated,
+        tracer: &mut Tracer,
+    ) -> Result<(), std::fmt::Error> {
+        // If the line is not set we assume that we can't make a nice output so we
+        // will just print the original message in red and bold. A column alone
+        // doesn't tell which line to show.
+        if annotated.line.is_none() {
+            return self.format_message_only(f, annotated);
+        }
+
+        let error_line = annotated.line.unwrap_or_default();
+        let error_text = self.input.line(error_line);
+
+        // Tabs get expanded to spaces before printing so the column has to be
+        // moved by the same amount of characters the tabs in front of it expand to.
+        // Without a column the error starts at the first character of the line that
+        // is not whitespace.
+        let error_column = match (error_text, annotated.column) {
+            (Some(text), None) if annotated.line.is_some() && self.settings.show_whitespace => {
+                self.expand_column(text, text.chars().take_while(|c| c.is_whitespace()).count())
+            }
+            (Some(text), None) if annotated.line.is_some() => self
+                .expand_tabs(text)
+                .chars()
+                .take_while(|c| c.is_whitespace())
+                .count(),
+            (Some(text), column) => self.expand_column(text, column.unwrap_or_default()),
+            (None, column) => column.unwrap_or_default(),
+        };
+
+        // Lines are prepared in the same order for the error line and the context
+        // lines: tabs are expanded first, then the common indentation is removed
+        // and the error line is shortened last, around the column in the
+        // dedented text. Columns stay relative to the line with expanded tabs
+        // until they are printed.
+
+        // Minimize the input to only what we need so we can reuse it without
+        // having to iterate over the whole input again.
+        // Also expand tabs to spaces
+        let minimized_input = self
+            .shown_lines(error_line)
+            .into_iter()
+            .map(|(line, text)| (line, self.expand_tabs(text)))
+            .collect::<Vec<_>>();
+
+        // If the minimized_input is empty we can assume that the input was empty as
+        // well. In that case we can't make a nice output so we will just print
+        // the original message in red and bold
+        if minimized_input.is_empty() {
+            return self.format_message_only(f, annotated);
+        }
+
+        // To reduce the amount of space text takes we want to remove unnecessary
+        // whitespace in front of the text.
+        // Find the line with the least amount of whitespace in front and use
+        // that to remove the whitespace later.
+        // We basically want to find the least indented line.
+        // We cant just use trim as that would remove all whitespace and remove all
+        // indentation.
+        // The anchor line is shown in front of the window if enabled and has to
+        // be considered when removing the whitespace as well
+        let anchor = self.shown_anchor_line(error_line).and_then(|line| {
+            let text = self.input.line(line)?;
+            Some((line, self.expand_tabs(text)))
+        });
+
+        // Lines with labels that are outside of the window are shown on their
+        // own so the labels are not lost
+        let shown = self.with_label_lines(
+            annotated,
+            anchor.into_iter().chain(minimized_input).collect(),
+        );
+
+        let whitespace_count = shown
+            .iter()
+            .map(|(_, line)| {
+                line.chars()
+                    .take_while(|c| self.settings.indentation_whitespace.is_indentation(*c))
+                    .count()
+            })
+            .min()
+            .unwrap_or_default();
+
+        tracer
+            .record(|trace| *trace = RenderTrace::new(error_line, error_column, whitespace_count));
+
+        // Single lines are shown without a gutter
+        let (separator, annotation_separator) = if self.unframed() {
+            (styled_separator(""), styled_separator(""))
+        } else {
+            (
+                styled_separator(SEPARATOR),
+                styled_separator(&self.annotation_separator()),
+            )
+        };
+
+        // When we don't print the line_position we want to fill up the space not used
+        // by the line_position with whitespace instead. If line numbers are disabled
+        // there is no space to fill up.
+        let line_number_width = if self.settings.show_line_numbers && !self.unframed() {
+            self.line_number_width(error_line)
+        } else {
+            0
+        };
+
+        // Byte offsets of the lines are shown behind the line numbers and have to be
+        // filled up as well
+        let offset_digits = self.offset_digits(error_line);
+        let fill_line_position = self.gutter_fill(line_number_width, offset_digits);
+
+        let message = self.caret_message(annotated);
+
+        // Want to avoid printing when we are not at the beginning of the line. For
+        // example anyhow will write 'Error:' in front of the output before
+        // printing the buffer. The compact style leaves that to the caller.
+        if self.settings.render_style != RenderStyle::Compact && !self.unframed() {
+            writeln!(f)?;
+        }
+
+        self.format_stats(f, annotated)?;
+
+        self.format_mapped_header(f, error_line, &fill_line_position)?;
+
+        // Also remove unnecessary whitespace in front of text
+        let mut format_line = |f: &mut fmt::Formatter<'_>, line_position: usize, text: &str| {
+            let labels = self.line_labels(annotated, line_position);
+            let gutter = self.gutter(line_position, error_line, line_number_width, offset_digits);
+
+            self.format_line(
+                f,
+                line_position,
+                error_line,
+                error_column,
+                annotated.column,
+                self.line_underline(annotated, line_position),
+                text.chars().skip(whitespace_count).collect::<String>(),
+                &message,
+                whitespace_count,
+                &separator,
+                &annotation_separator,
+                &fill_line_position,
+                &gutter,
+                &labels,
+                tracer,
+            )
+        };
+
+        // Lines that are left out between the anchor line, label lines and the
+        // window or between structural context lines are marked
+        let mut previous = None;
+
+        shown.iter().try_for_each(|(line_position, text)| {
+            if previous.is_some_and(|previous| previous + 1 < *line_position) {
+                self.format_omitted(f, &fill_line_position)?;
+            }
+            previous = Some(*line_position);
+
+            format_line(f, *line_position, text)
+        })
+    }
+
+    /// Add the lines with labels that are not part of the shown lines yet and
+    /// sort all lines by their position. The compact style only shows the
+    /// error line.
+    fn with_label_lines(
+        &self,
+        annotated: &Annotated,
+        mut shown: Vec<(usize, String)>,
+    ) -> Vec<(usize, String)> {
+        if self.settings.render_style != RenderStyle::Compact {
+            for line in annotated.labels.iter().map(|label| label.line) {
+                if shown.iter().any(|(shown, _)| *shown == line) {
+                    continue;
+                }
+
+                if let Some(text) = line
+                    .checked_sub(1)
+                    .and_then(|index| self.input.line(index + 1))
+                {
+                    shown.push((line, self.expand_tabs(text)));
+                }
+            }
+        }
+
+        shown.sort_by_key(|(line_position, _)| *line_position);
+        shown
+    }
+
+    /// Line of the top-level key the error line is nested under if it is shown
+    /// in front of the window. Lines that are already part of the window are
+    /// not shown twice.
+    fn shown_anchor_line(&self, error_line: usize) -> Option<usize> {
+        if !self.settings.show_anchor_line || !self.settings.contextualize {
+            return None;
+        }
+
+        let first = self
+            .shown_lines(error_line)
+            .first()
+            .map_or(error_line, |(line, _)| *line);
+
+        anchor::anchor_line(&self.input, error_line).filter(|line| *line < first)
+    }
+
+    /// Lines shown around the error line together with their line number
+    /// starting at 1 depending on the [`ContextMode`].
+    fn shown_lines(&self, error_line: usize) -> Vec<(usize, &str)> {
+        let lines = self
+            .input
+            .lines()
+            .enumerate()
+            .map(|(index, text)| (index + 1, text));
+
+        match self.settings.context_mode {
+            ContextMode::Lines => {
+                let (skip, take) = self.window(error_line);
+
+                lines.skip(skip).take(take).collect()
+            }
+
+            ContextMode::Structural => {
+                let ancestors =
+                    anchor::ancestor_lines(&self.input, error_line, self.context_lines());
+
+                lines
+                    .filter(|(line, _)| *line == error_line || ancestors.contains(line))
+                    .collect()
+            }
+        }
+    }
+
+    /// Marker for lines that are left out between shown lines. The end of the
+    /// marker is aligned with the separator.
+    fn format_omitted(&self, f: &mut fmt::Formatter<'_>, fill_line_position: &str) -> fmt::Result {
+        let width = fill_line_position.len() + 3;
+        let marker = format!(
+            "{: >width$}",
+            self.settings.labels.lines_omitted,
+            width = width
+        );
+
+        #[cfg(feature = "colored")]
+        return writeln!(f, "{}", marker.blue().bold().paint());
+
+        #[cfg(not(feature = "colored"))]
+        return writeln!(f, "{marker}");
+    }
+
+    /// Amount of lines to skip and to take from the input to get the lines
+    /// shown around the error line.
+    fn window(&self, error_line: usize) -> (usize, usize) {
+        Self::window_around(error_line, self.context_lines())
+    }
+
+    /// Amount of lines to skip and to take from the input to get the given
+    /// amount of context lines before and after the error line.
+    fn window_around(error_line: usize, context_lines: usize) -> (usize, usize) {
+        // Skip until we are amount of context lines before the error line (context)
+        // plus the line with the error ( + 1)
+        // Saturating sub if the error is in the first few line we can't take more
+        // context
+        let skip = usize::saturating_sub(error_line, context_lines + 1);
+
+        // Take lines before and after (context * 2) plus the line with the error ( + 1)
+        let take = context_lines * 2 + 1;
+
+        (skip, take)
+    }
+
+    /// Amount of lines to show before and after the error line. The compact
+    /// style only shows the error line itself.
+    fn context_lines(&self) -> usize {
+        match self.settings.render_style {
+            RenderStyle::Compact => 0,
+            RenderStyle::Full | RenderStyle::Minimal | RenderStyle::Assertion => {
+                self.settings.context_lines
+            }
+        }
+    }
+
+    /// Separator in front of carets and labels padded to the width of the
+    /// separator in front of the lines.
+    fn annotation_separator(&self) -> String {
+        let separator = &self.settings.annotation_separator;
+        let padding = SEPARATOR
+            .chars()
+            .count()
+            .saturating_sub(separator.chars().count());
+
+        format!(
+            "{: >before$}{}{: >after$}",
+            "",
+            separator,
+            "",
+            before = padding / 2,
+            after = padding - padding / 2
+        )
+    }
+
+    /// Header with the original file and line of the error line if a source
+    /// map is set and knows the line.
+    fn format_mapped_header(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        error_line: usize,
+        fill_line_position: &str,
+    ) -> fmt::Result {
+        let Some((path, line)) = self.mapped_line(error_line) else {
+            return Ok(());
+        };
+
+        let header = format!("{}:{}", path.display(), line);
+
+        #[cfg(feature = "colored")]
+        return writeln!(
+            f,
+            " {}{} {}",
+            fill_line_position,
+            "-->".blue().bold().paint(),
+            header
+        );
+
+        #[cfg(not(feature = "colored"))]
+        return writeln!(f, " {fill_line_position}--> {header}");
+    }
+
+    /// Line of the original file the line of the input came from if a source
+    /// map is set and knows the line.
+    fn mapped_line(&self, line: usize) -> Option<(PathBuf, usize)> {
+        self.source_map.as_ref().and_then(|map| (map.0)(line))
+    }
+
+    /// Line number shown in the gutter for the line of the input.
+    fn displayed_line(&self, line: usize) -> usize {
+        self.mapped_line(line).map_or(line, |(_, line)| line)
+    }
+
+    /// Line number formatted with the line number formatter if one is set.
+    fn format_line_number(&self, line: usize) -> String {
+        match &self.line_number_formatter {
+            Some(formatter) => (formatter.0)(line),
+            None => line.to_string(),
+        }
+    }
+
+    /// Line number shown in the gutter in front of the line. With absolute
+    /// numbering only the error line shows its number while relative numbering
+    /// shows the distance to the error line for every line.
+    fn line_number(&self, line_position: usize, error_line: usize) -> String {
+        if !self.settings.show_line_numbers || self.unframed() {
+            return String::new();
+        }
+
+        match self.settings.line_numbering {
+            LineNumbering::Absolute if line_position == error_line => {
+                self.format_line_number(self.displayed_line(line_position))
+            }
+            LineNumbering::Absolute => String::new(),
+            LineNumbering::Relative if line_position < error_line => {
+                format!("-{}", error_line - line_position)
+            }
+            LineNumbering::Relative if line_position > error_line => {
+                format!("+{}", line_position - error_line)
+            }
+            LineNumbering::Relative => "0".to_string(),
+        }
+    }
+
+    /// Width of the widest line number shown in front of the lines around the
+    /// error line.
+    fn line_number_width(&self, error_line: usize) -> usize {
+        match self.settings.line_numbering {
+            LineNumbering::Absolute => self
+                .format_line_number(self.displayed_line(error_line))
+                .chars()
+                .count(),
+            LineNumbering::Relative => {
+                let shown = self.shown_lines(error_line);
+
+                shown
+                    .first()
+                    .iter()
+                    .chain(shown.last().iter())
+                    .map(|(line, _)| line)
+                    .chain([error_line].iter())
+                    .chain(self.shown_anchor_line(error_line).iter())
+                    .map(|line| self.line_number(*line, error_line).len())
+                    .max()
+                    .unwrap_or_default()
+            }
+        }
+    }
+
+    /// Label in the gutter in front of the error line.
+    fn line_label(&self, line_position: usize) -> String {
+        let line_label = self.line_number(line_position, line_position);
+
+        // Terminals that don't understand hyperlinks would show the escape
+        // sequences so they are only used together with colors
+        #[cfg(feature = "colored")]
+        let line_label = match self.hyperlink() {
+            Some(target) if !line_label.is_empty() && self.will_colorize() => {
+                hyperlink::wrap(&line_label, &target)
+            }
+            _ => line_label,
+        };
+
+        line_label
+    }
+
+    /// Secondary labels on the line with their columns moved by the tab
+    /// expansion like the error column.
+    fn line_labels<'a>(
+        &self,
+        annotated: &'a Annotated,
+        line_position: usize,
+    ) -> Vec<(usize, &'a str)> {
+        let raw = self.input.line(line_position).unwrap_or_default();
+
+        annotated
+            .labels
+            .iter()
+            .filter(|label| label.line == line_position)
+            .map(|label| {
+                (
+                    self.expand_column(raw, label.column),
+                    label.message.as_str(),
+                )
+            })
+            .collect()
+    }
+
+    /// Part of the line that is underlined with the columns moved by the tab
+    /// expansion.
+    fn line_underline(
+        &self,
+        annotated: &Annotated,
+        line_position: usize,
+    ) -> Option<(usize, usize)> {
+        let raw = self.input.line(line_position).unwrap_or_default();
+
+        annotated.span.and_then(|span| {
+            span.columns_on(line_position, raw)
+                .map(|(start, end)| (self.expand_column(raw, start), self.expand_column(raw, end)))
+        })
+    }
+
+    /// Label in the gutter in front of a line with the line number aligned to
+    /// the right.
+    fn gutter(
+        &self,
+        line_position: usize,
+        error_line: usize,
+        line_number_width: usize,
+        offset_digits: usize,
+    ) -> String {
+        let offset_label = self.offset_label(line_position, offset_digits);
+
+        // The label of the error line can contain a hyperlink so the padding is
+        // based on the line number itself
+        let number = self.line_number(line_position, error_line);
+        let padding = line_number_width.saturating_sub(number.chars().count());
+        let number = if line_position == error_line {
+            self.line_label(line_position)
+        } else {
+            number
+        };
+
+        format!(
+            "{: >padding$}{}{}{}",
+            "",
+            number,
+            offset_label,
+            self.status_label(line_position),
+            padding = padding
+        )
+    }
+
+    /// Spaces as wide as the gutter in front of lines without a number.
+    fn gutter_fill(&self, line_number_width: usize, offset_digits: usize) -> String {
+        let width = line_number_width
+            + self.offset_label(1, offset_digits).len()
+            + self.status_label(1).chars().count();
+
+        format!("{: >width$}", "")
+    }
+
+    /// Status glyph of the line shown in the gutter behind the number. Empty
+    /// if no statuses are set.
+    fn status_label(&self, line: usize) -> String {
+        if self.line_statuses.is_empty() {
+            return String::new();
+        }
+
+        format!(" {}", self.line_statuses.get(&line).unwrap_or(&' '))
+    }
+
+    /// Amount of hex digits needed for the byte offsets of the lines shown
+    /// around the error line or 0 if byte offsets are not shown.
+    fn offset_digits(&self, error_line: usize) -> usize {
+        if !self.settings.show_byte_offsets {
+            return 0;
+        }
+
+        // Offsets only grow so the last shown line has the widest offset
+        let last = self
+            .shown_lines(error_line)
+            .last()
+            .map_or(error_line, |(line, _)| *line);
+
+        format!(
+            "{:x}",
+            enhance::byte_offset(self.input.text(), last, 0).unwrap_or_default()
+        )
+        .len()
+    }
+
+    /// Byte offset of the start of the line in the original input shown in the
+    /// gutter behind the line number. Empty if byte offsets are not shown.
+    fn offset_label(&self, line: usize, digits: usize) -> String {
+        if digits == 0 {
+            return String::new();
+        }
+
+        let offset = enhance::byte_offset(self.input.text(), line, 0).unwrap_or_default();
+
+        format!(" @0x{offset:0digits$x}")
+    }
+
+    /// Space left for the text of a line behind the gutter when the report has
+    /// to fit into the given width.
+    fn text_width(width: usize, fill_line_position: &str) -> usize {
+        width
+            .saturating_sub(1 + fill_line_position.len() + SEPARATOR.chars().count())
+            .max(1)
+    }
+
+    /// How lines are wrapped if they are wrapped at all. A viewport width wraps
+    /// every line while [`LongLineMode::Wrap`] marks the continuation of lines
+    /// in the gutter. Lines are shortened instead if the width of the terminal
+    /// is needed but unknown.
+    fn wrapping(&self, fill_line_position: &str) -> Option<Wrapping> {
+        if let Some(width) = self.settings.viewport_width {
+            return Some(Wrapping {
+                width: Self::text_width(width, fill_line_position),
+                continuation: fill_line_position.to_string(),
+                context: true,
+            });
+        }
+
+        match self.settings.long_line_mode {
+            LongLineMode::Truncate => None,
+            LongLineMode::Wrap => {
+                let width = self.settings.wrap_width.or_else(reflow::terminal_width)?;
+
+                Some(Wrapping {
+                    width: Self::text_width(width, fill_line_position),
+                    continuation: format!(
+                        "{: >fill$}",
+                        CONTINUATION,
+                        fill = fill_line_position.len()
+                    ),
+                    context: self.settings.wrap_context_lines,
+                })
+            }
+        }
+    }
+
+    /// Returns true if the error is about the input ending early and is
+    /// marked at the end of the last line. See [`SerdeError::set_eof_marker`].
+    pub(crate) fn ends_early(&self) -> bool {
+        self.settings.eof_marker && self.kind == ErrorKind::Eof
+    }
+
+    /// Marker below the given amount of characters of the error line. Errors
+    /// about the input ending early are marked with a single [`EOF_MARKER`].
+    fn marks(&self, width: usize) -> String {
+        if self.ends_early() {
+            format!("{EOF_MARKER}{}", " ".repeat(width.saturating_sub(1)))
+        } else {
+            "^".repeat(width)
+        }
+    }
+
+    /// If the error is marked with a box around a span of the given width.
+    /// Spans of a single character and messages in front of the caret are
+    /// marked with carets. See [`MarkerStyle::Box`].
+    fn boxed(&self, width: usize) -> bool {
+        self.settings.marker_style == MarkerStyle::Box
+            && width > 1
+            && self.settings.text_direction == TextDirection::Ltr
+            && !self.ends_early()
+    }
+
+    /// Message shown next to the caret including the enabled additions.
+    fn caret_message<'a>(&'a self, annotated: &Annotated) -> Cow<'a, str> {
+        let offset = match (annotated.line, annotated.column) {
+            (Some(line), Some(column)) if self.settings.show_byte_offset => {
+                enhance::byte_offset(self.input.text(), line, column)
+            }
+            _ => None,
+        };
+
+        let message = self.cleaned_message();
+
+        let message = match offset {
+            Some(offset) => Cow::Owned(format!(
+                "{} ({} {})",
+                message, self.settings.labels.byte, offset
+            )),
+            None => message,
+        };
+
+        let message = if self.ends_early() {
+            Cow::Owned(format!(
+                "{}: {}",
+                self.settings.labels.expected_more, message
+            ))
+        } else {
+            message
+        };
+
+        let message = if self.settings.show_total_lines {
+            Cow::Owned(format!(
+                "{} ({} {} {})",
+                message,
+                self.settings.labels.of,
+                stats::thousands(self.input_stats().lines()),
+                self.settings.labels.lines
+            ))
+        } else {
+            message
+        };
+
+        // The column is taken before tabs are expanded and the line is shortened
+        // so it matches the column in the input
+        match annotated.column {
+            Some(column) if self.settings.show_column_number => {
+                Cow::Owned(self.with_column_number(&message, column))
+            }
+            _ => message,
+        }
+    }
+
+    /// Returns true if the column of the error is already part of the message,
+    /// either shown with [`SerdeError::set_show_column_number`] or written like
+    /// `column 12` by the parser.
+    fn shows_column(&self) -> bool {
+        self.settings.show_column_number
+            || self
+                .message
+                .split("column ")
+                .skip(1)
+                .any(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+    }
+
+    /// Message with the column of the error in front of it like
+    /// `(column 12) message`. The column starts at 0 and is shown starting at
+    /// 1.
+    fn with_column_number(&self, message: &str, column: usize) -> String {
+        format!(
+            "({} {}) {}",
+            self.settings.labels.column,
+            column + 1,
+            message
+        )
+    }
+
+    /// Single line with the position and the message of the error like
+    /// `config.yaml:2:9: invalid type`. The position serde appends to the
+    /// message is removed as it is already part of the prefix. Without a path
+    /// the position is written as `line 2 column 9`.
+    fn minimal(&self) -> String {
+        let message = self.displayed_message();
+        let message = enhance::strip_position_suffix(&message);
+        let annotated = Annotated::new(self);
+
+        let Some(line) = annotated.line else {
+            return message.to_string();
+        };
+
+        let column = annotated.column.unwrap_or_default() + 1;
+
+        match self.mapped_line(line) {
+            Some((path, line)) => format!("{}:{line}:{column}: {message}", path.display()),
+            None => match &self.path {
+                Some(path) => format!("{}:{line}:{column}: {message}", path.display()),
+                None => format!("line {line} column {column}: {message}"),
+            },
+        }
+    }
+
+    /// Single line for [`RenderStyle::Assertion`].
+    fn assertion(&self) -> String {
+        let message = self.displayed_message();
+        let message = enhance::strip_position_suffix(&message);
+        let annotated = Annotated::new(self);
+
+        // serde_yaml prefixes the message with the path, e.g.
+        // `values[2]: invalid type`
+        let (path, message) = match message.split_once(": ") {
+            Some((path, rest)) if !path.contains(' ') && !path.is_empty() => {
+                (path.to_string(), rest)
+            }
+            _ => (self.json_path(&annotated), message),
+        };
+
+        // Type mismatches are written as the difference to what was expected
+        let summary = message
+            .strip_prefix("invalid type: ")
+            .and_then(|rest| rest.split_once(", expected "))
+            .map_or_else(
+                || message.to_string(),
+                |(found, expected)| {
+                    let expected = expected
+                        .strip_prefix("a ")
+                        .or_else(|| expected.strip_prefix("an "))
+                        .unwrap_or(expected);
+
+                    format!("expected {expected}, found {found}")
+                },
+            );
+
+        let path = if path.is_empty() {
+            String::new()
+        } else {
+            format!(" @ {path}")
+        };
+
+        let position = match annotated.line {
+            Some(line) => {
+                let column = annotated.column.unwrap_or_default() + 1;
+
+                match self
+                    .mapped_line(line)
+                    .or_else(|| Some((self.path.clone()?, line)))
+                {
+                    Some((path, line)) => format!(" ({}:{line}:{column})", path.display()),
+                    None => format!(" ({line}:{column})"),
+                }
+            }
+            None => String::new(),
+        };
+
+        format!("{summary}{path}{position}")
+    }
+
+    /// Path of keys and indices like `values[2]` to the JSON value at the
+    /// location of the error. Empty for other inputs and the top level.
+    fn json_path(&self, annotated: &Annotated) -> String {
+        if self.adapter != ErrorAdapter::Json {
+            return String::new();
+        }
+
+        let (Some(line), Some(column)) = (annotated.line, annotated.column) else {
+            return String::new();
+        };
+
+        let path = enhance::byte_offset(self.input.text(), line, column)
+            .and_then(|offset| locate::json_path_at(self.input.text(), offset))
+            .unwrap_or_default();
+
+        path.iter().fold(String::new(), |mut joined, segment| {
+            if segment.chars().all(|c| c.is_ascii_digit()) {
+                joined.push('[');
+                joined.push_str(segment);
+                joined.push(']');
+            } else {
+                if !joined.is_empty() {
+                    joined.push('.');
+                }
+                joined.push_str(segment);
+            }
+
+            joined
+        })
+    }
+
+    /// Message without escape sequences if stripping them is enabled.
+    fn plain_message(&self) -> Cow<'_, str> {
+        if self.settings.strip_ansi {
+            ansi::strip(&self.message)
+        } else {
+            Cow::Borrowed(&self.message)
+        }
+    }
+
+    /// Message shown to the user. This is the replacement of the message
+    /// rewriter if there is one or the message without escape sequences.
+    fn displayed_message(&self) -> Cow<'_, str> {
+        match self
+            .message_rewriter
+            .as_ref()
+            .and_then(|rewriter| (rewriter.0)(&self.message))
+        {
+            Some(message) => Cow::Owned(message),
+            None => self.plain_message(),
+        }
+    }
+
+    /// Message with the enabled cleanups applied. The original message is kept
+    /// in [`Self::message`].
+    fn cleaned_message(&self) -> Cow<'_, str> {
+        let message = self.displayed_message();
+
+        if !self.settings.strip_position_suffix {
+            return message;
+        }
+
+        match message {
+            Cow::Borrowed(message) => Cow::Borrowed(enhance::strip_position_suffix(message)),
+            Cow::Owned(message) => Cow::Owned(enhance::strip_position_suffix(&message).to_string()),
+        }
+    }
+
+    /// Lines of the message next to the caret. The message is wrapped if a
+    /// maximum width is set. `indent` is the amount of columns in front of the
+    /// message after the separator.
+    fn message_lines(&self, message: &str, indent: usize, fill_line_position: &str) -> Vec<String> {
+        let width = match self.settings.max_message_width {
+            Some(MaxMessageWidth::Fixed(width)) => width,
+            Some(MaxMessageWidth::Terminal) => match reflow::terminal_width() {
+                Some(columns) => columns.saturating_sub(
+                    1 + fill_line_position.len()
+                        + reflow::display_width(&self.annotation_separator())
+                        + indent,
+                ),
+                None => return vec![message.to_string()],
+            },
+            None => return vec![message.to_string()],
+        };
+
+        reflow::wrap(message, width)
+    }
+
+    /// Fallback when no snippet can be shown. Prints the placeholder for empty
+    /// inputs if one is set and the message.
+    fn format_message_only(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        annotated: &Annotated,
+    ) -> Result<(), std::fmt::Error> {
+        self.format_stats(f, annotated)?;
+
+        if let Some(placeholder) = self
+            .settings
+            .empty_input_placeholder
+            .as_ref()
+            .filter(|_| self.input.is_empty())
+        {
+            #[cfg(feature = "colored")]
+            writeln!(f, "{}", placeholder.dimmed().paint())?;
+
+            #[cfg(not(feature = "colored"))]
+            writeln!(f, "{placeholder}")?;
+        }
+
+        let message = match self.settings.viewport_width {
+            Some(width) => reflow::wrap(&self.cleaned_message(), width).join("\n"),
+            None => self.cleaned_message().to_string(),
+        };
+
+        #[cfg(feature = "colored")]
+        return writeln!(f, "{}", message.red().bold().paint());
+
+        #[cfg(not(feature = "colored"))]
+        return writeln!(f, "{message}");
+    }
+
+    /// Returns true if the input is a single line that is shown without the
+    /// empty line in front and without the gutter. See
+    /// [`SerdeError::set_frame_single_line`].
+    fn unframed(&self) -> bool {
+        !self.settings.frame_single_line
+            && self.input_stats().lines() == 1
+            && !self.settings.show_byte_offsets
+            && self.settings.long_line_mode == LongLineMode::Truncate
+            && self.line_statuses.is_empty()
+            && self.source_map.is_none()
+    }
+
+    /// Size of the input, computed on first use.
+    fn input_stats(&self) -> InputStats {
+        *self.stats.get_or_init(|| InputStats::new(&self.input))
+    }
+
+    /// Dimmed header with the size of the input and the position of the error
+    /// in it if enabled.
+    fn format_stats(&self, f: &mut fmt::Formatter<'_>, annotated: &Annotated) -> fmt::Result {
+        if !self.settings.show_stats {
+            return Ok(());
+        }
+
+        let header = self.input_stats().header(
+            &self.settings.labels,
+            self.severity,
+            annotated.line,
+            annotated.column,
+        );
+
+        #[cfg(feature = "colored")]
+        return writeln!(f, "{}", header.dimmed().paint());
+
+        #[cfg(not(feature = "colored"))]
+        return writeln!(f, "{header}");
+    }
+
+    fn format_notes(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        annotated: &Annotated,
+    ) -> Result<(), std::fmt::Error> {
+        // Notes are aligned with the separator of the lines above
+        let line = annotated.line.unwrap_or_default();
+        let line_number_width = if self.unframed() {
+            0
+        } else {
+            self.line_number_width(line)
+        };
+        let fill_line_position = self.gutter_fill(line_number_width, self.offset_digits(line));
+
+        if self.settings.show_raw_message {
+            let raw = format!(
+                " {} = {}: {}",
+                fill_line_position,
+                self.settings.labels.raw_message,
+                self.plain_message()
+            );
+
+            #[cfg(feature = "colored")]
+            writeln!(f, "{}", raw.dimmed().paint())?;
+
+            #[cfg(not(feature = "colored"))]
+            writeln!(f, "{raw}")?;
+        }
+
+        if self.settings.show_constructed_at {
+            let constructed_at = format!(
+                " {} = {} {}",
+                fill_line_position, self.settings.labels.constructed_at, self.constructed_at
+            );
+
+            #[cfg(feature = "colored")]
+            writeln!(f, "{}", constructed_at.dimmed().paint())?;
+
+            #[cfg(not(feature = "colored"))]
+            writeln!(f, "{constructed_at}")?;
+        }
+
+        annotated.notes.iter().try_for_each(|note| {
+            let note = note.render(&self.settings.labels);
+
+            // With a viewport width notes are wrapped and aligned behind the `=`
+            let lines = match self.settings.viewport_width {
+                Some(width) => {
+                    reflow::wrap(&note, width.saturating_sub(fill_line_position.len() + 4))
+                }
+                None => vec![note],
+            };
+
+            lines.iter().enumerate().try_for_each(|(index, line)| {
+                let marker = if index == 0 { "=" } else { " " };
+
+                #[cfg(feature = "colored")]
+                return writeln!(
+                    f,
+                    " {} {} {}",
+                    fill_line_position,
+                    marker.blue().bold().paint(),
+                    line.bold().paint()
+                );
+
+                #[cfg(not(feature = "colored"))]
+                return writeln!(f, " {fill_line_position} {marker} {line}");
+            })
+        })
+    }
+
+    /// Replace every tab in the text with `tab_width` spaces.
+    fn expand_tabs(&self, text: &str) -> String {
+        let text = if self.settings.strip_ansi {
+            ansi::strip(text)
+        } else {
+            Cow::Borrowed(text)
+        };
+
+        if !self.settings.show_whitespace {
+            return text.replace('\t', &" ".repeat(self.settings.tab_width));
+        }
+
+        text.chars().map(|c| self.shown_char(c)).collect()
+    }
+
+    /// Text a character of the input is shown as with
+    /// [`SerdeError::set_show_whitespace`]. Tabs take at least one column so
+    /// they stay visible.
+    fn shown_char(&self, c: char) -> Cow<'static, str> {
+        match c {
+            ' ' => Cow::Borrowed(WHITESPACE_SPACE),
+            '\t' => Cow::Owned(format!(
+                "{}{}",
+                WHITESPACE_TAB,
+                " ".repeat(self.settings.tab_width.saturating_sub(1))
+            )),
+            '\u{a0}'
+            | '\u{1680}'
+            | '\u{2000}'..='\u{200f}'
+            | '\u{2028}'..='\u{202f}'
+            | '\u{205f}'..='\u{2064}'
+            | '\u{3000}'
+            | '\u{feff}' => Cow::Owned(format!("⟨U+{:04X}⟩", u32::from(c))),
+            c => Cow::Owned(c.to_string()),
+        }
+    }
+
+    /// Map a column in the original text to the column in the text after tabs
+    /// have been expanded and escape sequences have been removed with
+    /// [`Self::expand_tabs`].
+    fn expand_column(&self, text: &str, column: usize) -> usize {
+        let (text, column) = if self.settings.strip_ansi {
+            (ansi::strip(text), ansi::strip_column(text, column))
+        } else {
+            (Cow::Borrowed(text), column)
+        };
+
+        if self.settings.show_whitespace {
+            let shown = text
+                .chars()
+                .take(column)
+                .map(|c| self.shown_char(c).chars().count())
+                .sum::<usize>();
+
+            return shown + column.saturating_sub(text.chars().count());
+        }
+
+        let tabs = text.chars().take(column).filter(|c| *c == '\t').count();
+
+        column + tabs * self.settings.tab_width.saturating_sub(1)
+    }
+
+    // TODO: Maybe make another internal struct for formatting instead of having
+    // this list of args.
+    #[allow(clippy::too_many_arguments, clippy::too_many_lines)]
+    fn format_line(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        line_position: usize,
+        error_line: usize,
+        error_column: usize,
+        input_column: Option<usize>,
+        underline: Option<(usize, usize)>,
+        text: String,
+        message: &str,
+        whitespace_count: usize,
+        separator: &Separator,
+        annotation_separator: &Separator,
+        fill_line_position: &str,
+        gutter: &str,
+        labels: &[(usize, &str)],
+        tracer: &mut Tracer,
+    ) -> Result<(), std::fmt::Error> {
+        let inline = if line_position == error_line {
+            self.inline_message(&text, message, gutter, underline, labels)
+        } else {
+            None
+        };
+
+        if let Some(message) = inline {
+            tracer.record(|trace| trace.layout = trace::Layout::Inline);
+            tracer.record_shown((text.clone(), error_column, false, false));
+
+            #[cfg(feature = "colored")]
+            return writeln!(
+                f,
+                " {}{}{}{}",
+                gutter.blue().bold().paint(),
+                separator,
+                self.highlight(&detach_leading_mark(&text), None),
+                format!("{INLINE_MARKER}{message}").red().bold().paint()
+            );
+
+            #[cfg(not(feature = "colored"))]
+            return writeln!(
+                f,
+                " {gutter}{separator}{}{INLINE_MARKER}{message}",
+                detach_leading_mark(&text)
+            );
+        }
+
+        if let Some(wrapping) = self.wrapping(fill_line_position) {
+            if line_position == error_line {
+                tracer.record(|trace| trace.layout = trace::Layout::Wrapped);
+            }
+
+            return self.format_line_reflowed(
+                f,
+                &wrapping,
+                line_position == error_line,
+                error_column,
+                underline,
+                &text,
+                message,
+                whitespace_count,
+                separator,
+                annotation_separator,
+                fill_line_position,
+                gutter,
+                labels,
+            );
+        }
+
+        if line_position == error_line {
+            let take = self.settings.context_characters * 2 + 1;
+            let line_length = text.len();
+            let long_line_threshold = take < line_length;
+
+            tracer.record(|trace| {
+                trace.line_length = line_length;
+                trace.long_line_threshold = take;
+            });
+
+            if self.settings.contextualize
+                && long_line_threshold
+                && self.settings.multiple_windows
+                && !labels.is_empty()
+            {
+                tracer.record(|trace| trace.layout = trace::Layout::Windows);
+
+                return self.format_windows(
+                    f,
+                    &text,
+                    error_column,
+                    message,
+                    whitespace_count,
+                    separator,
+                    annotation_separator,
+                    fill_line_position,
+                    gutter,
+                    labels,
+                    tracer,
+                );
+            }
+
+            // The text has already been dedented so the window is computed around
+            // the column in the dedented text. The column returned is moved back
+            // into the original line so it is handled like the other columns.
+            let (context_line, new_error_column, context_before, context_after) =
+                if self.settings.contextualize && long_line_threshold {
+                    let context_characters = self.settings.context_characters;
+                    let unit = self.settings.context_chars_unit;
+                    let dedented_column = error_column.saturating_sub(whitespace_count);
+                    let (context_line, column, context_before, context_after) =
+                        match self.settings.caret_anchor {
+                            Some(anchor) => Self::context_long_line_anchored(
+                                &text,
+                                dedented_column,
+                                context_characters,
+                                Self::anchored_before(anchor, context_characters),
+                                unit,
+                            ),
+                            None => Self::context_long_line(
+                                &text,
+                                dedented_column,
+                                context_characters,
+                                unit,
+                            ),
+                        };
+
+                    (
+                        context_line,
+                        error_column - (dedented_column - column),
+                        context_before,
+                        context_after,
+                    )
+                } else {
+                    (text, error_column, false, false)
+                };
+
+            // The caret doesn't point at the column of the error in the input
+            // anymore if the start of the line has been cut off so the column is
+            // shown in front of the message if the message doesn't tell it
+            let message = match input_column {
+                Some(column) if context_before && !self.shows_column() => {
+                    Cow::Owned(self.with_column_number(message, column))
+                }
+                _ => Cow::Borrowed(message),
+            };
+
+            tracer.record_shown((
+                context_line.clone(),
+                new_error_column,
+                context_before,
+                context_after,
+            ));
+
+            // Labels and underlines have to be moved by the same amount as the error
+            // column when the line has been shortened. Labels that are not visible
+            // anymore are skipped.
+            let shortened_by = error_column - new_error_column;
+            let ellipse_space = if context_before { ELLIPSE.len() } else { 0 };
+            let visible = context_line.chars().count();
+
+            // Underlines are cut off at the end of the visible part of the line
+            let (caret_column, underline) = match underline {
+                Some((start, end)) => {
+                    let start = start.saturating_sub(shortened_by);
+                    let end = end
+                        .saturating_sub(shortened_by)
+                        .min((whitespace_count + visible).saturating_sub(1));
+
+                    (start, (end + 1).saturating_sub(start).max(1))
+                }
+                None => (new_error_column, 1),
+            };
+
+            let boxed = self.boxed(underline);
+            let box_column = caret_column.saturating_sub(whitespace_count) + ellipse_space;
+
+            tracer.record(|trace| {
+                if context_before || context_after {
+                    trace.layout = trace::Layout::Shortened;
+                    trace.skip = shortened_by;
+                    trace.take = take;
+                }
+                trace.context_before = context_before;
+                trace.context_after = context_after;
+                trace.caret_column = box_column;
+            });
+
+            if boxed {
+                Self::format_box_edge(
+                    f,
+                    annotation_separator,
+                    fill_line_position,
+                    box_column,
+                    &format!("┌{}┐", "─".repeat(underline - 2)),
+                    &[],
+                )?;
+            }
+
+            self.format_error_line(
+                f,
+                &context_line,
+                gutter,
+                separator,
+                context_before,
+                context_after,
+            )?;
+
+            let message = self.message_lines(
+                &message,
+                caret_column.saturating_sub(whitespace_count) + ellipse_space + underline + 1,
+                fill_line_position,
+            );
+
+            if boxed {
+                Self::format_box_edge(
+                    f,
+                    annotation_separator,
+                    fill_line_position,
+                    box_column,
+                    &format!("└{}┘", "─".repeat(underline - 2)),
+                    &message,
+                )?;
+            } else {
+                self.format_error_information(
+                    f,
+                    &message,
+                    whitespace_count,
+                    annotation_separator,
+                    fill_line_position,
+                    caret_column,
+                    underline,
+                    context_before,
+                )?;
+            }
+
+            labels.iter().try_for_each(|(column, message)| {
+                match column
+                    .checked_sub(whitespace_count)
+                    .and_then(|column| column.checked_sub(shortened_by))
+                    .filter(|column| *column <= visible)
+                {
+                    Some(column) => Self::format_label(
+                        f,
+                        annotation_separator,
+                        fill_line_position,
+                        column + ellipse_space,
+                        message,
+                    ),
+                    None => Ok(()),
+                }
+            })
+        } else if self.settings.contextualize {
+            self.format_context_line(f, &text, separator, gutter)?;
+
+            if let Some((start, end)) = underline {
+                Self::format_underline(
+                    f,
+                    annotation_separator,
+                    fill_line_position,
+                    start.saturating_sub(whitespace_count),
+                    end + 1 - start,
+                )?;
+            }
+
+            labels.iter().try_for_each(|(column, message)| {
+                Self::format_label(
+                    f,
+                    annotation_separator,
+                    fill_line_position,
+                    column.saturating_sub(whitespace_count),
+                    message,
+                )
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Message shown behind the error line, shortened if it does not fit
+    /// completely. Returns [`None`] if the message is shown below the error
+    /// line. See [`SerdeError::set_annotation_style`].
+    fn inline_message(
+        &self,
+        text: &str,
+        message: &str,
+        gutter: &str,
+        underline: Option<(usize, usize)>,
+        labels: &[(usize, &str)],
+    ) -> Option<String> {
+        if self.settings.annotation_style != AnnotationStyle::Inline
+            || underline.is_some()
+            || !labels.is_empty()
+            || message.contains('\n')
+        {
+            return None;
+        }
+
+        let width = self
+            .settings
+            .viewport_width
+            .or_else(reflow::terminal_width)?;
+
+        let used = 1
+            + reflow::display_width(&ansi::strip(gutter))
+            + SEPARATOR.len()
+            + reflow::display_width(text)
+            + INLINE_MARKER.len();
+        let left = width.checked_sub(used)?;
+
+        if reflow::display_width(message) <= left {
+            Some(message.to_string())
+        } else if left >= INLINE_MIN_MESSAGE_WIDTH {
+            Some(reflow::truncate(message, left))
+        } else {
+            None
+        }
+    }
+
+    /// Format a shortened error line as multiple windows, one around the error
+    /// column and one around every label, joined by ellipses. Windows that
+    /// overlap are merged. The caret and the labels are printed below their
+    /// windows.
+    #[allow(clippy::too_many_arguments)]
+    fn format_windows(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        text: &str,
+        error_column: usize,
+        message: &str,
+        whitespace_count: usize,
+        separator: &Separator,
+        annotation_separator: &Separator,
+        fill_line_position: &str,
+        gutter: &str,
+        labels: &[(usize, &str)],
+        tracer: &mut Tracer,
+    ) -> Result<(), std::fmt::Error> {
+        #[cfg(feature = "graphemes_support")]
+        use unicode_segmentation::UnicodeSegmentation;
+
+        #[cfg(feature = "graphemes_support")]
+        let input = text.graphemes(true).collect::<Vec<_>>();
+
+        // Characters are sliced from the text so both variants are units of text
+        #[cfg(not(feature = "graphemes_support"))]
+        let input = text
+            .char_indices()
+            .map(|(index, c)| &text[index..index + c.len_utf8()])
+            .collect::<Vec<_>>();
+
+        let context_characters = self.settings.context_characters;
+
+        // Columns of the caret and the labels in the text without the whitespace
+        // that has been removed in front of it
+        let caret = error_column.saturating_sub(whitespace_count);
+        let labels = labels
+            .iter()
+            .map(|(column, message)| (column.saturating_sub(whitespace_count), *message))
+            .collect::<Vec<_>>();
+
+        let mut windows = std::iter::once(caret)
+            .chain(labels.iter().map(|(column, _)| *column))
+            .map(|column| {
+                (
+                    column.saturating_sub(context_characters).min(input.len()),
+                    (column + context_characters + 1).min(input.len()),
+                )
+            })
+            .collect::<Vec<_>>();
+        windows.sort_unstable();
 
-            #[cfg(not(feature = "colored"))]
-            return writeln!(f, "{}", self.message);
-        }
+        let windows =
+            windows
+                .into_iter()
+                .fold(Vec::<(usize, usize)>::new(), |mut merged, (start, end)| {
+                    match merged.last_mut() {
+                        Some(last) if start <= last.1 => last.1 = last.1.max(end),
+                        _ => merged.push((start, end)),
+                    }
+                    merged
+                });
 
-        let error_line = self.line.unwrap_or_default();
-        let error_column = self.column.unwrap_or_default();
+        #[cfg(feature = "colored")]
+        let ellipse = ELLIPSE.blue().bold().paint();
 
-        // Amount of lines to show before and after the error line
-        let context_lines = self.context_lines;
+        #[cfg(not(feature = "colored"))]
+        let ellipse = ELLIPSE;
 
-        // Skip until we are amount of context lines before the error line (context)
-        // plus the line with the error ( + 1)
-        // Saturating sub if the error is in the first few line we can't take more
-        // context
-        let skip = usize::saturating_sub(error_line, context_lines + 1);
+        #[cfg(feature = "colored")]
+        write!(f, " {}{}", gutter.blue().bold().paint(), separator)?;
 
-        // Take lines before and after (context * 2) plus the line with the error ( + 1)
-        let take = context_lines * 2 + 1;
+        #[cfg(not(feature = "colored"))]
+        write!(f, " {gutter}{separator}")?;
 
-        // Minimize the input to only what we need so we can reuse it without
-        // having to iterate over the whole input again.
-        // Also replace tabs with two spaces
-        let minimized_input = self
-            .input
-            .lines()
-            .skip(skip)
-            .take(take)
-            .map(|line| line.replace("\t", " "))
-            .collect::<Vec<_>>();
+        // Every window remembers the column it starts at in the printed line so
+        // the markers can be moved below it
+        let mut printed = 0;
+        let mut end_of_previous = 0;
+        let mut shown = Vec::new();
+
+        for (start, end) in windows {
+            if start > end_of_previous || (start > 0 && shown.is_empty()) {
+                write!(f, "{ellipse}")?;
+                printed += ELLIPSE.len();
+            }
 
-        // If the minimized_input is empty we can assume that the input was empty as
-        // well. In that case we can't make a nice output so we will just print
-        // the original message in red and bold
-        if minimized_input.is_empty() {
             #[cfg(feature = "colored")]
-            return writeln!(f, "{}", self.message.red().bold());
+            write!(f, "{}", self.highlight(&input[start..end].concat(), None))?;
 
             #[cfg(not(feature = "colored"))]
-            return writeln!(f, "{}", self.message);
+            input[start..end]
+                .iter()
+                .try_for_each(|unit| write!(f, "{unit}"))?;
+            shown.push((start, end, printed));
+
+            printed += end - start;
+            end_of_previous = end;
         }
 
-        // To reduce the amount of space text takes we want to remove unnecessary
-        // whitespace in front of the text.
-        // Find the line with the least amount of whitespace in front and use
-        // that to remove the whitespace later.
-        // We basically want to find the least indented line.
-        // We cant just use trim as that would remove all whitespace and remove all
-        // indentation.
-        let whitespace_count = minimized_input
-            .iter()
-            .map(|line| line.chars().take_while(|s| s.is_whitespace()).count())
-            .min()
-            .unwrap_or_default();
+        if end_of_previous < input.len() {
+            write!(f, "{ellipse}")?;
+        }
 
-        #[cfg(feature = "colored")]
-        let separator = SEPARATOR.blue().bold();
+        writeln!(f)?;
 
-        #[cfg(not(feature = "colored"))]
-        let separator = SEPARATOR;
+        let printed_column = |column: usize| {
+            shown
+                .iter()
+                .find(|(start, end, _)| *start <= column && column < (*end).max(start + 1))
+                .or_else(|| shown.last())
+                .map_or(column, |(start, _, printed)| {
+                    printed + column.saturating_sub(*start)
+                })
+        };
 
-        // When we don't print the line_position we want to fill up the space not used
-        // by the line_position with whitespace instead
-        let fill_line_position = format!("{: >fill$}", "", fill = error_line.to_string().len());
+        let caret = printed_column(caret);
+        let message = self.message_lines(message, caret + 2, fill_line_position);
 
-        // Want to avoid printing when we are not at the beginning of the line. For
-        // example anyhow will write 'Error:' in front of the output before
-        // printing the buffer
-        writeln!(f)?;
+        tracer.record(|trace| trace.caret_column = caret);
 
-        self.input
-            .lines()
-            .into_iter()
-            .enumerate()
-            .skip(skip)
-            .take(take)
-            .map(|(index, text)| {
-                // Make the index start at 1 makes it nicer to work with
-                // Also remove unnecessary whitespace in front of text
-                (
-                    index + 1,
-                    text.chars()
-                        .skip(whitespace_count)
-                        .collect::<String>()
-                        .replace("\t", " "),
-                )
-            })
-            .try_for_each(|(line_position, text)| {
-                self.format_line(
-                    f,
-                    line_position,
-                    error_line,
-                    error_column,
-                    text,
-                    whitespace_count,
-                    &separator,
-                    &fill_line_position,
-                )
-            })?;
+        self.format_error_information(
+            f,
+            &message,
+            0,
+            annotation_separator,
+            fill_line_position,
+            caret,
+            1,
+            false,
+        )?;
 
-        Ok(())
+        labels.iter().try_for_each(|(column, message)| {
+            Self::format_label(
+                f,
+                annotation_separator,
+                fill_line_position,
+                printed_column(*column),
+                message,
+            )
+        })
     }
 
-    // TODO: Maybe make another internal struct for formatting instead of having
-    // this list of args.
+    /// Format a line wrapped to the width of the wrapping. Carets, underlines
+    /// and labels are printed below the part of the line they point at and the
+    /// message follows the end of the underline, on its own lines if it does
+    /// not fit behind it. Context lines that are not wrapped are cut off
+    /// instead.
     #[allow(clippy::too_many_arguments)]
-    fn format_line(
+    fn format_line_reflowed(
         &self,
         f: &mut fmt::Formatter<'_>,
-        line_position: usize,
-        error_line: usize,
+        wrapping: &Wrapping,
+        is_error_line: bool,
         error_column: usize,
-        text: String,
+        underline: Option<(usize, usize)>,
+        text: &str,
+        message: &str,
         whitespace_count: usize,
+        separator: &Separator,
+        annotation_separator: &Separator,
+        fill_line_position: &str,
+        gutter: &str,
+        labels: &[(usize, &str)],
+    ) -> Result<(), std::fmt::Error> {
+        if !is_error_line && !self.settings.contextualize {
+            return Ok(());
+        }
 
-        #[cfg(feature = "colored")] separator: &colored::ColoredString,
+        // Marked columns relative to the text without the common indentation.
+        // The error line is always marked, at least with a single caret.
+        let marked = match underline {
+            Some((start, end)) => Some((start, end)),
+            None if is_error_line => Some((error_column, error_column)),
+            None => None,
+        }
+        .map(|(start, end)| {
+            (
+                start.saturating_sub(whitespace_count),
+                end.saturating_sub(whitespace_count),
+            )
+        });
 
-        #[cfg(not(feature = "colored"))] separator: &str,
+        let width = wrapping.width;
+        let wrapped = is_error_line || wrapping.context;
 
-        fill_line_position: &str,
-    ) -> Result<(), std::fmt::Error> {
-        if line_position == error_line {
-            let long_line_threshold = self.context_characters * 2 + 1;
-            let long_line_threshold = long_line_threshold < text.len();
+        // The caret can be behind the last character, for example at the end of
+        // the input, so there have to be enough rows to show it
+        let length = text
+            .chars()
+            .count()
+            .max(marked.map_or(0, |(_, end)| end + 1))
+            .max(1);
 
-            let (context_line, new_error_column, context_before, context_after) =
-                if self.contextualize && long_line_threshold {
-                    let context_characters = self.context_characters;
-                    Self::context_long_line(&text, error_column, context_characters)
+        // Rows are split between grapheme clusters so they are never torn apart.
+        // Every grapheme cluster takes up a single column in a row.
+        let starts = Self::unit_starts(text, length);
+        let unit = |column: usize| starts.partition_point(|start| *start <= column) - 1;
+        let char_start = |unit: usize| starts.get(unit).copied().unwrap_or(length);
+
+        let rows = if wrapped {
+            starts.len().div_ceil(width)
+        } else {
+            1
+        };
+
+        for row in 0..rows {
+            let first = row * width;
+            let start = char_start(first);
+            let end = char_start(first + width);
+            let segment = if !wrapped && starts.len() > width {
+                let shown = char_start(width.saturating_sub(ELLIPSE.len()));
+                format!(
+                    "{}{}",
+                    text.chars().take(shown).collect::<String>(),
+                    ELLIPSE
+                )
+            } else {
+                text.chars()
+                    .skip(start)
+                    .take(end - start)
+                    .collect::<String>()
+            };
+
+            let line_label = if row == 0 {
+                gutter
+            } else {
+                &wrapping.continuation
+            };
+
+            if is_error_line {
+                self.format_error_line(f, &segment, line_label, separator, false, false)?;
+            } else {
+                self.format_context_line(f, &segment, separator, line_label)?;
+            }
+
+            if let Some((mark_start, mark_end)) = marked.filter(|(s, e)| *s < end && *e >= start) {
+                let column = unit(mark_start.max(start)) - first;
+                let marks = unit(mark_end.min(end - 1)) + 1 - unit(mark_start.max(start));
+
+                if is_error_line && mark_end < end {
+                    self.format_reflowed_message(
+                        f,
+                        width,
+                        message,
+                        annotation_separator,
+                        fill_line_position,
+                        column,
+                        marks,
+                    )?;
                 } else {
-                    (text, error_column, false, false)
-                };
+                    Self::format_underline(
+                        f,
+                        annotation_separator,
+                        fill_line_position,
+                        column,
+                        marks,
+                    )?;
+                }
+            }
 
-            Self::format_error_line(
-                f,
-                &context_line,
-                line_position,
-                separator,
-                context_before,
-                context_after,
-            )?;
+            labels
+                .iter()
+                .map(|(column, message)| (column.saturating_sub(whitespace_count), message))
+                .filter(|(column, _)| (start..end).contains(column))
+                .try_for_each(|(column, message)| {
+                    Self::format_label(
+                        f,
+                        annotation_separator,
+                        fill_line_position,
+                        unit(column) - first,
+                        message,
+                    )
+                })?;
+        }
+
+        Ok(())
+    }
+
+    /// Columns (counted in characters) where the grapheme clusters of the text
+    /// start, or its characters without the `graphemes_support` feature. Single
+    /// characters are added behind the text until `length` characters are
+    /// covered.
+    fn unit_starts(text: &str, length: usize) -> Vec<usize> {
+        #[cfg(feature = "graphemes_support")]
+        use unicode_segmentation::UnicodeSegmentation;
+
+        #[cfg(feature = "graphemes_support")]
+        let units = text
+            .graphemes(true)
+            .map(|grapheme| grapheme.chars().count());
+
+        #[cfg(not(feature = "graphemes_support"))]
+        let units = text.chars().map(|_| 1);
+
+        let mut starts = Vec::new();
+        let mut start = 0;
+
+        for unit in units.chain(std::iter::repeat(1)) {
+            if start >= length {
+                break;
+            }
+
+            starts.push(start);
+            start += unit;
+        }
+
+        starts
+    }
 
-            self.format_error_information(
+    /// Format the carets below a wrapped error line followed by the message.
+    /// The message is wrapped onto the following lines if it does not fit
+    /// behind the carets.
+    #[allow(clippy::too_many_arguments)]
+    fn format_reflowed_message(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        width: usize,
+        message: &str,
+        separator: &Separator,
+        fill_line_position: &str,
+        column: usize,
+        marks: usize,
+    ) -> Result<(), std::fmt::Error> {
+        let behind = column + marks + 1;
+        if !message.contains('\n') && behind + reflow::display_width(message) <= width {
+            return self.format_error_information(
                 f,
-                whitespace_count,
+                &[message.to_string()],
+                0,
                 separator,
                 fill_line_position,
-                new_error_column,
-                context_before,
-            )
-        } else if self.contextualize {
-            Self::format_context_line(f, &text, separator, fill_line_position)
-        } else {
-            Ok(())
+                column,
+                marks,
+                false,
+            );
         }
+
+        let carets = format!("{: >column$}{}", "", self.marks(marks), column = column);
+
+        #[cfg(feature = "colored")]
+        let carets = carets.red().bold().paint();
+
+        writeln!(f, " {fill_line_position}{separator}{carets}")?;
+
+        reflow::wrap(message, width).iter().try_for_each(|line| {
+            #[cfg(feature = "colored")]
+            let line = line.red().bold().paint();
+
+            writeln!(f, " {fill_line_position}{separator}{line}")
+        })
+    }
+
+    /// Format an underline without a message below a context line.
+    fn format_underline(
+        f: &mut fmt::Formatter<'_>,
+        separator: &Separator,
+        fill_line_position: &str,
+        column: usize,
+        width: usize,
+    ) -> Result<(), std::fmt::Error> {
+        let underline = format!("{: >column$}{}", "", "^".repeat(width), column = column);
+
+        #[cfg(feature = "colored")]
+        let underline = underline.red().bold().paint();
+
+        writeln!(f, " {fill_line_position}{separator}{underline}")
+    }
+
+    /// Format a secondary label pointing at the given column of the line above.
+    fn format_label(
+        f: &mut fmt::Formatter<'_>,
+        separator: &Separator,
+        fill_line_position: &str,
+        column: usize,
+        message: &str,
+    ) -> Result<(), std::fmt::Error> {
+        let label = format!("{: >column$}- {}", "", message, column = column);
+
+        #[cfg(feature = "colored")]
+        let label = label.blue().bold().paint();
+
+        writeln!(f, " {fill_line_position}{separator}{label}")
     }
 
+    #[cfg_attr(not(feature = "colored"), allow(clippy::unused_self))]
     fn format_error_line(
+        &self,
         f: &mut fmt::Formatter<'_>,
         text: &str,
-        line_position: usize,
-        #[cfg(feature = "colored")] separator: &colored::ColoredString,
-        #[cfg(not(feature = "colored"))] separator: &str,
+        line_label: &str,
+        separator: &Separator,
         context_before: bool,
         context_after: bool,
     ) -> Result<(), std::fmt::Error> {
         #[cfg(feature = "colored")]
-        let line_pos = line_position.to_string().blue().bold();
+        let line_pos = line_label.blue().bold().paint();
 
         #[cfg(not(feature = "colored"))]
-        let line_pos = line_position;
+        let line_pos = line_label;
 
-        write!(f, " {}{}", line_pos, separator)?;
+        write!(f, " {line_pos}{separator}")?;
 
         if context_before {
             #[cfg(feature = "colored")]
-            write!(f, "{}", (ELLIPSE.blue().bold()))?;
+            write!(f, "{}", (ELLIPSE.blue().bold().paint()))?;
             #[cfg(not(feature = "colored"))]
-            write!(f, "{}", ELLIPSE)?;
+            write!(f, "{ELLIPSE}")?;
         }
 
-        write!(f, "{}", text)?;
+        let text = detach_leading_mark(text);
+
+        #[cfg(feature = "colored")]
+        write!(f, "{}", self.highlight(&text, None))?;
+
+        #[cfg(not(feature = "colored"))]
+        write!(f, "{text}")?;
 
         if context_after {
             #[cfg(feature = "colored")]
-            write!(f, "{}", (ELLIPSE.blue().bold()))?;
+            write!(f, "{}", (ELLIPSE.blue().bold().paint()))?;
             #[cfg(not(feature = "colored"))]
-            write!(f, "{}", ELLIPSE)?;
+            write!(f, "{ELLIPSE}")?;
         }
 
         writeln!(f)
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn format_error_information(
         &self,
         f: &mut fmt::Formatter<'_>,
+        message: &[String],
         whitespace_count: usize,
-        #[cfg(feature = "colored")] separator: &colored::ColoredString,
-
-        #[cfg(not(feature = "colored"))] separator: &str,
-
+        separator: &Separator,
         fill_line_position: &str,
         error_column: usize,
+        underline: usize,
         context_before: bool,
     ) -> Result<(), std::fmt::Error> {
         let ellipse_space = if context_before { ELLIPSE.len() } else { 0 };
+        let column = error_column.saturating_sub(whitespace_count) + ellipse_space;
+
+        if self.settings.text_direction == TextDirection::Rtl {
+            return Self::format_error_information_rtl(
+                f,
+                message,
+                &self.marks(underline),
+                separator,
+                fill_line_position,
+                column,
+                underline,
+            );
+        }
 
         // Print whitespace until we reach the column value of the message. We also
         // have to add the amount of whitespace in front of the other lines.
         // If context_before is true we also need to add the space used by the ellipse
         let fill_column_position = format!(
-            "{: >column$}^ {}",
+            "{: >column$}{} {}",
             "",
-            self.message,
-            column = error_column - whitespace_count + ellipse_space
+            self.marks(underline),
+            message.first().map_or("", String::as_str),
+            column = column
         );
 
         #[cfg(feature = "colored")]
-        let fill_column_position = fill_column_position.red().bold();
+        let fill_column_position = fill_column_position.red().bold().paint();
 
-        writeln!(
-            f,
-            " {}{}{}",
-            fill_line_position, separator, fill_column_position,
-        )
+        writeln!(f, " {fill_line_position}{separator}{fill_column_position}")?;
+
+        // Wrapped lines of the message are aligned with the start of the message
+        message.iter().skip(1).try_for_each(|line| {
+            let line = format!("{: >indent$}{}", "", line, indent = column + underline + 1);
+
+            #[cfg(feature = "colored")]
+            let line = line.red().bold().paint();
+
+            writeln!(f, " {fill_line_position}{separator}{line}")
+        })
     }
 
-    fn format_context_line(
+    /// Format the top or the bottom of the box around the span of the error
+    /// followed by the message. See [`MarkerStyle::Box`].
+    fn format_box_edge(
         f: &mut fmt::Formatter<'_>,
-        text: &str,
-        #[cfg(feature = "colored")] separator: &colored::ColoredString,
+        separator: &Separator,
+        fill_line_position: &str,
+        column: usize,
+        edge: &str,
+        message: &[String],
+    ) -> Result<(), std::fmt::Error> {
+        // Wrapped lines of the message are aligned with the start of the message
+        let indent = column + edge.chars().count() + 1;
+
+        let edge = match message.first() {
+            Some(first) => format!("{: >column$}{} {}", "", edge, first, column = column),
+            None => format!("{: >column$}{}", "", edge, column = column),
+        };
+
+        #[cfg(feature = "colored")]
+        let edge = edge.red().bold().paint();
+
+        writeln!(f, " {fill_line_position}{separator}{edge}")?;
+
+        message.iter().skip(1).try_for_each(|line| {
+            let line = format!("{: >indent$}{}", "", line, indent = indent);
+
+            #[cfg(feature = "colored")]
+            let line = line.red().bold().paint();
+
+            writeln!(f, " {fill_line_position}{separator}{line}")
+        })
+    }
 
-        #[cfg(not(feature = "colored"))] separator: &str,
+    /// Format the carets with the message in front of them for
+    /// [`TextDirection::Rtl`]. The message is moved below the carets and ends
+    /// with them if it does not fit in front.
+    fn format_error_information_rtl(
+        f: &mut fmt::Formatter<'_>,
+        message: &[String],
+        carets: &str,
+        separator: &Separator,
+        fill_line_position: &str,
+        column: usize,
+        underline: usize,
+    ) -> Result<(), std::fmt::Error> {
+        let in_front = message
+            .iter()
+            .all(|line| reflow::display_width(line) < column);
+
+        // Every line of the message ends at the same column
+        let aligned = |line: &str, end: usize| {
+            format!(
+                "{}{}",
+                " ".repeat(end.saturating_sub(reflow::display_width(line))),
+                line
+            )
+        };
+
+        let lines = if in_front {
+            let mut lines = message
+                .iter()
+                .map(|line| aligned(line, column - 1))
+                .collect::<Vec<_>>();
+
+            match lines.first_mut() {
+                Some(first) => *first = format!("{first} {carets}"),
+                None => lines.push(format!("{: >column$}{}", "", carets, column = column)),
+            }
+
+            lines
+        } else {
+            std::iter::once(format!("{: >column$}{}", "", carets, column = column))
+                .chain(message.iter().map(|line| aligned(line, column + underline)))
+                .collect()
+        };
+
+        lines.iter().try_for_each(|line| {
+            #[cfg(feature = "colored")]
+            let line = line.red().bold().paint();
+
+            writeln!(f, " {fill_line_position}{separator}{line}")
+        })
+    }
 
+    #[cfg_attr(not(feature = "colored"), allow(clippy::unused_self))]
+    fn format_context_line(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        text: &str,
+        separator: &Separator,
         fill_line_position: &str,
     ) -> Result<(), std::fmt::Error> {
+        let text = detach_leading_mark(text);
+
         #[cfg(feature = "colored")]
-        return writeln!(f, " {}{}{}", fill_line_position, separator, text.yellow());
+        return writeln!(
+            f,
+            " {}{}{}",
+            fill_line_position,
+            separator,
+            self.highlight(&text, Some(colored::Color::Yellow))
+        );
 
         #[cfg(not(feature = "colored"))]
-        return writeln!(f, " {}{}{}", fill_line_position, separator, text);
+        return writeln!(f, " {fill_line_position}{separator}{text}");
+    }
+
+    /// Text with every occurrence of the highlight term underlined in magenta,
+    /// whitespace markers dimmed and the rest in the given color. See
+    /// [`SerdeError::set_highlight_term`] and
+    /// [`SerdeError::set_show_whitespace`].
+    #[cfg(feature = "colored")]
+    fn highlight(&self, text: &str, color: Option<colored::Color>) -> String {
+        let style = |part: &str, marker: bool| match (marker, color) {
+            (true, _) => part.dimmed().paint().to_string(),
+            (false, Some(color)) => part.color(color).paint().to_string(),
+            (false, None) => part.to_string(),
+        };
+
+        let plain = |part: &str| {
+            if !self.settings.show_whitespace {
+                return style(part, false);
+            }
+
+            whitespace_runs(part)
+                .into_iter()
+                .map(|(run, marker)| style(run, marker))
+                .collect::<String>()
+        };
+
+        let Some(term) = self
+            .settings
+            .highlight_term
+            .as_deref()
+            .filter(|term| !term.is_empty())
+        else {
+            return plain(text);
+        };
+
+        let mut highlighted = String::new();
+        let mut rest = text;
+
+        while let Some(index) = rest.find(term) {
+            highlighted.push_str(&plain(&rest[..index]));
+            highlighted.push_str(&term.magenta().underline().paint().to_string());
+            rest = &rest[index + term.len()..];
+        }
+
+        highlighted.push_str(&plain(rest));
+
+        highlighted
     }
 
     fn context_long_line(
         text: &str,
         error_column: usize,
         context_chars: usize,
+        unit: ContextUnit,
     ) -> (String, usize, bool, bool) {
-        #[cfg(feature = "graphemes_support")]
-        use unicode_segmentation::UnicodeSegmentation;
+        Self::context_long_line_anchored(text, error_column, context_chars, context_chars + 1, unit)
+    }
 
+    /// Amount of characters shown before the error column for the given caret
+    /// anchor.
+    #[allow(
+        clippy::cast_possible_truncation,
+        clippy::cast_precision_loss,
+        clippy::cast_sign_loss
+    )]
+    fn anchored_before(anchor: f32, context_chars: usize) -> usize {
+        let shown = context_chars * 2;
+
+        ((anchor * shown as f32).round() as usize).min(shown)
+    }
+
+    /// Shorten the line like [`Self::context_long_line`] but show `before`
+    /// characters before the error column.
+    fn context_long_line_anchored(
+        text: &str,
+        error_column: usize,
+        context_chars: usize,
+        before: usize,
+        unit: ContextUnit,
+    ) -> (String, usize, bool, bool) {
         #[cfg(feature = "graphemes_support")]
-        // As we could deal with unicode we can have characters that are multiple code
-        // points. In that case we do not want to iterate over each code point
-        // (i.e. using text.chars()) we need to use graphemes instead.
-        let input = text.graphemes(true).collect::<Vec<_>>();
+        use unicode_segmentation::UnicodeSegmentation;
 
-        #[cfg(not(feature = "graphemes_support"))]
-        // If graphemes are not something we expect to deal with we can also just use chars
-        // instead.
-        let input = text.chars().collect::<Vec<_>>();
+        let input = match unit {
+            #[cfg(feature = "graphemes_support")]
+            // As we could deal with unicode we can have characters that are multiple code
+            // points. In that case we do not want to iterate over each code point
+            // (i.e. using text.chars()) we need to use graphemes instead.
+            ContextUnit::Graphemes | ContextUnit::Columns => {
+                text.graphemes(true).collect::<Vec<_>>()
+            }
 
-        // Skip until we are amount of context chars before the error column (context)
-        // plus the column with the error ( + 1) Saturating sub if the error is
-        // in the first few chars we can't take more context
-        let skip = usize::saturating_sub(error_column, context_chars + 1);
+            // If graphemes are not something we expect to deal with we can also just use
+            // chars instead.
+            _ => text
+                .char_indices()
+                .map(|(index, c)| &text[index..index + c.len_utf8()])
+                .collect::<Vec<_>>(),
+        };
 
         // Take chars before and after (context_chars * 2) plus the column with the
         // error ( + 1)
         let take = context_chars * 2 + 1;
 
+        let (skip, take) = if unit == ContextUnit::Columns {
+            Self::column_budget(&input, error_column, before, take)
+        } else {
+            // Skip until we are the amount of chars before the error column. Saturating
+            // sub if the error is in the first few chars we can't take more context
+            (usize::saturating_sub(error_column, before), take)
+        };
+
         // If we skipped any characters that means we are contextualizing before the
         // error. That means that we need to print ... at the beginning of the error
         // line later on in the code.
@@ -675,4 +4952,42 @@ impl SerdeError {
             context_after,
         )
     }
+
+    /// Amount of units skipped and taken so the units before the error column
+    /// are less than `before` display columns wide and all taken units at most
+    /// `take` display columns. The unit with the error is always taken.
+    fn column_budget(
+        input: &[&str],
+        error_column: usize,
+        before: usize,
+        take: usize,
+    ) -> (usize, usize) {
+        // Columns start at 1 so the unit with the error is the one before it
+        let error_column = error_column.saturating_sub(1).min(input.len());
+
+        let mut skip = error_column;
+        let mut width = 0;
+        while skip > 0 {
+            let unit = reflow::display_width(input[skip - 1]);
+            if width + unit >= before {
+                break;
+            }
+
+            width += unit;
+            skip -= 1;
+        }
+
+        let mut end = error_column;
+        while end < input.len() {
+            let unit = reflow::display_width(input[end]);
+            if end > error_column && width + unit > take {
+                break;
+            }
+
+            width += unit;
+            end += 1;
+        }
+
+        (skip, (end - skip).max(1))
+    }
 }
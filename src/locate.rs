@@ -0,0 +1,710 @@
+//! Find the position of a value in the source of a document.
+//!
+//! This is useful when a problem with a value is only detected after parsing
+//! succeeded, for example by validating the parsed config. The returned
+//! [`Location`] can then be used to point a [`SerdeError`](crate::SerdeError)
+//! at the value:
+//!
+//! ```rust
+//! use format_serde_error::{
+//!     locate,
+//!     SerdeError,
+//! };
+//!
+//! let input = r#"{"spec": {"replicas": 0}}"#;
+//! let location = locate::json_pointer(input, "/spec/replicas").unwrap();
+//!
+//! let err = SerdeError::at(input.to_string(), "replicas must be at least 1", location);
+//! # let _ = err;
+//! ```
+
+/// Position in the source of a document. Lines start at 1 and columns are
+/// counted in characters starting at 0 like the positions of
+/// [`SerdeError`](crate::SerdeError).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    /// Line of the position starting at 1.
+    pub line: usize,
+    /// Column of the position in characters starting at 0.
+    pub column: usize,
+    /// Offset of the position in bytes from the start of the input.
+    pub byte_offset: usize,
+}
+
+impl Location {
+    /// Compute the line and column for the given byte offset in the input.
+    /// Returns [`None`] if the offset is out of bounds or not on a character
+    /// boundary.
+    #[must_use]
+    pub fn from_byte_offset(input: &str, byte_offset: usize) -> Option<Self> {
+        let before = input.get(..byte_offset)?;
+
+        let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+        let line = before.matches('\n').count() + 1;
+        let column = before[line_start..].chars().count();
+
+        Some(Self {
+            line,
+            column,
+            byte_offset,
+        })
+    }
+
+    /// Compute the byte offset for the given line starting at 1 and column in
+    /// characters starting at 0. The position right behind the last character
+    /// of a line is part of the line. Returns [`None`] if the position is not
+    /// in the input.
+    #[must_use]
+    pub fn from_line_column(input: &str, line: usize, column: usize) -> Option<Self> {
+        let line_start = if line == 1 {
+            0
+        } else {
+            input.match_indices('\n').nth(line.checked_sub(2)?)?.0 + 1
+        };
+
+        let text = input[line_start..].split('\n').next().unwrap_or_default();
+        let column_offset = text
+            .char_indices()
+            .map(|(offset, _)| offset)
+            .chain(std::iter::once(text.len()))
+            .nth(column)?;
+
+        Some(Self {
+            line,
+            column,
+            byte_offset: line_start + column_offset,
+        })
+    }
+}
+
+/// Find the position of the value the [JSON pointer](https://datatracker.ietf.org/doc/html/rfc6901)
+/// refers to. The input is scanned without building a document so this also
+/// works for large inputs. Returns [`None`] if the pointer is invalid, the
+/// value does not exist or the input is malformed before the value was found.
+#[must_use]
+pub fn json_pointer(input: &str, pointer: &str) -> Option<Location> {
+    locate_json(input, pointer, false)
+}
+
+/// Same as [`json_pointer`] but returns the position of the key of the value
+/// instead. Returns [`None`] if the value is not part of an object.
+#[must_use]
+pub fn json_pointer_key(input: &str, pointer: &str) -> Option<Location> {
+    locate_json(input, pointer, true)
+}
+
+fn locate_json(input: &str, pointer: &str, key: bool) -> Option<Location> {
+    let target = parse_json_pointer(pointer)?;
+
+    let mut scanner = JsonScanner {
+        input: input.as_bytes(),
+        position: 0,
+        goal: Goal::Pointer {
+            target: &target,
+            key,
+        },
+        path: Vec::new(),
+        duplicates: Vec::new(),
+    };
+
+    match scanner.value() {
+        Err(Stop::Found(offset)) => Location::from_byte_offset(input, offset),
+        Ok(()) | Err(Stop::Invalid) => None,
+    }
+}
+
+/// Path of keys and indices leading to the value that ends at or contains the
+/// byte offset. If the offset is right after a key the path of the object
+/// containing the key is returned. Returns [`None`] if the input is malformed
+/// before the offset.
+pub(crate) fn json_path_at(input: &str, offset: usize) -> Option<Vec<String>> {
+    let mut scanner = JsonScanner {
+        input: input.as_bytes(),
+        position: 0,
+        goal: Goal::Offset(offset),
+        path: Vec::new(),
+        duplicates: Vec::new(),
+    };
+
+    match scanner.value() {
+        Err(Stop::Found(_)) => Some(scanner.path),
+        Ok(()) | Err(Stop::Invalid) => None,
+    }
+}
+
+fn parse_json_pointer(pointer: &str) -> Option<Vec<String>> {
+    if pointer.is_empty() {
+        return Some(Vec::new());
+    }
+
+    let pointer = pointer.strip_prefix('/')?;
+
+    Some(
+        pointer
+            .split('/')
+            .map(|segment| segment.replace("~1", "/").replace("~0", "~"))
+            .collect(),
+    )
+}
+
+/// Key that is defined more than once in the same JSON object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DuplicateKey {
+    pub(crate) key: String,
+    /// Byte offset of the first definition of the key.
+    pub(crate) first: usize,
+    /// Byte offset of the repeated definition of the key.
+    pub(crate) duplicate: usize,
+}
+
+/// Find all keys that are defined more than once in the same object. Keys
+/// with the same name in different objects are fine. Scanning stops at the
+/// first syntax error so only duplicates before it are returned.
+#[cfg_attr(not(feature = "serde_json"), allow(dead_code))]
+pub(crate) fn json_duplicate_keys(input: &str) -> Vec<DuplicateKey> {
+    let mut scanner = JsonScanner {
+        input: input.as_bytes(),
+        position: 0,
+        goal: Goal::Duplicates,
+        path: Vec::new(),
+        duplicates: Vec::new(),
+    };
+
+    // Errors only stop the scan, everything found until then is still valid
+    let _ = scanner.value();
+
+    scanner.duplicates
+}
+
+/// Reasons for the scanner to stop early.
+enum Stop {
+    /// Target was found at the given byte offset.
+    Found(usize),
+    /// Input is not valid JSON.
+    Invalid,
+}
+
+/// What the scanner is looking for.
+enum Goal<'a> {
+    /// Value or key at the given path.
+    Pointer { target: &'a [String], key: bool },
+    /// Innermost value or key ending at or after the byte offset.
+    #[cfg_attr(not(feature = "schemars"), allow(dead_code))]
+    Offset(usize),
+    /// Keys defined more than once in the same object.
+    #[cfg_attr(not(feature = "serde_json"), allow(dead_code))]
+    Duplicates,
+}
+
+struct JsonScanner<'a> {
+    input: &'a [u8],
+    position: usize,
+    goal: Goal<'a>,
+    path: Vec<String>,
+    duplicates: Vec<DuplicateKey>,
+}
+
+impl JsonScanner<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.position).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\r' | b'\n')) {
+            self.position += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), Stop> {
+        self.skip_whitespace();
+
+        if self.peek() == Some(byte) {
+            self.position += 1;
+            Ok(())
+        } else {
+            Err(Stop::Invalid)
+        }
+    }
+
+    /// Stop if the offset the scanner is looking for has been passed.
+    fn check_offset(&self) -> Result<(), Stop> {
+        match self.goal {
+            Goal::Offset(offset) if self.position >= offset => Err(Stop::Found(self.position)),
+            _ => Ok(()),
+        }
+    }
+
+    fn value(&mut self) -> Result<(), Stop> {
+        self.skip_whitespace();
+
+        if let Goal::Pointer { target, key: false } = self.goal {
+            if self.path == target {
+                return Err(Stop::Found(self.position));
+            }
+        }
+
+        match self.peek() {
+            Some(b'{') => self.object(),
+            Some(b'[') => self.array(),
+            Some(b'"') => self.string().map(|_| ()),
+            Some(_) => self.scalar(),
+            None => Err(Stop::Invalid),
+        }?;
+
+        self.check_offset()
+    }
+
+    fn object(&mut self) -> Result<(), Stop> {
+        self.expect(b'{')?;
+        self.skip_whitespace();
+
+        if self.peek() == Some(b'}') {
+            self.position += 1;
+            return Ok(());
+        }
+
+        // Keys of this object with their position to find duplicates
+        let mut keys: Vec<(String, usize)> = Vec::new();
+
+        loop {
+            self.skip_whitespace();
+            let key_position = self.position;
+            let key = self.string()?;
+            self.check_offset()?;
+
+            if let Goal::Duplicates = self.goal {
+                match keys.iter().find(|(existing, _)| *existing == key) {
+                    Some((_, first)) => self.duplicates.push(DuplicateKey {
+                        key: key.clone(),
+                        first: *first,
+                        duplicate: key_position,
+                    }),
+                    None => keys.push((key.clone(), key_position)),
+                }
+            }
+
+            self.path.push(key);
+            if let Goal::Pointer { target, key: true } = self.goal {
+                if self.path == target {
+                    return Err(Stop::Found(key_position));
+                }
+            }
+
+            self.expect(b':')?;
+            self.value()?;
+            self.path.pop();
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                Some(b'}') => {
+                    self.position += 1;
+                    return Ok(());
+                }
+                _ => return Err(Stop::Invalid),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<(), Stop> {
+        self.expect(b'[')?;
+        self.skip_whitespace();
+
+        if self.peek() == Some(b']') {
+            self.position += 1;
+            return Ok(());
+        }
+
+        let mut index = 0_usize;
+        loop {
+            self.path.push(index.to_string());
+            self.value()?;
+            self.path.pop();
+            index += 1;
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                Some(b']') => {
+                    self.position += 1;
+                    return Ok(());
+                }
+                _ => return Err(Stop::Invalid),
+            }
+        }
+    }
+
+    /// Consume a string and return its unescaped content. Only the escapes
+    /// relevant for comparing keys are handled, everything else is kept as is.
+    fn string(&mut self) -> Result<String, Stop> {
+        if self.peek() != Some(b'"') {
+            return Err(Stop::Invalid);
+        }
+        self.position += 1;
+
+        let mut content = Vec::new();
+        loop {
+            match self.peek() {
+                Some(b'"') => {
+                    self.position += 1;
+                    return String::from_utf8(content).map_err(|_| Stop::Invalid);
+                }
+                Some(b'\\') => {
+                    let escaped = self.input.get(self.position + 1).copied();
+                    match escaped {
+                        Some(b'"') => content.push(b'"'),
+                        Some(b'\\') => content.push(b'\\'),
+                        Some(b'/') => content.push(b'/'),
+                        Some(other) => content.extend_from_slice(&[b'\\', other]),
+                        None => return Err(Stop::Invalid),
+                    }
+                    self.position += 2;
+                }
+                Some(byte) => {
+                    content.push(byte);
+                    self.position += 1;
+                }
+                None => return Err(Stop::Invalid),
+            }
+        }
+    }
+
+    fn scalar(&mut self) -> Result<(), Stop> {
+        let start = self.position;
+
+        while !matches!(
+            self.peek(),
+            None | Some(b',' | b']' | b'}' | b' ' | b'\t' | b'\r' | b'\n')
+        ) {
+            self.position += 1;
+        }
+
+        if self.position == start {
+            Err(Stop::Invalid)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Find the position of a field in CSV input. Records and fields are counted
+/// starting at 0 like the positions of the `csv` crate, a header row is
+/// record 0. Fields are separated by commas, fields in double quotes can
+/// contain commas, doubled quotes and line breaks. Empty lines are skipped
+/// like the `csv` crate does. The position is the first character of the
+/// field inside of the quotes. Returns [`None`] if the record or the field
+/// does not exist.
+#[must_use]
+pub fn csv_field(input: &str, record: usize, field: usize) -> Option<Location> {
+    let mut start = 0;
+
+    for _ in 0..record {
+        start = csv_record_end(input.as_bytes(), csv_record_start(input, start)?);
+    }
+
+    let offset = csv_field_offset(input, csv_record_start(input, start)?, field)?;
+    Location::from_byte_offset(input, offset)
+}
+
+/// Byte offset of the field in the record starting at the byte offset. See
+/// [`csv_field`].
+pub(crate) fn csv_field_offset(input: &str, record_start: usize, field: usize) -> Option<usize> {
+    let bytes = input.as_bytes();
+    let mut position = record_start;
+
+    for _ in 0..field {
+        position = csv_field_end(bytes, position);
+        if bytes.get(position) != Some(&b',') {
+            return None;
+        }
+        position += 1;
+    }
+
+    match bytes.get(position) {
+        Some(b'"') => Some(position + 1),
+        _ => Some(position),
+    }
+}
+
+/// Byte offset of the next record at or after the byte offset skipping empty
+/// lines. Returns [`None`] at the end of the input.
+fn csv_record_start(input: &str, mut position: usize) -> Option<usize> {
+    let bytes = input.as_bytes();
+
+    while matches!(bytes.get(position), Some(b'\n' | b'\r')) {
+        position += 1;
+    }
+
+    (position < bytes.len()).then_some(position)
+}
+
+/// Byte offset right behind the line break ending the record that starts at
+/// the byte offset.
+fn csv_record_end(bytes: &[u8], mut position: usize) -> usize {
+    loop {
+        position = csv_field_end(bytes, position);
+
+        match bytes.get(position) {
+            Some(b',') => position += 1,
+            Some(_) => return position + 1,
+            None => return position,
+        }
+    }
+}
+
+/// Byte offset of the comma or line break behind the field starting at the
+/// byte offset.
+fn csv_field_end(bytes: &[u8], mut position: usize) -> usize {
+    let mut quoted = bytes.get(position) == Some(&b'"');
+    if quoted {
+        position += 1;
+    }
+
+    while let Some(byte) = bytes.get(position) {
+        match (quoted, byte) {
+            (true, b'"') if bytes.get(position + 1) == Some(&b'"') => position += 1,
+            (true, b'"') => quoted = false,
+            (false, b',' | b'\n' | b'\r') => return position,
+            _ => {}
+        }
+
+        position += 1;
+    }
+
+    position
+}
+
+/// Segment of a path into a YAML document used by [`yaml_path`].
+#[cfg(feature = "serde_yaml")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    /// Key of a mapping.
+    Key(String),
+    /// Index into a sequence. Also matches a mapping key with the same
+    /// number.
+    Index(usize),
+}
+
+#[cfg(feature = "serde_yaml")]
+impl PathSegment {
+    /// Parse a dotted path like `values.2.timeout` into segments. Segments
+    /// that are numbers become [`PathSegment::Index`].
+    #[must_use]
+    pub fn parse(path: &str) -> Vec<PathSegment> {
+        if path.is_empty() {
+            return Vec::new();
+        }
+
+        path.split('.')
+            .map(|segment| match segment.parse() {
+                Ok(index) => PathSegment::Index(index),
+                Err(_) => PathSegment::Key(segment.to_string()),
+            })
+            .collect()
+    }
+
+    fn matches_key(&self, key: &str) -> bool {
+        match self {
+            PathSegment::Key(expected) => expected == key,
+            PathSegment::Index(index) => index.to_string() == key,
+        }
+    }
+}
+
+/// Find the position of the node the path refers to in a YAML document.
+/// Block and flow styles as well as quoted keys are supported. Returns
+/// [`None`] if the node does not exist, the input can not be parsed or the
+/// path leads through an alias or a merge key as the position of the node
+/// would be ambiguous.
+#[cfg(feature = "serde_yaml")]
+#[must_use]
+pub fn yaml_path(input: &str, path: &[PathSegment]) -> Option<Location> {
+    let mut events = YamlEvents::default();
+    yaml_rust::parser::Parser::new(input.chars())
+        .load(&mut events, false)
+        .ok()?;
+
+    // Skip StreamStart and DocumentStart
+    let mut position = events.0.iter().position(|(event, _)| {
+        !matches!(
+            event,
+            yaml_rust::Event::StreamStart | yaml_rust::Event::DocumentStart
+        )
+    })?;
+
+    let root = YamlNode::parse(&events.0, &mut position)?;
+    let marker = root.find(path)?;
+
+    Location::from_line_column(input, marker.line(), marker.col())
+}
+
+/// Find all keys that are defined more than once in the same YAML mapping.
+/// Keys with the same name in different mappings are fine. Returns nothing if
+/// the input can not be parsed.
+#[cfg(feature = "serde_yaml")]
+pub(crate) fn yaml_duplicate_keys(input: &str) -> Vec<DuplicateKey> {
+    let mut events = YamlEvents::default();
+    if yaml_rust::parser::Parser::new(input.chars())
+        .load(&mut events, false)
+        .is_err()
+    {
+        return Vec::new();
+    }
+
+    let mut position = 0;
+    let mut duplicates = Vec::new();
+
+    while position < events.0.len() {
+        if matches!(events.0[position].0, yaml_rust::Event::MappingStart(_)) {
+            if let Some(root) = YamlNode::parse(&events.0, &mut position) {
+                root.duplicate_keys(input, &mut duplicates);
+            }
+        } else {
+            position += 1;
+        }
+    }
+
+    duplicates
+}
+
+#[cfg(feature = "serde_yaml")]
+#[derive(Default)]
+struct YamlEvents(Vec<(yaml_rust::Event, yaml_rust::scanner::Marker)>);
+
+#[cfg(feature = "serde_yaml")]
+impl yaml_rust::parser::MarkedEventReceiver for YamlEvents {
+    fn on_event(&mut self, event: yaml_rust::Event, marker: yaml_rust::scanner::Marker) {
+        self.0.push((event, marker));
+    }
+}
+
+#[cfg(feature = "serde_yaml")]
+enum YamlNode {
+    Scalar(String, yaml_rust::scanner::Marker),
+    Sequence(Vec<YamlNode>, yaml_rust::scanner::Marker),
+    Mapping(Vec<(YamlNode, YamlNode)>, yaml_rust::scanner::Marker),
+    Alias,
+}
+
+#[cfg(feature = "serde_yaml")]
+impl YamlNode {
+    fn parse(
+        events: &[(yaml_rust::Event, yaml_rust::scanner::Marker)],
+        position: &mut usize,
+    ) -> Option<Self> {
+        use yaml_rust::Event;
+
+        let (event, marker) = events.get(*position)?;
+        let marker = *marker;
+        *position += 1;
+
+        match event {
+            Event::Scalar(value, ..) => Some(Self::Scalar(value.clone(), marker)),
+            Event::Alias(_) => Some(Self::Alias),
+            Event::SequenceStart(_) => {
+                let mut items = Vec::new();
+                while !Self::at_end(events, *position, &Event::SequenceEnd) {
+                    items.push(Self::parse(events, position)?);
+                }
+                *position += 1;
+
+                Some(Self::Sequence(items, marker))
+            }
+            Event::MappingStart(_) => {
+                let mut pairs = Vec::new();
+                while !Self::at_end(events, *position, &Event::MappingEnd) {
+                    let key = Self::parse(events, position)?;
+                    let value = Self::parse(events, position)?;
+                    pairs.push((key, value));
+                }
+                *position += 1;
+
+                Some(Self::Mapping(pairs, marker))
+            }
+            _ => None,
+        }
+    }
+
+    /// Check if the event at the position is the given end event. Also returns
+    /// true if there are no events left so parsing terminates.
+    fn at_end(
+        events: &[(yaml_rust::Event, yaml_rust::scanner::Marker)],
+        position: usize,
+        end: &yaml_rust::Event,
+    ) -> bool {
+        events.get(position).is_none_or(|(event, _)| event == end)
+    }
+
+    /// Collect the keys defined more than once in this node and all nodes
+    /// nested in it.
+    fn duplicate_keys(&self, input: &str, duplicates: &mut Vec<DuplicateKey>) {
+        match self {
+            Self::Sequence(items, _) => {
+                for item in items {
+                    item.duplicate_keys(input, duplicates);
+                }
+            }
+
+            Self::Mapping(pairs, _) => {
+                for (index, (key, value)) in pairs.iter().enumerate() {
+                    if let Self::Scalar(name, marker) = key {
+                        let first = pairs[..index].iter().find_map(|(other, _)| match other {
+                            Self::Scalar(other, first) if other == name => Some(first),
+                            _ => None,
+                        });
+
+                        let offsets = first.and_then(|first| {
+                            let first =
+                                Location::from_line_column(input, first.line(), first.col())?;
+                            let duplicate =
+                                Location::from_line_column(input, marker.line(), marker.col())?;
+
+                            Some((first.byte_offset, duplicate.byte_offset))
+                        });
+
+                        if let Some((first, duplicate)) = offsets {
+                            duplicates.push(DuplicateKey {
+                                key: name.clone(),
+                                first,
+                                duplicate,
+                            });
+                        }
+                    }
+
+                    value.duplicate_keys(input, duplicates);
+                }
+            }
+
+            Self::Scalar(..) | Self::Alias => {}
+        }
+    }
+
+    fn find(&self, path: &[PathSegment]) -> Option<yaml_rust::scanner::Marker> {
+        let Some((segment, rest)) = path.split_first() else {
+            return match self {
+                Self::Scalar(_, marker) | Self::Sequence(_, marker) | Self::Mapping(_, marker) => {
+                    Some(*marker)
+                }
+                Self::Alias => None,
+            };
+        };
+
+        match (self, segment) {
+            (Self::Sequence(items, _), PathSegment::Index(index)) => items.get(*index)?.find(rest),
+
+            (Self::Mapping(pairs, _), segment) => {
+                let found = pairs.iter().find(|(key, _)| match key {
+                    Self::Scalar(key, _) => segment.matches_key(key),
+                    _ => false,
+                });
+
+                match found {
+                    Some((_, value)) => value.find(rest),
+                    None => None,
+                }
+            }
+
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,40 @@
+//! Emitting errors through the [`log`] crate.
+
+use crate::SerdeError;
+
+/// Representation of an error emitted with [`SerdeError::log_with_format`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogFormat {
+    /// A single line with the position and the message of the error like
+    /// [`RenderStyle::Minimal`](crate::RenderStyle::Minimal).
+    #[default]
+    Compact,
+
+    /// The same multi-line snippet the [`std::fmt::Display`] implementation
+    /// produces.
+    Full,
+}
+
+impl SerdeError {
+    /// Emit the error as a single line through the [`log`] macros at the given
+    /// level. See [`SerdeError::log_with_format`] to emit the full snippet.
+    ///
+    /// ```rust
+    /// use format_serde_error::SerdeError;
+    ///
+    /// let err = SerdeError::new("a: b".to_string(), ("error".into(), Some(1), Some(3)));
+    /// err.log(log::Level::Warn);
+    /// ```
+    pub fn log(&self, level: log::Level) {
+        self.log_with_format(level, LogFormat::Compact);
+    }
+
+    /// Emit the error through the [`log`] macros at the given level in the
+    /// given representation.
+    pub fn log_with_format(&self, level: log::Level, format: LogFormat) {
+        match format {
+            LogFormat::Compact => log::log!(level, "{}", self.minimal()),
+            LogFormat::Full => log::log!(level, "{self}"),
+        }
+    }
+}
@@ -0,0 +1,106 @@
+//! Writes styled text with colors that can be switched on and off for a
+//! single render without changing the global coloring mode. See
+//! [`SerdeError::render_bytes`](crate::SerdeError::render_bytes).
+
+use std::{
+    cell::Cell,
+    fmt,
+};
+
+use colored::{
+    ColoredString,
+    Styles,
+};
+
+thread_local! {
+    /// Colors forced on or off for the renders on this thread.
+    static FORCED: Cell<Option<bool>> = const { Cell::new(None) };
+}
+
+/// Codes of the styles in the order [`colored`] writes them.
+const STYLE_CODES: [(Styles, &str); 8] = [
+    (Styles::Bold, "1"),
+    (Styles::Dimmed, "2"),
+    (Styles::Underline, "4"),
+    (Styles::Reversed, "7"),
+    (Styles::Italic, "3"),
+    (Styles::Blink, "5"),
+    (Styles::Hidden, "8"),
+    (Styles::Strikethrough, "9"),
+];
+
+const RESET: &str = "\x1B[0m";
+
+/// Run the closure with colors switched on or off for everything painted on
+/// this thread in the meantime.
+pub(crate) fn with_colors<T>(color: bool, f: impl FnOnce() -> T) -> T {
+    /// Restores the previous setting even if the closure panics.
+    struct Restore(Option<bool>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            FORCED.with(|forced| forced.set(self.0));
+        }
+    }
+
+    let _restore = Restore(FORCED.with(|forced| forced.replace(Some(color))));
+
+    f()
+}
+
+/// Returns true if painted text is written with colors right now.
+pub(crate) fn should_colorize() -> bool {
+    FORCED
+        .with(Cell::get)
+        .unwrap_or_else(|| colored::control::SHOULD_COLORIZE.should_colorize())
+}
+
+/// Styled text that is written with colors if [`should_colorize`] allows it.
+#[derive(Clone)]
+pub(crate) struct Painted(ColoredString);
+
+/// Turn styled text into [`Painted`] text.
+pub(crate) trait Paint {
+    fn paint(self) -> Painted;
+}
+
+impl Paint for ColoredString {
+    fn paint(self) -> Painted {
+        Painted(self)
+    }
+}
+
+impl Painted {
+    /// Escape sequence that starts the style of the text like [`colored`]
+    /// writes it.
+    fn style(&self) -> String {
+        let style = self.0.style();
+
+        let codes = STYLE_CODES
+            .iter()
+            .filter(|(styles, _)| style.contains(*styles))
+            .map(|(_, code)| (*code).into())
+            .chain(self.0.bgcolor().map(|color| color.to_bg_str()))
+            .chain(self.0.fgcolor().map(|color| color.to_fg_str()))
+            .collect::<Vec<_>>();
+
+        format!("\x1B[{}m", codes.join(";"))
+    }
+}
+
+impl fmt::Display for Painted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text: &str = &self.0;
+
+        if !should_colorize() || self.0.is_plain() {
+            return f.write_str(text);
+        }
+
+        // Styled text nested in the text is reset at its end so the style of
+        // the text has to be started again behind it
+        let style = self.style();
+        let text = text.replace(RESET, &format!("{RESET}{style}"));
+
+        write!(f, "{style}{text}{RESET}")
+    }
+}
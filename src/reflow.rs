@@ -0,0 +1,132 @@
+//! Wrapping of text for reports rendered with a limited width.
+
+#[cfg(feature = "unicode_width")]
+use unicode_width::{
+    UnicodeWidthChar,
+    UnicodeWidthStr,
+};
+
+/// Amount of columns the text takes up in a terminal.
+pub(crate) fn display_width(text: &str) -> usize {
+    #[cfg(feature = "unicode_width")]
+    return text.width();
+
+    #[cfg(not(feature = "unicode_width"))]
+    return text.chars().count();
+}
+
+/// Amount of columns the character takes up in a terminal.
+fn char_width(c: char) -> usize {
+    #[cfg(feature = "unicode_width")]
+    return c.width().unwrap_or_default();
+
+    #[cfg(not(feature = "unicode_width"))]
+    return {
+        let _ = c;
+        1
+    };
+}
+
+/// Width of the terminal in columns if it can be detected.
+pub(crate) fn terminal_width() -> Option<usize> {
+    #[cfg(feature = "terminal_size")]
+    if let Some((terminal_size::Width(width), _)) = terminal_size::terminal_size() {
+        return Some(usize::from(width));
+    }
+
+    std::env::var("COLUMNS").ok()?.trim().parse().ok()
+}
+
+/// Shorten the text so that it is at most `width` columns wide including the
+/// ellipse at its end.
+pub(crate) fn truncate(text: &str, width: usize) -> String {
+    let width = width.saturating_sub(crate::ELLIPSE.len());
+    let mut truncated = String::new();
+    let mut length = 0;
+
+    for c in text.chars() {
+        length += char_width(c);
+        if length > width {
+            break;
+        }
+
+        truncated.push(c);
+    }
+
+    truncated.push_str(crate::ELLIPSE);
+
+    truncated
+}
+
+/// Split the text at whitespace that is not quoted in backticks.
+fn words(text: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut start = None;
+    let mut quoted = false;
+
+    for (index, c) in text.char_indices() {
+        if c.is_whitespace() && !quoted {
+            if let Some(start) = start.take() {
+                words.push(&text[start..index]);
+            }
+
+            continue;
+        }
+
+        if c == '`' {
+            quoted = !quoted;
+        }
+
+        start.get_or_insert(index);
+    }
+
+    if let Some(start) = start {
+        words.push(&text[start..]);
+    }
+
+    words
+}
+
+/// Wrap the text at whitespace so that no line is wider than `width` columns.
+/// Text quoted in backticks is kept together and only split like other words
+/// that are wider than a whole line. Line breaks in the text are kept.
+pub(crate) fn wrap(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        let mut length = 0;
+
+        for word in words(paragraph) {
+            // Words that don't fit behind the current word start a new line
+            if length > 0 && length + 1 + display_width(word) > width {
+                lines.push(std::mem::take(&mut line));
+                length = 0;
+            }
+
+            if length > 0 {
+                line.push(' ');
+                length += 1;
+            }
+
+            for c in word.chars() {
+                if length > 0 && length + char_width(c) > width {
+                    lines.push(std::mem::take(&mut line));
+                    length = 0;
+                }
+
+                line.push(c);
+                length += char_width(c);
+            }
+        }
+
+        lines.push(line);
+    }
+
+    if lines.is_empty() {
+        lines.push(String::new());
+    }
+
+    lines
+}
@@ -0,0 +1,290 @@
+use std::{
+    fmt,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+#[cfg(feature = "colored")]
+use colored::Colorize;
+
+#[cfg(feature = "colored")]
+use crate::paint::Paint;
+
+use crate::{
+    enhance::{
+        Note,
+        NoteKind,
+    },
+    ErrorAdapter,
+    SerdeError,
+};
+
+/// Header printed in front of the path of a file in a [`Report`].
+const FILE_HEADER: &str = "-->";
+
+/// Collection of errors from multiple files. The errors are grouped by the
+/// file they occurred in and printed with a summary at the end.
+///
+/// ```rust
+/// use format_serde_error::{
+///     Report,
+///     SerdeError,
+/// };
+///
+/// let mut report = Report::new();
+/// report.add(
+///     "config.yaml",
+///     SerdeError::new("a: b".to_string(), ("error".into(), Some(1), Some(3))),
+/// );
+///
+/// assert!(!report.is_empty());
+/// println!("{}", report);
+/// ```
+#[derive(Debug)]
+pub struct Report {
+    files: Vec<(PathBuf, Vec<SerdeError>)>,
+    sort_key: SortKey,
+    merge_overlapping: bool,
+}
+
+impl Default for Report {
+    fn default() -> Self {
+        ReportBuilder::default().build()
+    }
+}
+
+/// Order of the errors of a file in a [`Report`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortKey {
+    /// Order by line and column. Errors without a location come last.
+    #[default]
+    Line,
+    /// Order by [`Severity`](crate::Severity), errors before warnings. Errors
+    /// with the same severity stay in the order they were added.
+    Severity,
+    /// Keep the order the errors were added in.
+    Insertion,
+}
+
+/// Builder for a [`Report`] that controls how the errors are ordered and
+/// combined.
+///
+/// ```rust
+/// use format_serde_error::{
+///     Report,
+///     SortKey,
+/// };
+///
+/// let report = Report::builder()
+///     .sort_by(SortKey::Insertion)
+///     .merge_overlapping(false)
+///     .build();
+/// # let _ = report;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReportBuilder {
+    sort_key: SortKey,
+    merge_overlapping: bool,
+}
+
+impl Default for ReportBuilder {
+    fn default() -> Self {
+        Self {
+            sort_key: SortKey::default(),
+            merge_overlapping: true,
+        }
+    }
+}
+
+impl ReportBuilder {
+    /// Set the order of the errors of a file. Default is [`SortKey::Line`].
+    #[must_use]
+    pub fn sort_by(mut self, sort_key: SortKey) -> Self {
+        self.sort_key = sort_key;
+        self
+    }
+
+    /// Set if errors at the same line and column of a file are merged into
+    /// the error added first. The messages of the merged errors are shown as
+    /// notes. Default is true.
+    #[must_use]
+    pub fn merge_overlapping(mut self, merge_overlapping: bool) -> Self {
+        self.merge_overlapping = merge_overlapping;
+        self
+    }
+
+    /// Create the empty [`Report`].
+    #[must_use]
+    pub fn build(self) -> Report {
+        Report {
+            files: Vec::new(),
+            sort_key: self.sort_key,
+            merge_overlapping: self.merge_overlapping,
+        }
+    }
+}
+
+/// Structured representation of a single error in a [`Report`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// Path of the file the error occurred in.
+    pub path: PathBuf,
+    /// Line the error occurred at.
+    pub line: Option<usize>,
+    /// Column the error occurred at.
+    pub column: Option<usize>,
+    /// Message of the error as it is shown to the user. See
+    /// [`SerdeError::set_message_rewriter`].
+    pub message: String,
+    /// Format adapter that produced the error. See
+    /// [`SerdeError::error_kind`].
+    pub error_kind: ErrorAdapter,
+}
+
+impl Report {
+    /// Create a new empty [`Report`] that orders errors by line and merges
+    /// errors at the same position. Use [`Report::builder`] to change that.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a [`ReportBuilder`] to configure the order of the errors.
+    #[must_use]
+    pub fn builder() -> ReportBuilder {
+        ReportBuilder::default()
+    }
+
+    /// Add an error that occurred in the file at the given path. Errors for
+    /// the same path are grouped together and ordered like configured with
+    /// [`ReportBuilder::sort_by`].
+    pub fn add(&mut self, path: impl Into<PathBuf>, err: SerdeError) -> &mut Self {
+        let path = path.into();
+
+        let index = self.files.iter().position(|(p, _)| *p == path);
+        let index = index.unwrap_or_else(|| {
+            self.files.push((path, Vec::new()));
+            self.files.len() - 1
+        });
+        let errors = &mut self.files[index].1;
+
+        let overlapping = errors.iter_mut().find(|existing| {
+            existing.line.is_some()
+                && existing.column.is_some()
+                && (existing.line, existing.column) == (err.line, err.column)
+        });
+
+        match overlapping {
+            Some(existing) if self.merge_overlapping => Self::merge(existing, err),
+            _ => errors.push(err),
+        }
+
+        // Sorting is stable so errors that compare equal stay in the order they
+        // were added
+        match self.sort_key {
+            // Errors without a location come last
+            SortKey::Line => errors.sort_by_key(|err| (err.line.is_none(), err.line, err.column)),
+            SortKey::Severity => errors.sort_by_key(|err| err.severity),
+            SortKey::Insertion => {}
+        }
+
+        self
+    }
+
+    /// Combine an error at the same position into an existing error.
+    fn merge(existing: &mut SerdeError, err: SerdeError) {
+        existing.severity = existing.severity.min(err.severity);
+        existing.notes.push(Note {
+            kind: NoteKind::Note,
+            message: err.displayed_message().into_owned(),
+        });
+        existing.notes.extend(err.notes);
+        existing.labels.extend(err.labels);
+    }
+
+    /// Returns true if no errors have been added to the report.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Total amount of errors in the report.
+    #[must_use]
+    pub fn error_count(&self) -> usize {
+        self.files.iter().map(|(_, errors)| errors.len()).sum()
+    }
+
+    /// Amount of files that contain at least one error.
+    #[must_use]
+    pub fn file_count(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Iterate over all errors together with the path of the file they
+    /// occurred in.
+    pub fn iter(&self) -> impl Iterator<Item = (&Path, &SerdeError)> {
+        self.files
+            .iter()
+            .flat_map(|(path, errors)| errors.iter().map(move |err| (path.as_path(), err)))
+    }
+
+    /// Get all errors as structured [`Diagnostic`]s.
+    #[must_use]
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        self.iter()
+            .map(|(path, err)| Diagnostic {
+                path: path.to_path_buf(),
+                line: err.line(),
+                column: err.column(),
+                message: err.displayed_message().into_owned(),
+                error_kind: err.error_kind(),
+            })
+            .collect()
+    }
+
+    fn format_summary(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let errors = self.error_count();
+        let files = self.file_count();
+
+        let summary = format!(
+            "{} {} in {} {}",
+            errors,
+            if errors == 1 { "error" } else { "errors" },
+            files,
+            if files == 1 { "file" } else { "files" },
+        );
+
+        #[cfg(feature = "colored")]
+        return writeln!(f, "{}", summary.red().bold().paint());
+
+        #[cfg(not(feature = "colored"))]
+        return writeln!(f, "{summary}");
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (path, errors) in &self.files {
+            #[cfg(feature = "colored")]
+            writeln!(
+                f,
+                "{} {}",
+                FILE_HEADER.blue().bold().paint(),
+                path.display()
+            )?;
+
+            #[cfg(not(feature = "colored"))]
+            writeln!(f, "{} {}", FILE_HEADER, path.display())?;
+
+            for err in errors {
+                err.render(f)?;
+            }
+
+            writeln!(f)?;
+        }
+
+        self.format_summary(f)
+    }
+}
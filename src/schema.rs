@@ -0,0 +1,236 @@
+//! Suggestions for unknown keys and variants based on a schema generated by
+//! [`schemars`].
+
+use schemars::schema::{
+    RootSchema,
+    Schema,
+    SchemaObject,
+    SingleOrVec,
+};
+
+use crate::{
+    enhance,
+    SerdeError,
+};
+
+/// Limit for following references and subschemas so recursive schemas can not
+/// loop forever.
+const MAX_DEPTH: usize = 16;
+
+/// Build a help message listing the closest keys or variants the schema
+/// allows at the position of the error. Returns [`None`] if the error is not
+/// about an unknown key or variant or the schema has no candidates for it.
+pub(crate) fn suggestion(err: &SerdeError, root: &RootSchema) -> Option<String> {
+    let field = enhance::quoted_after(&err.message, "unknown field `");
+    let (unknown, what) = if let Some(field) = field {
+        (field, "keys")
+    } else {
+        (
+            enhance::quoted_after(&err.message, "unknown variant `")?,
+            "variants",
+        )
+    };
+
+    let path = error_path(err)?;
+
+    let mut schema = &root.schema;
+    for segment in &path {
+        schema = step(root, schema, segment, 0)?;
+    }
+
+    let mut candidates = Vec::new();
+    if what == "keys" {
+        collect_keys(root, schema, &mut candidates, 0);
+    } else {
+        collect_variants(root, schema, &mut candidates, 0);
+    }
+
+    if candidates.is_empty() {
+        return None;
+    }
+
+    let closest = enhance::closest(unknown, candidates.iter().map(String::as_str));
+    let message = match closest.as_slice() {
+        [] => format!("valid {what} here are {}", quote_list(&candidates)),
+        [single] => format!("did you mean `{single}`?"),
+        several => format!("did you mean one of {}?", quote_list(several)),
+    };
+
+    Some(message)
+}
+
+fn quote_list<S: AsRef<str>>(values: &[S]) -> String {
+    values
+        .iter()
+        .map(|value| format!("`{}`", value.as_ref()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Path of keys and indices from the root of the document to the object or
+/// value the error is about.
+fn error_path(err: &SerdeError) -> Option<Vec<String>> {
+    // serde_yaml prefixes the message with the path, e.g.
+    // `inner[0].env: unknown variant`
+    if let Some(index) = err.message.find(": unknown ") {
+        let prefix = &err.message[..index];
+        if !prefix.contains(' ') {
+            return Some(
+                prefix
+                    .split(['.', '['])
+                    .map(|segment| segment.trim_end_matches(']').to_string())
+                    .filter(|segment| !segment.is_empty())
+                    .collect(),
+            );
+        }
+    }
+
+    // toml appends the dotted key, e.g. `for key `inner.env``
+    if let Some(key) = enhance::quoted_after(&err.message, " for key `") {
+        return Some(key.split('.').map(ToString::to_string).collect());
+    }
+
+    match (err.line, err.column) {
+        (Some(line), Some(column)) => {
+            let offset = enhance::byte_offset(err.input.text(), line, column)?;
+            // Inputs that are not JSON, like YAML without a path, are about
+            // the top level
+            Some(crate::locate::json_path_at(err.input.text(), offset).unwrap_or_default())
+        }
+        // Without a location the error is about the top level
+        _ => Some(Vec::new()),
+    }
+}
+
+/// The schema itself together with all schemas it references or combines.
+fn alternatives<'a>(
+    root: &'a RootSchema,
+    schema: &'a SchemaObject,
+    depth: usize,
+) -> Vec<&'a SchemaObject> {
+    if depth > MAX_DEPTH {
+        return Vec::new();
+    }
+
+    let mut found = vec![schema];
+
+    if let Some(reference) = &schema.reference {
+        let name = reference.trim_start_matches("#/definitions/");
+        if let Some(Schema::Object(definition)) = root.definitions.get(name) {
+            found.extend(alternatives(root, definition, depth + 1));
+        }
+    }
+
+    if let Some(subschemas) = &schema.subschemas {
+        let combined = [&subschemas.all_of, &subschemas.any_of, &subschemas.one_of];
+
+        for schemas in combined.iter().copied().flatten() {
+            for subschema in schemas {
+                if let Schema::Object(subschema) = subschema {
+                    found.extend(alternatives(root, subschema, depth + 1));
+                }
+            }
+        }
+    }
+
+    found
+}
+
+/// Find the schema of the child with the given key or index.
+fn step<'a>(
+    root: &'a RootSchema,
+    schema: &'a SchemaObject,
+    segment: &str,
+    depth: usize,
+) -> Option<&'a SchemaObject> {
+    let candidates = alternatives(root, schema, depth);
+    let object = |schema: &'a Schema| match schema {
+        Schema::Object(object) => Some(object),
+        Schema::Bool(_) => None,
+    };
+
+    let property = candidates.iter().find_map(|candidate| {
+        let validation = candidate.object.as_ref()?;
+
+        validation
+            .properties
+            .get(segment)
+            .or(validation.additional_properties.as_deref())
+    });
+
+    if let Some(property) = property {
+        return object(property);
+    }
+
+    let items = candidates
+        .iter()
+        .find_map(|candidate| candidate.array.as_ref()?.items.as_ref())?;
+
+    match (items, segment.parse::<usize>()) {
+        (SingleOrVec::Single(item), Ok(_)) => object(item),
+        (SingleOrVec::Vec(items), Ok(index)) => object(items.get(index)?),
+        // toml does not include the index of arrays of tables in the key so
+        // look up the key in the items instead
+        (SingleOrVec::Single(item), Err(_)) if depth < MAX_DEPTH => {
+            step(root, object(item)?, segment, depth + 1)
+        }
+        _ => None,
+    }
+}
+
+fn collect_keys(root: &RootSchema, schema: &SchemaObject, keys: &mut Vec<String>, depth: usize) {
+    let candidates = alternatives(root, schema, depth);
+
+    for candidate in &candidates {
+        if let Some(validation) = &candidate.object {
+            for key in validation.properties.keys() {
+                if !keys.contains(key) {
+                    keys.push(key.clone());
+                }
+            }
+        }
+    }
+
+    // Arrays of tables in toml report the key of the array
+    if keys.is_empty() && depth < MAX_DEPTH {
+        let item = candidates.iter().find_map(|candidate| {
+            match candidate.array.as_ref()?.items.as_ref()? {
+                SingleOrVec::Single(item) => Some(item),
+                SingleOrVec::Vec(_) => None,
+            }
+        });
+
+        if let Some(Schema::Object(item)) = item.map(AsRef::as_ref) {
+            collect_keys(root, item, keys, depth + 1);
+        }
+    }
+}
+
+fn collect_variants(
+    root: &RootSchema,
+    schema: &SchemaObject,
+    variants: &mut Vec<String>,
+    depth: usize,
+) {
+    for candidate in alternatives(root, schema, depth) {
+        let values = candidate
+            .enum_values
+            .iter()
+            .flatten()
+            .chain(candidate.const_value.as_ref())
+            .filter_map(|value| value.as_str().map(ToString::to_string));
+
+        // Variants with data are objects with the variant name as the only key
+        let tagged = candidate
+            .object
+            .as_ref()
+            .filter(|validation| validation.properties.len() == 1 && validation.required.len() == 1)
+            .and_then(|validation| validation.required.iter().next().cloned());
+
+        for variant in values.chain(tagged) {
+            if !variants.contains(&variant) {
+                variants.push(variant);
+            }
+        }
+    }
+}
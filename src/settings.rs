@@ -0,0 +1,486 @@
+use std::sync::atomic::Ordering;
+
+use crate::{
+    HyperlinkScheme,
+    Labels,
+    CONTEXTUALIZE,
+    CONTEXTUALIZE_DEFAULT,
+    CONTEXT_CHARACTERS,
+    CONTEXT_CHARACTERS_DEFAULT,
+    CONTEXT_LINES,
+    CONTEXT_LINES_DEFAULT,
+    SEPARATOR,
+    TAB_WIDTH_DEFAULT,
+};
+
+/// Settings that control how a [`SerdeError`](crate::SerdeError) is rendered.
+/// The [`Default`] implementation uses the documented defaults of the crate
+/// and ignores changes made to the global defaults.
+///
+/// ```rust
+/// use format_serde_error::{
+///     RenderSettings,
+///     SerdeError,
+/// };
+///
+/// let settings = RenderSettings {
+///     context_lines: 1,
+///     ..RenderSettings::default()
+/// };
+///
+/// let err = SerdeError::with_config(
+///     "a: b".to_string(),
+///     ("error".into(), Some(1), Some(3)),
+///     settings,
+/// );
+/// # let _ = err;
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[allow(clippy::struct_excessive_bools)]
+pub struct RenderSettings {
+    /// If the output should be contextualized or not. See
+    /// [`SerdeError::set_contextualize`](crate::SerdeError::set_contextualize).
+    pub contextualize: bool,
+
+    /// Amount of lines shown before and after the error. See
+    /// [`SerdeError::set_context_lines`](crate::SerdeError::set_context_lines).
+    pub context_lines: usize,
+
+    /// Amount of characters shown before and after the error when shortening
+    /// long lines. See
+    /// [`SerdeError::set_context_characters`](crate::SerdeError::set_context_characters).
+    pub context_characters: usize,
+
+    /// If line numbers are shown in front of the lines. See
+    /// [`SerdeError::set_show_line_numbers`](crate::SerdeError::set_show_line_numbers).
+    pub show_line_numbers: bool,
+
+    /// Amount of spaces a tab is replaced with. See
+    /// [`SerdeError::set_tab_width`](crate::SerdeError::set_tab_width).
+    pub tab_width: usize,
+
+    /// If missing field errors point at the start of the object. See
+    /// [`SerdeError::set_relocate_missing_field`](crate::SerdeError::set_relocate_missing_field).
+    pub relocate_missing_field: bool,
+
+    /// If errors about the value of a key also mark the key. See
+    /// [`SerdeError::set_mark_key`](crate::SerdeError::set_mark_key).
+    pub mark_key: bool,
+
+    /// Placeholder printed when the input is empty. See
+    /// [`SerdeError::set_empty_input_placeholder`](crate::SerdeError::set_empty_input_placeholder).
+    pub empty_input_placeholder: Option<String>,
+
+    /// If a preview of the value the error is about is shown. See
+    /// [`SerdeError::set_show_value_preview`](crate::SerdeError::set_show_value_preview).
+    pub show_value_preview: bool,
+
+    /// If the byte offset of the error is appended to the message. See
+    /// [`SerdeError::set_show_byte_offset`](crate::SerdeError::set_show_byte_offset).
+    pub show_byte_offset: bool,
+
+    /// If errors about the length of a sequence underline the whole sequence.
+    /// See
+    /// [`SerdeError::set_underline_sequence`](crate::SerdeError::set_underline_sequence).
+    pub underline_sequence: bool,
+
+    /// Separator in front of the lines with carets, underlines and labels. See
+    /// [`SerdeError::set_annotation_separator`](crate::SerdeError::set_annotation_separator).
+    pub annotation_separator: String,
+
+    /// Format of the hyperlink for the line number of the error. See
+    /// [`SerdeError::set_hyperlink_scheme`](crate::SerdeError::set_hyperlink_scheme).
+    pub hyperlink_scheme: HyperlinkScheme,
+
+    /// If the rendering depends on the category of the error. See
+    /// [`SerdeError::set_categorize`](crate::SerdeError::set_categorize).
+    pub categorize: bool,
+
+    /// Width the whole report is wrapped to. See
+    /// [`SerdeError::set_viewport_width`](crate::SerdeError::set_viewport_width).
+    pub viewport_width: Option<usize>,
+
+    /// Which characters count as indentation. See
+    /// [`SerdeError::set_indentation_whitespace`](crate::SerdeError::set_indentation_whitespace).
+    pub indentation_whitespace: IndentWs,
+
+    /// Amount of spaces every rendered line is indented with. See
+    /// [`SerdeError::set_block_indent`](crate::SerdeError::set_block_indent).
+    pub block_indent: usize,
+
+    /// On which side of the caret the message is placed. See
+    /// [`SerdeError::set_text_direction`](crate::SerdeError::set_text_direction).
+    pub text_direction: TextDirection,
+
+    /// How much of the error is rendered. See
+    /// [`SerdeError::set_render_style`](crate::SerdeError::set_render_style).
+    pub render_style: RenderStyle,
+
+    /// Fixed strings added to the output. See
+    /// [`SerdeError::set_labels`](crate::SerdeError::set_labels).
+    pub labels: Labels,
+
+    /// If the column of the error is printed next to the caret. See
+    /// [`SerdeError::set_show_column_number`](crate::SerdeError::set_show_column_number).
+    pub show_column_number: bool,
+
+    /// If shortened error lines are split into multiple windows. See
+    /// [`SerdeError::set_multiple_windows`](crate::SerdeError::set_multiple_windows).
+    pub multiple_windows: bool,
+
+    /// If the top-level key the error is nested under is always shown. See
+    /// [`SerdeError::set_show_anchor_line`](crate::SerdeError::set_show_anchor_line).
+    pub show_anchor_line: bool,
+
+    /// If a header with statistics about the input is shown. See
+    /// [`SerdeError::set_show_stats`](crate::SerdeError::set_show_stats).
+    pub show_stats: bool,
+
+    /// If the total amount of lines is shown next to the caret. See
+    /// [`SerdeError::set_show_total_lines`](crate::SerdeError::set_show_total_lines).
+    pub show_total_lines: bool,
+
+    /// If ANSI escape sequences are removed from the message and the input.
+    /// See [`SerdeError::set_strip_ansi`](crate::SerdeError::set_strip_ansi).
+    pub strip_ansi: bool,
+
+    /// If the position serde appends to the message is removed. See
+    /// [`SerdeError::set_strip_position_suffix`](crate::SerdeError::set_strip_position_suffix).
+    pub strip_position_suffix: bool,
+
+    /// If the original message is shown below the cleaned one. See
+    /// [`SerdeError::set_show_raw_message`](crate::SerdeError::set_show_raw_message).
+    pub show_raw_message: bool,
+
+    /// If the location the error was created at is shown below the message.
+    /// See [`SerdeError::set_show_constructed_at`](crate::SerdeError::set_show_constructed_at).
+    pub show_constructed_at: bool,
+
+    /// If the backtrace of the creation of the error is shown. See
+    /// [`SerdeError::set_show_backtrace`](crate::SerdeError::set_show_backtrace).
+    #[cfg(feature = "backtrace")]
+    pub show_backtrace: bool,
+
+    /// Width the message next to the caret is wrapped to. See
+    /// [`SerdeError::set_max_message_width`](crate::SerdeError::set_max_message_width).
+    pub max_message_width: Option<MaxMessageWidth>,
+
+    /// Where the error column is placed in shortened lines. See
+    /// [`SerdeError::set_caret_anchor`](crate::SerdeError::set_caret_anchor).
+    pub caret_anchor: Option<f32>,
+
+    /// How lines that are too long are shown. See
+    /// [`SerdeError::set_long_line_mode`](crate::SerdeError::set_long_line_mode).
+    pub long_line_mode: LongLineMode,
+
+    /// Width lines are wrapped to with [`LongLineMode::Wrap`]. See
+    /// [`SerdeError::set_wrap_width`](crate::SerdeError::set_wrap_width).
+    pub wrap_width: Option<usize>,
+
+    /// If context lines are wrapped with [`LongLineMode::Wrap`]. See
+    /// [`SerdeError::set_wrap_context_lines`](crate::SerdeError::set_wrap_context_lines).
+    pub wrap_context_lines: bool,
+
+    /// If the byte offsets of the lines are shown in the gutter. See
+    /// [`SerdeError::set_show_byte_offsets`](crate::SerdeError::set_show_byte_offsets).
+    pub show_byte_offsets: bool,
+
+    /// How the lines are numbered in the gutter. See
+    /// [`SerdeError::set_line_numbering`](crate::SerdeError::set_line_numbering).
+    pub line_numbering: LineNumbering,
+
+    /// Which lines are shown around the error line. See
+    /// [`SerdeError::set_context_mode`](crate::SerdeError::set_context_mode).
+    pub context_mode: ContextMode,
+
+    /// Where the message is placed relative to the error line. See
+    /// [`SerdeError::set_annotation_style`](crate::SerdeError::set_annotation_style).
+    pub annotation_style: AnnotationStyle,
+
+    /// Text that is highlighted wherever it occurs in the shown lines. See
+    /// [`SerdeError::set_highlight_term`](crate::SerdeError::set_highlight_term).
+    pub highlight_term: Option<String>,
+
+    /// If whitespace in the shown lines is made visible. See
+    /// [`SerdeError::set_show_whitespace`](crate::SerdeError::set_show_whitespace).
+    pub show_whitespace: bool,
+
+    /// If built-in hints for common mistakes are shown. See
+    /// [`SerdeError::set_hints`](crate::SerdeError::set_hints).
+    pub hints: bool,
+
+    /// If errors about the input ending early are marked behind the last
+    /// character. See
+    /// [`SerdeError::set_eof_marker`](crate::SerdeError::set_eof_marker).
+    pub eof_marker: bool,
+
+    /// If inputs with a single line are shown with the full frame. See
+    /// [`SerdeError::set_frame_single_line`](crate::SerdeError::set_frame_single_line).
+    pub frame_single_line: bool,
+
+    /// How the error is marked on the error line. See
+    /// [`SerdeError::set_marker_style`](crate::SerdeError::set_marker_style).
+    pub marker_style: MarkerStyle,
+
+    /// Unit the amount of context characters is measured in. See
+    /// [`SerdeError::set_context_chars_unit`](crate::SerdeError::set_context_chars_unit).
+    pub context_chars_unit: ContextUnit,
+}
+
+impl Default for RenderSettings {
+    fn default() -> Self {
+        Self {
+            contextualize: CONTEXTUALIZE_DEFAULT,
+            context_lines: CONTEXT_LINES_DEFAULT,
+            context_characters: CONTEXT_CHARACTERS_DEFAULT,
+            show_line_numbers: true,
+            tab_width: TAB_WIDTH_DEFAULT,
+            relocate_missing_field: false,
+            mark_key: false,
+            empty_input_placeholder: None,
+            show_value_preview: false,
+            show_byte_offset: false,
+            underline_sequence: false,
+            annotation_separator: SEPARATOR.to_string(),
+            hyperlink_scheme: HyperlinkScheme::default(),
+            categorize: false,
+            viewport_width: None,
+            indentation_whitespace: IndentWs::default(),
+            block_indent: 0,
+            text_direction: TextDirection::default(),
+            render_style: RenderStyle::default(),
+            labels: Labels::default(),
+            show_column_number: false,
+            multiple_windows: false,
+            show_anchor_line: false,
+            show_stats: false,
+            show_total_lines: false,
+            strip_ansi: true,
+            strip_position_suffix: false,
+            show_raw_message: false,
+            show_constructed_at: false,
+            #[cfg(feature = "backtrace")]
+            show_backtrace: false,
+            max_message_width: None,
+            caret_anchor: None,
+            long_line_mode: LongLineMode::default(),
+            wrap_width: None,
+            wrap_context_lines: true,
+            show_byte_offsets: false,
+            line_numbering: LineNumbering::default(),
+            context_mode: ContextMode::default(),
+            annotation_style: AnnotationStyle::default(),
+            highlight_term: None,
+            show_whitespace: false,
+            hints: true,
+            eof_marker: true,
+            frame_single_line: false,
+            marker_style: MarkerStyle::default(),
+            context_chars_unit: ContextUnit::default(),
+        }
+    }
+}
+
+/// Width the message next to the caret is wrapped to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum MaxMessageWidth {
+    /// Wrap the message after the given amount of columns.
+    Fixed(usize),
+
+    /// Wrap the message at the edge of the terminal. The width is taken from
+    /// the terminal with the `terminal_size` feature or from the `COLUMNS`
+    /// environment variable. The message is not wrapped if the width is
+    /// unknown.
+    Terminal,
+}
+
+/// Characters that count as indentation that is removed in front of the shown
+/// lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum IndentWs {
+    /// Every character that is whitespace according to Unicode, including for
+    /// example non-breaking spaces.
+    #[default]
+    Unicode,
+
+    /// Only ASCII spaces and tabs. Other whitespace is kept as it could be
+    /// part of the content.
+    AsciiOnly,
+}
+
+impl IndentWs {
+    pub(crate) fn is_indentation(self, c: char) -> bool {
+        match self {
+            Self::Unicode => c.is_whitespace(),
+            Self::AsciiOnly => c == ' ' || c == '\t',
+        }
+    }
+}
+
+/// Reading direction of the message next to the caret.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum TextDirection {
+    /// The message is placed behind the caret for left-to-right languages.
+    #[default]
+    Ltr,
+
+    /// The message is placed in front of the caret for right-to-left
+    /// languages. Messages that don't fit in front of the caret are placed
+    /// below it instead.
+    Rtl,
+}
+
+/// How much of an error is rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum RenderStyle {
+    /// The snippet with the context lines around the error, preceded by an
+    /// empty line, followed by the notes.
+    #[default]
+    Full,
+
+    /// Only the error line with the caret and the notes without the empty line
+    /// in front.
+    Compact,
+
+    /// A single line with the position and the message like
+    /// `config.yaml:2:9: invalid type` without colors. Without a path the
+    /// position is written as `line 2 column 9`. Meant for line oriented logs
+    /// like the ones of CI systems.
+    Minimal,
+
+    /// A single line with what was expected and found, the path of the value
+    /// and the position like `expected string, found map @ values[2] (4:10)`
+    /// without colors. Meant for the messages of failed assertions in tests.
+    Assertion,
+}
+
+/// How the lines are numbered in the gutter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum LineNumbering {
+    /// Show the line number of the error line in the input.
+    #[default]
+    Absolute,
+
+    /// Show the distance to the error line for every line, `0` for the error
+    /// line itself and for example `-3` and `+3` for the lines around it. The
+    /// message keeps the absolute position.
+    Relative,
+}
+
+/// Which lines are shown around the error line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ContextMode {
+    /// The amount of lines set with
+    /// [`SerdeError::set_context_lines`](crate::SerdeError::set_context_lines)
+    /// before and after the error line.
+    #[default]
+    Lines,
+
+    /// The nearest lines in front of the error line that are less indented
+    /// than the lines after them, for example the keys of the YAML mappings
+    /// the error line is nested in. At most as many lines as set with
+    /// [`SerdeError::set_context_lines`](crate::SerdeError::set_context_lines)
+    /// are shown. Left out lines between them are marked.
+    Structural,
+}
+
+/// How lines that are too long are shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum LongLineMode {
+    /// Only show the part of the error line around the error. See
+    /// [`SerdeError::set_context_characters`](crate::SerdeError::set_context_characters).
+    #[default]
+    Truncate,
+
+    /// Wrap lines into multiple rows of the width set with
+    /// [`SerdeError::set_wrap_width`](crate::SerdeError::set_wrap_width) or the
+    /// width of the terminal. Rows after the first are marked with `↪` in front
+    /// of the separator. Falls back to [`LongLineMode::Truncate`] if the width
+    /// of the terminal is unknown.
+    Wrap,
+}
+
+/// How the error is marked on the error line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum MarkerStyle {
+    /// Carets below the error column or below the whole span of the error.
+    #[default]
+    Caret,
+
+    /// A box around the span of the error with `┌─┐` above and `└─┘` below the
+    /// error line. The message is placed behind the bottom of the box. Errors
+    /// without a span or with a span of a single character are marked with a
+    /// caret.
+    Box,
+}
+
+/// Where the message is placed relative to the error line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum AnnotationStyle {
+    /// The message is shown behind a caret in the row below the error line.
+    #[default]
+    CaretBelow,
+
+    /// The message is shown behind the error line like
+    /// `port: x  <-- invalid type` if both fit into the viewport width or the
+    /// width of the terminal. Messages that are too long are shortened as long
+    /// as enough of them is left. Falls back to
+    /// [`AnnotationStyle::CaretBelow`] otherwise and for errors with labels,
+    /// underlines or messages with multiple lines.
+    Inline,
+}
+
+/// Unit the amount of context characters of a shortened long line is measured
+/// in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ContextUnit {
+    /// Count grapheme clusters so characters made of multiple code points are
+    /// never split. Counts characters without the `graphemes_support` feature.
+    #[default]
+    Graphemes,
+
+    /// Count characters, every code point counts as one.
+    Chars,
+
+    /// Count display columns so wide characters, for example CJK characters,
+    /// count double and the shortened line fits the given amount of columns.
+    /// Counts every character as one column without the `unicode_width`
+    /// feature.
+    Columns,
+}
+
+impl RenderSettings {
+    /// Settings using the current global defaults like
+    /// [`set_default_context_lines`](crate::set_default_context_lines).
+    pub(crate) fn from_global_defaults() -> Self {
+        Self {
+            contextualize: CONTEXTUALIZE.load(Ordering::Relaxed),
+            context_lines: CONTEXT_LINES.load(Ordering::Relaxed),
+            context_characters: CONTEXT_CHARACTERS.load(Ordering::Relaxed),
+            labels: crate::get_default_labels(),
+            ..Self::default()
+        }
+    }
+}
@@ -0,0 +1,98 @@
+//! Input of an error, either as one text or as lines that were split before.
+//! See [`SerdeError::from_lines`](crate::SerdeError::from_lines).
+
+use std::sync::OnceLock;
+
+/// Input of an error. The lines of split input are used directly to render
+/// the error line and its context. The joined text is only built when a byte
+/// offset into the input is needed.
+#[derive(Debug)]
+pub(crate) enum Source {
+    Text(String),
+    Lines {
+        lines: Vec<String>,
+        text: OnceLock<String>,
+    },
+}
+
+impl From<String> for Source {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+
+impl From<Vec<String>> for Source {
+    fn from(lines: Vec<String>) -> Self {
+        Self::Lines {
+            lines,
+            text: OnceLock::new(),
+        }
+    }
+}
+
+impl PartialEq for Source {
+    fn eq(&self, other: &Self) -> bool {
+        self.text() == other.text()
+    }
+}
+
+impl Source {
+    /// Complete input. Lines are joined with `\n` so lines that still end
+    /// with `\r` keep their `\r\n` line ending.
+    pub(crate) fn text(&self) -> &str {
+        match self {
+            Self::Text(text) => text,
+            Self::Lines { lines, text } => text.get_or_init(|| lines.join("\n")),
+        }
+    }
+
+    /// Lines of the input without line endings like [`str::lines`].
+    pub(crate) fn lines(&self) -> Box<dyn Iterator<Item = &str> + '_> {
+        match self {
+            Self::Text(text) => Box::new(text.lines()),
+            Self::Lines { lines, .. } => {
+                Box::new(counted(lines).iter().map(|line| without_cr(line)))
+            }
+        }
+    }
+
+    /// Line starting at 1 without its line ending.
+    pub(crate) fn line(&self, line: usize) -> Option<&str> {
+        let index = line.checked_sub(1)?;
+
+        match self {
+            Self::Text(text) => text.lines().nth(index),
+            Self::Lines { lines, .. } => counted(lines).get(index).map(|line| without_cr(line)),
+        }
+    }
+
+    /// Returns true if the input is empty.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Length of the input in bytes.
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Self::Text(text) => text.len(),
+            Self::Lines { lines, .. } => {
+                lines.iter().map(String::len).sum::<usize>() + lines.len().saturating_sub(1)
+            }
+        }
+    }
+}
+
+/// Lines that are counted like the lines of the joined text. Splitting input
+/// that ends with a line break gives an empty last line which [`str::lines`]
+/// does not return.
+fn counted(lines: &[String]) -> &[String] {
+    match lines.split_last() {
+        Some((last, rest)) if last.is_empty() => rest,
+        _ => lines,
+    }
+}
+
+/// Line without the `\r` of a `\r\n` line ending.
+fn without_cr(line: &str) -> &str {
+    line.strip_suffix('\r').unwrap_or(line)
+}
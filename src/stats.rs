@@ -0,0 +1,114 @@
+//! Statistics about the input shown in the header of the excerpt. See
+//! [`SerdeError::set_show_stats`](crate::SerdeError::set_show_stats).
+
+use crate::{
+    source::Source,
+    Labels,
+    Severity,
+};
+
+/// Size of the input. Computed once as counting the lines of large documents
+/// is not free.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct InputStats {
+    lines: usize,
+    bytes: usize,
+}
+
+impl InputStats {
+    pub(crate) fn new(input: &Source) -> Self {
+        Self {
+            lines: input.lines().count(),
+            bytes: input.len(),
+        }
+    }
+
+    /// Amount of lines in the input.
+    pub(crate) fn lines(self) -> usize {
+        self.lines
+    }
+
+    /// Header like `error at line 114 of 2,341 (column 12), 184 KiB document`.
+    /// `line` starts at 1 and `column` at 0. Only the size is shown if the
+    /// position is unknown.
+    pub(crate) fn header(
+        self,
+        labels: &Labels,
+        severity: Severity,
+        line: Option<usize>,
+        column: Option<usize>,
+    ) -> String {
+        let size = format!("{} {}", human_size(self.bytes), labels.document);
+
+        let Some(line) = line else {
+            return size;
+        };
+
+        let severity = match severity {
+            Severity::Error => &labels.error,
+            Severity::Warning => &labels.warning,
+        };
+
+        let column = column
+            .map(|column| format!(" ({} {})", labels.column, thousands(column + 1)))
+            .unwrap_or_default();
+
+        format!(
+            "{} {} {} {} {}{}, {}",
+            severity,
+            labels.at_line,
+            thousands(line),
+            labels.of,
+            thousands(self.lines),
+            column,
+            size
+        )
+    }
+}
+
+/// Number with a comma between every group of three digits like `2,341`.
+// `is_multiple_of` needs a newer compiler than the crate supports
+#[allow(clippy::manual_is_multiple_of)]
+pub(crate) fn thousands(number: usize) -> String {
+    let digits = number.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index) % 3 == 0 {
+            grouped.push(',');
+        }
+
+        grouped.push(digit);
+    }
+
+    grouped
+}
+
+/// Amount of bytes in the largest binary unit that keeps the value above 1
+/// like `184 KiB`. Values below 10 keep one decimal.
+fn human_size(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = UNITS[0];
+
+    for next in UNITS.iter().skip(1) {
+        if size < 1024.0 {
+            break;
+        }
+
+        size /= 1024.0;
+        unit = next;
+    }
+
+    if size < 10.0 {
+        format!("{size:.1} {unit}")
+    } else {
+        format!("{size:.0} {unit}")
+    }
+}
@@ -0,0 +1,99 @@
+use pretty_assertions::assert_eq;
+
+use super::SerdeError;
+
+const YAML: &str = "name: app
+server:
+  http:
+    host: localhost
+    port: 80
+    timeout: 30
+    retries: 3
+    backoff: 2
+    limit: x
+    burst: 10";
+
+/// The top-level key is pinned in front of the context lines and the left out
+/// lines are marked.
+#[test]
+fn yaml() {
+    super::init();
+
+    let mut err = SerdeError::new(YAML.to_string(), ("invalid type".into(), Some(9), Some(11)));
+    err.set_context_lines(1).set_show_anchor_line(true);
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | server:\n");
+    expected.push_str(" ...\n");
+    expected.push_str("   |     backoff: 2\n");
+    expected.push_str(" 9 |     limit: x\n");
+    expected.push_str("   |            ^ invalid type\n");
+    expected.push_str("   |     burst: 10\n");
+
+    assert_eq!(expected, err.to_string());
+}
+
+/// No marker is shown if the anchor line is right in front of the context
+/// lines.
+#[test]
+fn yaml_adjacent() {
+    super::init();
+
+    let mut err = SerdeError::new(YAML.to_string(), ("invalid type".into(), Some(4), Some(10)));
+    err.set_context_lines(1).set_show_anchor_line(true);
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | server:\n");
+    expected.push_str("   |   http:\n");
+    expected.push_str(" 4 |     host: localhost\n");
+    expected.push_str("   |           ^ invalid type\n");
+    expected.push_str("   |     port: 80\n");
+
+    assert_eq!(expected, err.to_string());
+}
+
+/// The key of the root object that contains the error is pinned.
+#[test]
+fn json() {
+    super::init();
+
+    let input = r#"{
+  "name": "app",
+  "server": {
+    "host": "localhost",
+    "port": 80,
+    "limit": "x"
+  }
+}"#;
+
+    let mut err = SerdeError::new(
+        input.to_string(),
+        ("invalid type".into(), Some(6), Some(13)),
+    );
+    err.set_context_lines(0).set_show_anchor_line(true);
+
+    let mut expected = String::from("\n");
+    expected.push_str(r#"   | "server": {"#);
+    expected.push('\n');
+    expected.push_str(" ...\n");
+    expected.push_str(r#" 6 |   "limit": "x""#);
+    expected.push('\n');
+    expected.push_str("   |            ^ invalid type\n");
+
+    assert_eq!(expected, err.to_string());
+}
+
+/// Errors on top-level keys have no anchor line.
+#[test]
+fn not_nested() {
+    super::init();
+
+    let mut err = SerdeError::new(YAML.to_string(), ("invalid type".into(), Some(1), Some(6)));
+    err.set_context_lines(0).set_show_anchor_line(true);
+
+    let mut expected = String::from("\n");
+    expected.push_str(" 1 | name: app\n");
+    expected.push_str("   |       ^ invalid type\n");
+
+    assert_eq!(expected, err.to_string());
+}
@@ -0,0 +1,37 @@
+use pretty_assertions::assert_eq;
+
+use super::SerdeError;
+
+const MESSAGE: &str = "\u{1b}[1;31minvalid type\u{1b}[0m: expected a \u{1b}[32mstring\u{1b}[0m";
+const INPUT: &str =
+    "name: \u{1b}[32mvalue\u{1b}[0m\nkey: \u{1b}]8;;file:///a\u{1b}\\link\u{1b}]8;;\u{7} 1";
+
+/// Escape sequences are removed from the message and the lines and the caret
+/// points at the same character as before.
+#[test]
+fn strip_ansi() {
+    super::init();
+
+    // The `1` is behind the hyperlink escape sequences at column 32
+    let err = SerdeError::new(INPUT.to_string(), (MESSAGE.into(), Some(2), Some(32)));
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | name: value\n");
+    expected.push_str(" 2 | key: link 1\n");
+    expected.push_str("   |           ^ invalid type: expected a string\n");
+
+    assert!(err.get_strip_ansi());
+    assert_eq!(expected, err.to_string());
+    assert_eq!(MESSAGE, err.message());
+}
+
+/// With stripping disabled the escape sequences are kept.
+#[test]
+fn keep_ansi() {
+    super::init();
+
+    let mut err = SerdeError::new(INPUT.to_string(), (MESSAGE.into(), None, None));
+    err.set_strip_ansi(false);
+
+    assert_eq!(format!("{}\n", MESSAGE), err.to_string());
+}
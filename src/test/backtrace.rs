@@ -0,0 +1,32 @@
+use std::backtrace::{
+    Backtrace,
+    BacktraceStatus,
+};
+
+use crate::SerdeError;
+
+/// The backtrace is captured like the ones of the standard library depending
+/// on the environment so both cases are checked.
+#[test]
+fn backtrace() {
+    super::init();
+
+    let mut err = SerdeError::new(
+        "a: b".to_string(),
+        ("invalid type".into(), Some(1), Some(3)),
+    );
+    let without = err.to_string();
+
+    assert_eq!(err.backtrace().status(), Backtrace::capture().status());
+
+    err.set_show_backtrace(true);
+    let with = err.to_string();
+
+    assert!(with.starts_with(&without));
+
+    if err.backtrace().status() == BacktraceStatus::Captured {
+        assert!(with.len() > without.len());
+    } else {
+        assert_eq!(with, without);
+    }
+}
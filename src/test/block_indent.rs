@@ -0,0 +1,23 @@
+use pretty_assertions::assert_eq;
+
+use crate::SerdeError;
+
+#[test]
+fn block_indent() {
+    super::init();
+
+    let input = "name: test\nport: eighty\nhost: localhost";
+    let mut err = SerdeError::new(input.to_string(), ("invalid type".into(), Some(2), Some(6)));
+    err.set_block_indent(4);
+
+    let mut expected = String::from("    \n");
+    expected.push_str("       | name: test\n");
+    expected.push_str("     2 | port: eighty\n");
+    expected.push_str("       |       ^ invalid type\n");
+    expected.push_str("       | host: localhost\n");
+
+    let got = err.to_string();
+
+    assert_eq!(expected, got);
+    assert!(got.lines().all(|line| line.starts_with("    ")));
+}
@@ -0,0 +1,190 @@
+use std::io;
+
+use pretty_assertions::assert_eq;
+
+use super::{
+    Config,
+    SerdeError,
+};
+use crate::{
+    Category,
+    ErrorAdapter,
+    ErrorKind,
+};
+
+fn render(input: &str) -> (Option<Category>, String) {
+    let err = serde_json::from_str::<Config>(input).expect_err("input should not parse");
+    let mut err = SerdeError::new(input.to_string(), err);
+
+    (err.category(), err.set_categorize(true).to_string())
+}
+
+#[test]
+fn not_json() {
+    super::init();
+
+    let err = SerdeError::new("input".to_string(), ("error".into(), Some(1), Some(1)));
+
+    assert_eq!(None, err.category());
+    assert_eq!(ErrorKind::Unknown, err.kind());
+}
+
+/// Every category of `serde_json` maps to the same kind.
+#[test]
+fn kind() {
+    let cases = [
+        ("{\n  \"values\": [\n", ErrorKind::Eof),
+        (r#"{"values": ["a" "b"]}"#, ErrorKind::Syntax),
+        (r#"{"values": ["a", 1]}"#, ErrorKind::Data),
+    ];
+
+    for (input, expected) in cases {
+        let err = serde_json::from_str::<Config>(input).expect_err("input should not parse");
+        let err = SerdeError::new(input.to_string(), err);
+
+        assert_eq!(expected, err.kind(), "{}", input);
+    }
+
+    let err = serde_json::from_reader::<_, Config>(FailingReader).expect_err("reading should fail");
+    let err = SerdeError::new(String::new(), err);
+
+    assert_eq!(ErrorKind::Io, err.kind());
+}
+
+/// The kind of `serde_yaml` errors is guessed from the message.
+#[cfg(feature = "serde_yaml")]
+#[test]
+fn kind_yaml() {
+    let cases = [
+        ("values: [a", ErrorKind::Syntax),
+        ("values: a", ErrorKind::Data),
+    ];
+
+    for (input, expected) in cases {
+        let err = serde_yaml::from_str::<Config>(input).expect_err("input should not parse");
+        let err = SerdeError::new(input.to_string(), err);
+
+        assert_eq!(expected, err.kind(), "{} {}", input, err.message());
+    }
+}
+
+#[test]
+fn error_kind() {
+    let input = "values: a";
+
+    let json = serde_json::from_str::<Config>(input).expect_err("input should not parse");
+    let custom = ("error".into(), Some(1), Some(0));
+
+    assert_eq!(
+        ErrorAdapter::Json,
+        SerdeError::new(input.to_string(), json).error_kind()
+    );
+    assert_eq!(
+        ErrorAdapter::Custom,
+        SerdeError::new(input.to_string(), custom).error_kind()
+    );
+}
+
+#[cfg(feature = "serde_yaml")]
+#[test]
+fn error_kind_yaml() {
+    let input = "values: a";
+
+    let yaml = serde_yaml::from_str::<Config>(input).expect_err("input should not parse");
+
+    assert_eq!(
+        ErrorAdapter::Yaml,
+        SerdeError::new(input.to_string(), yaml).error_kind()
+    );
+}
+
+#[test]
+fn eof() {
+    super::init();
+
+    let input = "{\n  \"values\": [\n    \"a\",\n    \"b\"\n";
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | {\n");
+    expected.push_str("   |   \"values\": [\n");
+    expected.push_str("   |     \"a\",\n");
+    expected.push_str(" 4 |     \"b\"\n");
+    expected.push_str("   |        ↵ expected more: EOF while parsing a list at line 5 column 0\n");
+    expected.push_str(
+        "   = note: the document ended unexpectedly while the `[` from line 2 was still open\n",
+    );
+
+    assert_eq!((Some(Category::Eof), expected), render(input));
+}
+
+#[test]
+fn eof_in_string() {
+    super::init();
+
+    let input = r#"{"values": ["a"#;
+
+    let mut expected = String::new();
+    expected.push_str(r#" {"values": ["a"#);
+    expected.push('\n');
+    expected.push_str("               ↵ expected more: ");
+    expected.push_str("EOF while parsing a string at line 1 column 14\n");
+    expected.push_str(
+        "  = note: the document ended unexpectedly while the `\"` from line 1 was still open\n",
+    );
+
+    assert_eq!((Some(Category::Eof), expected), render(input));
+}
+
+#[test]
+fn syntax() {
+    super::init();
+
+    let input = r#"{"values": ["a" "b"]}"#;
+
+    let mut expected = String::new();
+    expected.push_str(r#" {"values": ["a" "b"]}"#);
+    expected.push('\n');
+    expected.push_str("                  ^ expected `,` or `]` at line 1 column 17\n");
+
+    assert_eq!((Some(Category::Syntax), expected), render(input));
+}
+
+#[test]
+fn data() {
+    super::init();
+
+    let input = r#"{"values": ["a", 1]}"#;
+
+    let mut expected = String::new();
+    expected.push_str(r#" {"values": ["a", 1]}"#);
+    expected.push('\n');
+    expected.push_str(
+        "                  ^ invalid type: integer `1`, expected a string at line 1 column 18\n",
+    );
+    expected.push_str("  = note: type mismatch\n");
+    expected.push_str("  = note: the value here is `1`\n");
+    expected.push_str(
+        "  = help: quote the value to keep it a string: `{\"values\": [\"a\", \"1\"]}`\n",
+    );
+
+    assert_eq!((Some(Category::Data), expected), render(input));
+}
+
+struct FailingReader;
+
+impl io::Read for FailingReader {
+    fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+        Err(io::Error::other("disk on fire"))
+    }
+}
+
+#[test]
+fn io() {
+    super::init();
+
+    let err = serde_json::from_reader::<_, Config>(FailingReader).expect_err("reading should fail");
+    let mut err = SerdeError::new(String::new(), err);
+
+    assert_eq!(Some(Category::Io), err.category());
+    assert_eq!("disk on fire\n", err.set_categorize(true).to_string());
+}
@@ -7,3 +7,12 @@ use serde::{
 pub struct Config {
     values: Vec<String>,
 }
+
+#[cfg(all(
+    feature = "colored",
+    any(feature = "serde_json", feature = "serde_yaml")
+))]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NestedConfig {
+    inner: Config,
+}
@@ -0,0 +1,42 @@
+use pretty_assertions::assert_eq;
+
+use crate::SerdeError;
+
+#[test]
+fn constructed_at() {
+    super::init();
+
+    let line = line!() + 1;
+    let mut err = SerdeError::new(
+        "a: b".to_string(),
+        ("invalid type".into(), Some(1), Some(3)),
+    );
+
+    assert_eq!(err.constructed_at().file(), file!());
+    assert_eq!(err.constructed_at().line(), line);
+    assert!(format!("{err:?}").contains(file!()));
+
+    err.set_show_constructed_at(true);
+
+    let mut expected = String::new();
+    expected.push_str(" a: b\n");
+    expected.push_str("    ^ invalid type\n");
+    expected.push_str("  = constructed at ");
+    expected.push_str(file!());
+    expected.push(':');
+    expected.push_str(&line.to_string());
+    expected.push_str(":19\n");
+
+    assert_eq!(expected, err.to_string());
+}
+
+#[test]
+fn from_lines() {
+    let line = line!() + 1;
+    let err = SerdeError::from_lines(
+        vec!["a: b".to_string()],
+        ("invalid type".into(), Some(1), Some(3)),
+    );
+
+    assert_eq!(err.constructed_at().line(), line);
+}
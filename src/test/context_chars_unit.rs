@@ -0,0 +1,48 @@
+use pretty_assertions::assert_eq;
+
+use super::SerdeError;
+use crate::ContextUnit;
+
+const INPUT: &str = "first: line
+name: 名前名前名前名前名前名前名前名前名前名前!名前名前名前名前名前名前名前名前名前名前";
+
+#[test]
+fn graphemes() {
+    super::init();
+
+    let mut err = SerdeError::new(
+        INPUT.to_string(),
+        ("invalid value".into(), Some(2), Some(27)),
+    );
+    err.set_context_characters(5);
+
+    let got = err.to_string();
+    let error_line = got.lines().nth(2).unwrap_or_default();
+
+    assert_eq!(ContextUnit::Graphemes, err.get_context_chars_unit());
+    assert_eq!(" 2 | ...前名前名前!名前名前名...", error_line);
+}
+
+#[cfg(feature = "unicode_width")]
+#[test]
+fn columns() {
+    use unicode_width::UnicodeWidthStr;
+
+    super::init();
+
+    let mut err = SerdeError::new(
+        INPUT.to_string(),
+        ("invalid value".into(), Some(2), Some(27)),
+    );
+    err.set_context_characters(5)
+        .set_context_chars_unit(ContextUnit::Columns);
+
+    let got = err.to_string();
+    let error_line = got.lines().nth(2).unwrap_or_default();
+    let shown = error_line
+        .trim_start_matches(" 2 | ...")
+        .trim_end_matches("...");
+
+    assert_eq!(" 2 | ...名前!名前名...", error_line);
+    assert!(shown.width() <= 5 * 2 + 1);
+}
@@ -0,0 +1,68 @@
+use pretty_assertions::assert_eq;
+
+use crate::{
+    ContextMode,
+    SerdeError,
+};
+
+const INPUT: &str = "name: app
+services:
+  web:
+    image: nginx
+    replicas: 2
+    ports:
+      # public ports
+      - 80
+      - 443
+      - eighty
+    env: prod
+";
+
+fn error() -> SerdeError {
+    let mut err = SerdeError::new(
+        INPUT.to_string(),
+        (
+            "invalid type: string \"eighty\", expected u16".into(),
+            Some(10),
+            Some(8),
+        ),
+    );
+    err.set_context_mode(ContextMode::Structural);
+
+    err
+}
+
+/// The keys the error line is nested in are shown instead of its siblings.
+#[test]
+fn structural() {
+    super::init();
+
+    let mut expected = String::from("\n");
+    expected.push_str("    | services:\n");
+    expected.push_str("    |   web:\n");
+    expected.push_str("  ...\n");
+    expected.push_str("    |     ports:\n");
+    expected.push_str("  ...\n");
+    expected.push_str(" 10 |       - eighty\n");
+    expected.push_str("    |         ^ invalid type: string \"eighty\", expected u16\n");
+
+    assert_eq!(expected, error().to_string());
+}
+
+/// Only the closest ancestors are shown if there are more than context
+/// lines.
+#[test]
+fn structural_limited() {
+    super::init();
+
+    let mut err = error();
+    err.set_context_lines(1);
+
+    let mut expected = String::from("\n");
+    expected.push_str("    | ports:\n");
+    expected.push_str("  ...\n");
+    expected.push_str(" 10 |   - eighty\n");
+    expected.push_str("    |     ^ invalid type: string \"eighty\", expected u16\n");
+
+    assert_eq!(expected, err.to_string());
+}
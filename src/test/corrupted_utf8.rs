@@ -0,0 +1,50 @@
+use pretty_assertions::assert_eq;
+
+use crate::SerdeError;
+
+/// Latin-1 text in the title and a combining mark at the start of a line
+/// without a character to attach to, decoded with `from_utf8_lossy`.
+const INPUT: &[u8] = include_bytes!("../../resources/corrupted_utf8.yaml");
+
+fn render(line: usize, column: usize) -> String {
+    let input = String::from_utf8_lossy(INPUT).into_owned();
+
+    let mut err = SerdeError::new(input, ("invalid".into(), Some(line), Some(column)));
+    err.set_context_lines(0);
+    err.to_string()
+}
+
+/// Replacement characters are shown as they are and take up a single column.
+#[test]
+fn replacement_character() {
+    super::init();
+
+    let mut before = String::from("\n");
+    before.push_str(" 2 | title: caf\u{fffd} au lait\n");
+    before.push_str("   |        ^ invalid\n");
+
+    let mut after = String::from("\n");
+    after.push_str(" 2 | title: caf\u{fffd} au lait\n");
+    after.push_str("   |             ^ invalid\n");
+
+    assert_eq!(before, render(2, 7));
+    assert_eq!(after, render(2, 12));
+}
+
+/// Combining marks at the start of a line are attached to a space in front
+/// of them instead of the separator and the caret stays aligned.
+#[test]
+fn leading_combining_mark() {
+    super::init();
+
+    let mut mark = String::from("\n");
+    mark.push_str(" 3 |  \u{301}accent: x\n");
+    mark.push_str("   | ^ invalid\n");
+
+    let mut after = String::from("\n");
+    after.push_str(" 3 |  \u{301}accent: x\n");
+    after.push_str("   |           ^ invalid\n");
+
+    assert_eq!(mark, render(3, 0));
+    assert_eq!(after, render(3, 10));
+}
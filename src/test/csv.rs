@@ -0,0 +1,60 @@
+use pretty_assertions::assert_eq;
+use serde::Deserialize;
+
+use crate::SerdeError;
+
+const INPUT: &str = "name,port,note\n\"web, primary\",80,ok\ndb,\"5432x\",\"two\nlines\"\n";
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct Row {
+    name: String,
+    port: u16,
+    note: String,
+}
+
+/// The caret points at the field inside of its quotes.
+#[test]
+fn typed_field() {
+    super::init();
+
+    let err = csv::Reader::from_reader(INPUT.as_bytes())
+        .deserialize::<Row>()
+        .find_map(Result::err)
+        .expect("port should not be a number");
+
+    let mut err = SerdeError::new(INPUT.to_string(), err);
+    err.set_context_lines(1);
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | \"web, primary\",80,ok\n");
+    expected.push_str(" 3 | db,\"5432x\",\"two\n");
+    expected.push_str(
+        "   |     ^ CSV deserialize error: record 2 (line: 3, byte: 36): field 1: invalid digit \
+         found in string\n",
+    );
+    expected.push_str("   | lines\"\n");
+
+    assert_eq!(expected, err.to_string());
+    assert_eq!(Some(3), err.line());
+    assert_eq!(Some(4), err.column());
+}
+
+/// Records with the wrong amount of fields have no field so the whole line
+/// is underlined.
+#[test]
+fn unequal_lengths() {
+    super::init();
+
+    let input = "name,port\nweb,80\ndb\n";
+    let err = csv::Reader::from_reader(input.as_bytes())
+        .records()
+        .find_map(Result::err)
+        .expect("record should be too short");
+
+    let err = SerdeError::new(input.to_string(), err);
+
+    assert_eq!(Some(3), err.line());
+    assert_eq!(None, err.column());
+    assert_eq!(crate::ErrorAdapter::Csv, err.error_kind());
+}
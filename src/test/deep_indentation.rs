@@ -0,0 +1,109 @@
+use pretty_assertions::assert_eq;
+
+use crate::SerdeError;
+
+/// Input with the given indentation in front of every line and a long value
+/// on the second line.
+fn input(indentation: &str) -> String {
+    let value = "x".repeat(300);
+
+    format!("{indentation}a: 1\n{indentation}b: {value}\n{indentation}c: 2")
+}
+
+fn render(indentation: &str, column: usize, context_characters: usize) -> String {
+    let mut err = SerdeError::new(input(indentation), ("bad".into(), Some(2), Some(column)));
+    err.set_context_lines(1)
+        .set_context_characters(context_characters);
+
+    err.to_string()
+}
+
+/// Error line shortened to the start of the line.
+fn shortened_start() -> String {
+    let mut expected = String::from("\n");
+    expected.push_str("   | a: 1\n");
+    expected.push_str(" 2 | b: ");
+    expected.push_str(&"x".repeat(58));
+    expected.push_str("...\n");
+    expected.push_str("   |  ^ bad\n");
+    expected.push_str("   | c: 2\n");
+
+    expected
+}
+
+/// Error line shortened around the 200th column after the indentation. The
+/// column in the input is shown as the caret has been moved.
+fn shortened_middle(column: usize) -> String {
+    let mut expected = String::from("\n");
+    expected.push_str("   | a: 1\n");
+    expected.push_str(" 2 | ...");
+    expected.push_str(&"x".repeat(61));
+    expected.push_str("...\n");
+    expected.push_str("   | ");
+    expected.push_str(&" ".repeat(34));
+    expected.push_str("^ (column ");
+    expected.push_str(&column.to_string());
+    expected.push_str(") bad\n");
+    expected.push_str("   | c: 2\n");
+
+    expected
+}
+
+/// Error line shown completely.
+fn complete(caret: usize) -> String {
+    let mut expected = String::from("\n");
+    expected.push_str("   | a: 1\n");
+    expected.push_str(" 2 | b: ");
+    expected.push_str(&"x".repeat(300));
+    expected.push_str("\n   | ");
+    expected.push_str(&" ".repeat(caret));
+    expected.push_str("^ bad\n");
+    expected.push_str("   | c: 2\n");
+
+    expected
+}
+
+#[test]
+fn shortened() {
+    super::init();
+
+    for indentation in [100, 150, 200] {
+        let spaces = " ".repeat(indentation);
+
+        assert_eq!(shortened_start(), render(&spaces, indentation + 1, 30));
+        assert_eq!(
+            shortened_middle(indentation + 201),
+            render(&spaces, indentation + 200, 30)
+        );
+    }
+}
+
+#[test]
+fn not_shortened() {
+    super::init();
+
+    for indentation in [100, 150, 200] {
+        let spaces = " ".repeat(indentation);
+
+        assert_eq!(complete(1), render(&spaces, indentation + 1, 1000));
+        assert_eq!(complete(200), render(&spaces, indentation + 200, 1000));
+    }
+}
+
+/// Tabs are expanded before the indentation is removed and the line is
+/// shortened.
+#[test]
+fn tabs() {
+    super::init();
+
+    let tabs = "\t".repeat(60);
+    let render = |column: usize| {
+        let mut err = SerdeError::new(input(&tabs), ("bad".into(), Some(2), Some(column)));
+        err.set_context_lines(1).set_tab_width(2);
+
+        err.to_string()
+    };
+
+    assert_eq!(shortened_start(), render(61));
+    assert_eq!(shortened_middle(261), render(260));
+}
@@ -0,0 +1,66 @@
+use pretty_assertions::assert_eq;
+use serde::Deserialize;
+
+use super::{
+    Config,
+    SerdeError,
+};
+use crate::Deserializer;
+
+#[cfg(feature = "serde_yaml")]
+#[test]
+fn yaml() {
+    super::init();
+
+    let input = include_str!("../../resources/config.yaml");
+
+    let err = Config::deserialize(Deserializer::from_str_yaml(input))
+        .expect_err("input should not parse");
+    let expected = SerdeError::new(
+        input.to_string(),
+        serde_yaml::from_str::<Config>(input).expect_err("input should not parse"),
+    );
+
+    assert_eq!(expected.to_string(), err.to_string());
+}
+
+#[cfg(feature = "serde_yaml")]
+#[test]
+fn yaml_ok() {
+    super::init();
+
+    let result = Config::deserialize(Deserializer::from_str_yaml("values: ['first']"));
+
+    assert!(result.is_ok());
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn json() {
+    super::init();
+
+    let input = include_str!("../../resources/config_pretty.json");
+
+    let err = Config::deserialize(Deserializer::from_str_json(input))
+        .expect_err("input should not parse");
+    let expected = SerdeError::new(
+        input.to_string(),
+        serde_json::from_str::<Config>(input).expect_err("input should not parse"),
+    );
+
+    assert_eq!(expected.to_string(), err.to_string());
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn json_trailing_characters() {
+    super::init();
+
+    let input = r#"{"values": []} trailing"#;
+
+    let err = Config::deserialize(Deserializer::from_str_json(input))
+        .expect_err("input should not parse");
+
+    assert_eq!("trailing characters at line 1 column 16", err.message());
+    assert_eq!(Some(1), err.line());
+}
@@ -0,0 +1,68 @@
+use pretty_assertions::assert_eq;
+
+use crate::check_duplicate_keys;
+
+#[test]
+fn no_duplicates() {
+    super::init();
+
+    let input = r#"{"name": "a", "inner": {"name": "b", "list": [{"name": "c"}, {"name": "d"}]}}"#;
+
+    assert!(check_duplicate_keys(input).is_empty());
+}
+
+#[test]
+fn duplicate_with_first_definition() {
+    super::init();
+
+    let input = "{\n  \"name\": \"a\",\n  \"inner\": {\"name\": \"b\"},\n  \"name\": \"c\"\n}";
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | {\n");
+    expected.push_str("   |   \"name\": \"a\",\n");
+    expected.push_str("   |   - first defined here\n");
+    expected.push_str("   |   \"inner\": {\"name\": \"b\"},\n");
+    expected.push_str(" 4 |   \"name\": \"c\"\n");
+    expected.push_str("   |   ^ duplicate key `name`\n");
+    expected.push_str("   | }\n");
+
+    let errors = check_duplicate_keys(input);
+
+    assert_eq!(1, errors.len());
+    assert_eq!(expected, errors[0].to_string());
+}
+
+#[test]
+fn duplicates_in_nested_objects() {
+    super::init();
+
+    let input = r#"{"a": {"x": 1, "x": 2}, "b": [{"y": 1}, {"y": 2, "y": 3}], "a": {}}"#;
+
+    let found = check_duplicate_keys(input)
+        .iter()
+        .map(|err| (err.message().to_string(), err.column()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        vec![
+            ("duplicate key `x`".to_string(), Some(15)),
+            ("duplicate key `y`".to_string(), Some(49)),
+            ("duplicate key `a`".to_string(), Some(59)),
+        ],
+        found
+    );
+}
+
+#[test]
+fn stops_at_syntax_error() {
+    super::init();
+
+    let input = r#"{"a": 1, "a": 2, "b": oops "b": 3}"#;
+
+    let found = check_duplicate_keys(input)
+        .iter()
+        .map(|err| err.message().to_string())
+        .collect::<Vec<_>>();
+
+    assert_eq!(vec!["duplicate key `a`".to_string()], found);
+}
@@ -0,0 +1,50 @@
+use std::fmt;
+
+use pretty_assertions::assert_eq;
+
+use crate::SerdeError;
+
+#[derive(Debug)]
+struct ParseError {
+    line: usize,
+    column: usize,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("expected a number")
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+fn extract(err: &(dyn std::error::Error + 'static)) -> Option<(usize, usize)> {
+    err.downcast_ref::<ParseError>()
+        .map(|err| (err.line, err.column))
+}
+
+#[test]
+fn extracted_position() {
+    super::init();
+
+    let err = ParseError { line: 2, column: 6 };
+    let err = SerdeError::from_dyn_error("host: a\nport: x".to_string(), &err, extract);
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | host: a\n");
+    expected.push_str(" 2 | port: x\n");
+    expected.push_str("   |       ^ expected a number\n");
+
+    assert_eq!(expected, err.to_string());
+}
+
+/// Only the message is shown if the position can't be extracted.
+#[test]
+fn unknown_position() {
+    super::init();
+
+    let err = std::io::Error::other("unexpected end of file");
+    let err = SerdeError::from_dyn_error("port: 1".to_string(), &err, extract);
+
+    assert_eq!("unexpected end of file\n", err.to_string());
+}
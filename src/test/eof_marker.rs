@@ -0,0 +1,41 @@
+use pretty_assertions::assert_eq;
+
+use crate::SerdeError;
+
+fn error(input: &str) -> SerdeError {
+    let err =
+        serde_json::from_str::<serde_json::Value>(input).expect_err("input should be incomplete");
+
+    SerdeError::new(input.to_string(), err)
+}
+
+/// `serde_json` reports the error on the empty line after the input. The marker
+/// is moved behind the last character instead.
+#[test]
+fn behind_last_line() {
+    super::init();
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | {\n");
+    expected.push_str(" 2 |   \"a\": 1,\n");
+    expected.push_str("   |          ↵ expected more: ");
+    expected.push_str("EOF while parsing a value at line 3 column 0\n");
+
+    assert_eq!(expected, error("{\n  \"a\": 1,\n").to_string());
+}
+
+/// Without the marker the error is shown at the location reported by the
+/// parser.
+#[test]
+fn disabled() {
+    super::init();
+
+    let mut err = error("[1, 2");
+    err.set_eof_marker(false);
+
+    let mut expected = String::new();
+    expected.push_str(" [1, 2\n");
+    expected.push_str("      ^ EOF while parsing a list at line 1 column 5\n");
+
+    assert_eq!(expected, err.to_string());
+}
@@ -0,0 +1,34 @@
+use pretty_assertions::assert_eq;
+
+use crate::SerdeError;
+
+#[test]
+fn same_as_joined_input() {
+    super::init();
+
+    let lines = vec![
+        "name: test".to_string(),
+        "port: eighty".to_string(),
+        "host: localhost".to_string(),
+    ];
+
+    let joined = SerdeError::new(lines.join("\n"), ("invalid type".into(), Some(2), Some(6)));
+    let split = SerdeError::from_lines(lines, ("invalid type".into(), Some(2), Some(6)));
+
+    assert_eq!(joined.to_string(), split.to_string());
+}
+
+#[test]
+fn keeps_crlf_line_endings() {
+    super::init();
+
+    let input = "name: test\r\nport: eighty\r\nhost: localhost\r\n";
+    let lines = input.split('\n').map(String::from).collect::<Vec<_>>();
+
+    let joined = SerdeError::new(input.to_string(), ("invalid type".into(), Some(2), Some(6)));
+    let split = SerdeError::from_lines(lines, ("invalid type".into(), Some(2), Some(6)));
+
+    assert_eq!(joined.to_string(), split.to_string());
+    assert_eq!(split.input.text(), input);
+    assert_eq!(split.location(), joined.location());
+}
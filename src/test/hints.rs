@@ -0,0 +1,50 @@
+use pretty_assertions::assert_eq;
+
+use crate::{
+    hints,
+    SerdeError,
+};
+
+fn render(message: &str) -> String {
+    SerdeError::new("a: b".to_string(), (message.into(), Some(1), Some(3))).to_string()
+}
+
+/// Matching hints are shown in the order they were registered and are removed
+/// again at the end of the scope. All checks are part of one test as the
+/// registry is shared by the tests that run in parallel and a scope would
+/// remove the hints of another test.
+#[test]
+fn registry() {
+    super::init();
+
+    let message = "hints test: unknown setting";
+
+    let mut expected = String::new();
+    expected.push_str(" a: b\n");
+    expected.push_str("    ^ hints test: unknown setting\n");
+
+    hints::scoped(|| {
+        hints::register("hints test:", "check the spelling of the setting");
+        hints::register("unknown setting", "see the documentation for all settings");
+        hints::register("hints test: other", "not shown");
+
+        let mut expected = expected.clone();
+        expected.push_str("  = help: check the spelling of the setting\n");
+        expected.push_str("  = help: see the documentation for all settings\n");
+
+        assert_eq!(expected, render(message));
+    });
+
+    assert_eq!(expected, render(message));
+
+    // Hints are also removed if the scope panics
+    let result = std::panic::catch_unwind(|| {
+        hints::scoped(|| {
+            hints::register("hints test:", "not shown after the panic");
+            panic!("failed check inside of the scope");
+        });
+    });
+
+    assert!(result.is_err());
+    assert_eq!(expected, render(message));
+}
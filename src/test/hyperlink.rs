@@ -0,0 +1,88 @@
+use pretty_assertions::assert_eq;
+
+use super::SerdeError;
+use crate::HyperlinkScheme;
+
+fn error() -> SerdeError {
+    SerdeError::new(
+        "name: x\nport: http".to_string(),
+        (
+            "invalid type: string, expected u16".into(),
+            Some(2),
+            Some(6),
+        ),
+    )
+}
+
+#[test]
+fn no_path() {
+    super::init();
+
+    assert_eq!(None, error().hyperlink());
+}
+
+#[test]
+fn file() {
+    super::init();
+
+    let mut err = error();
+    err.set_path("/srv/app/config file.yaml");
+
+    assert_eq!(
+        Some("file:///srv/app/config%20file.yaml".to_string()),
+        err.hyperlink()
+    );
+}
+
+#[test]
+fn vscode() {
+    super::init();
+
+    let mut err = error();
+    err.set_path("/srv/app/config.yaml")
+        .set_hyperlink_scheme(HyperlinkScheme::VsCode);
+
+    assert_eq!(
+        Some("vscode://file/srv/app/config.yaml:2:7".to_string()),
+        err.hyperlink()
+    );
+}
+
+#[test]
+fn idea() {
+    super::init();
+
+    let mut err = error();
+    err.set_path("/srv/app/config.yaml")
+        .set_hyperlink_scheme(HyperlinkScheme::Idea);
+
+    assert_eq!(
+        Some("idea://open?file=/srv/app/config.yaml&line=2&column=7".to_string()),
+        err.hyperlink()
+    );
+}
+
+#[test]
+fn source_map() {
+    super::init();
+
+    let mut err = error();
+    err.set_path("/srv/app/generated.yaml")
+        .set_hyperlink_scheme(HyperlinkScheme::VsCode)
+        .set_source_map(|line| Some(("/srv/app/config.yaml.j2".into(), line + 10)));
+
+    assert_eq!(
+        Some("vscode://file/srv/app/config.yaml.j2:12:7".to_string()),
+        err.hyperlink()
+    );
+}
+
+#[test]
+fn not_rendered_without_color() {
+    super::init();
+
+    let mut err = error();
+    err.set_path("/srv/app/config.yaml");
+
+    assert!(!err.to_string().contains('\x1b'));
+}
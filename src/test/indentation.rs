@@ -0,0 +1,43 @@
+use pretty_assertions::assert_eq;
+
+use crate::{
+    IndentWs,
+    SerdeError,
+};
+
+/// The second line starts with a non-breaking space that is part of the value.
+const INPUT: &str = "  first: 1\n\u{a0} second: x";
+
+fn render(indentation_whitespace: IndentWs) -> String {
+    let mut err = SerdeError::new(
+        INPUT.to_string(),
+        ("invalid type".into(), Some(2), Some(10)),
+    );
+    err.set_indentation_whitespace(indentation_whitespace);
+
+    err.to_string()
+}
+
+#[test]
+fn unicode() {
+    super::init();
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | first: 1\n");
+    expected.push_str(" 2 | second: x\n");
+    expected.push_str("   |         ^ invalid type\n");
+
+    assert_eq!(expected, render(IndentWs::Unicode));
+}
+
+#[test]
+fn ascii_only() {
+    super::init();
+
+    let mut expected = String::from("\n");
+    expected.push_str("   |   first: 1\n");
+    expected.push_str(" 2 | \u{a0} second: x\n");
+    expected.push_str("   |           ^ invalid type\n");
+
+    assert_eq!(expected, render(IndentWs::AsciiOnly));
+}
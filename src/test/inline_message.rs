@@ -0,0 +1,77 @@
+use pretty_assertions::assert_eq;
+
+use crate::{
+    AnnotationStyle,
+    SerdeError,
+};
+
+const INPUT: &str = "- first: 1\n- second: 2\n- third: x\n";
+
+fn error(message: &str) -> SerdeError {
+    let mut err = SerdeError::new(INPUT.to_string(), (message.into(), Some(3), Some(9)));
+    err.set_context_lines(1)
+        .set_inline_message(true)
+        .set_viewport_width(40);
+
+    err
+}
+
+/// Short messages are shown behind the error line.
+#[test]
+fn short() {
+    super::init();
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | - second: 2\n");
+    expected.push_str(" 3 | - third: x  <-- invalid type\n");
+
+    assert_eq!(expected, error("invalid type").to_string());
+}
+
+/// Messages that don't fit behind the error line are shown below it.
+#[test]
+fn too_long() {
+    super::init();
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | - second: 2\n");
+    expected.push_str(" 3 | - third: x\n");
+    expected.push_str("   |          ^\n");
+    expected.push_str("   | expected a number instead\n");
+
+    assert_eq!(expected, error("expected a number instead").to_string());
+}
+
+/// Messages that are too long are shortened if enough of them is left.
+#[test]
+fn truncated() {
+    super::init();
+
+    let mut err = error("expected a number instead");
+    err.set_viewport_width(45);
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | - second: 2\n");
+    expected.push_str(" 3 | - third: x  <-- expected a number ins...\n");
+
+    assert_eq!(expected, err.to_string());
+}
+
+/// The annotation style can be set directly.
+#[test]
+fn annotation_style() {
+    super::init();
+
+    let mut err = error("invalid type");
+    assert_eq!(AnnotationStyle::Inline, err.get_annotation_style());
+
+    err.set_annotation_style(AnnotationStyle::CaretBelow);
+    assert!(!err.get_inline_message());
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | - second: 2\n");
+    expected.push_str(" 3 | - third: x\n");
+    expected.push_str("   |          ^ invalid type\n");
+
+    assert_eq!(expected, err.to_string());
+}
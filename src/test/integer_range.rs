@@ -0,0 +1,70 @@
+use pretty_assertions::assert_eq;
+
+use crate::SerdeError;
+
+#[test]
+fn negative_into_unsigned() {
+    super::init();
+
+    let input = "[1, 2, 3, 4, -5]";
+    let err = serde_json::from_str::<Vec<u32>>(input).expect_err("-5 should not fit into u32");
+
+    let mut expected = String::new();
+    expected.push_str(" [1, 2, 3, 4, -5]\n");
+    expected.push_str(
+        "                ^ invalid value: integer `-5`, expected u32 at line 1 column 15\n",
+    );
+    expected.push_str("  = help: this field cannot be negative\n");
+
+    assert_eq!(
+        expected,
+        SerdeError::new(input.to_string(), err).to_string()
+    );
+}
+
+#[test]
+fn too_large() {
+    super::init();
+
+    let input = "{\n  \"retries\": 300\n}";
+    let err = serde_json::from_str::<std::collections::HashMap<String, u8>>(input)
+        .expect_err("300 should not fit into u8");
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | {\n");
+    expected.push_str(" 2 |   \"retries\": 300\n");
+    expected.push_str(
+        "   |                 ^ invalid value: integer `300`, expected u8 at line 2 column 16\n",
+    );
+    expected.push_str("   | }\n");
+    expected.push_str("   = help: this field accepts whole numbers from 0 to 255\n");
+
+    assert_eq!(
+        expected,
+        SerdeError::new(input.to_string(), err).to_string()
+    );
+}
+
+#[test]
+fn signed() {
+    super::init();
+
+    let mut err = SerdeError::new(
+        "offset: -200\n".to_string(),
+        (
+            "invalid value: integer `-200`, expected i8".into(),
+            Some(1),
+            Some(8),
+        ),
+    );
+
+    assert!(
+        err.to_string()
+            .ends_with("= help: this field accepts whole numbers from -128 to 127\n"),
+        "{}",
+        err
+    );
+
+    err.set_hints(false);
+    assert!(!err.to_string().contains("help:"), "{}", err);
+}
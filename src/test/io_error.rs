@@ -0,0 +1,27 @@
+use std::io;
+
+use pretty_assertions::assert_eq;
+
+use crate::SerdeError;
+
+#[test]
+fn into_io_error() {
+    super::init();
+
+    let err = SerdeError::new(
+        "a: b".to_string(),
+        ("invalid type".into(), Some(1), Some(3)),
+    );
+    let rendered = err.to_string();
+
+    let io_err = io::Error::from(err);
+
+    assert_eq!(io_err.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(rendered, io_err.to_string());
+
+    let inner = io_err
+        .into_inner()
+        .and_then(|inner| inner.downcast::<SerdeError>().ok())
+        .expect("inner error is the SerdeError");
+    assert_eq!(inner.line(), Some(1));
+}
@@ -0,0 +1,154 @@
+use pretty_assertions::assert_eq;
+
+use crate::SerdeError;
+
+fn render(input: &str) -> String {
+    let err = serde_json::from_str::<serde_json::Value>(input)
+        .expect_err("input should not be valid JSON");
+
+    let mut err = SerdeError::new(input.to_string(), err);
+    err.set_context_lines(1);
+    err.to_string()
+}
+
+#[test]
+fn single_quoted_key() {
+    super::init();
+
+    let mut expected = String::new();
+    expected.push_str(" {'name': 1}\n");
+    expected.push_str("  ^ key must be a string at line 1 column 2\n");
+    expected.push_str(
+        "  = help: JSON strings must use double quotes; write \"name\" instead of 'name'\n",
+    );
+
+    assert_eq!(expected, render("{'name': 1}"));
+}
+
+#[test]
+fn single_quoted_value() {
+    super::init();
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | {\n");
+    expected.push_str(" 2 |   \"name\": 'value'\n");
+    expected.push_str("   |           ^ expected value at line 2 column 11\n");
+    expected.push_str("   | }\n");
+    expected.push_str(
+        "   = help: JSON strings must use double quotes; write \"value\" instead of 'value'\n",
+    );
+
+    assert_eq!(expected, render("{\n  \"name\": 'value'\n}"));
+}
+
+/// Strings that would need escaping in double quotes are not rewritten.
+#[test]
+fn single_quoted_without_fix() {
+    super::init();
+
+    let mut expected = String::new();
+    expected.push_str(" ['say \"hi\"']\n");
+    expected.push_str("  ^ expected value at line 1 column 2\n");
+    expected.push_str("  = help: JSON strings must use double quotes\n");
+
+    assert_eq!(expected, render("['say \"hi\"']"));
+}
+
+#[test]
+fn unquoted_key() {
+    super::init();
+
+    let mut expected = String::from("\n");
+    expected.push_str("   |   \"name\": \"value\",\n");
+    expected.push_str(" 3 |   port_number: 8080\n");
+    expected.push_str("   |   ^ key must be a string at line 3 column 3\n");
+    expected.push_str("   | }\n");
+    expected.push_str(
+        "   = help: JSON keys must be quoted; write \"port_number\" instead of port_number\n",
+    );
+
+    assert_eq!(
+        expected,
+        render("{\n  \"name\": \"value\",\n  port_number: 8080\n}")
+    );
+}
+
+/// Unquoted values and keys that are not identifiers get no hint as only the
+/// character at the error decides.
+#[test]
+fn other_characters() {
+    super::init();
+
+    let mut expected = String::new();
+    expected.push_str(" {\"name\": value}\n");
+    expected.push_str("           ^ expected value at line 1 column 10\n");
+
+    assert_eq!(expected, render("{\"name\": value}"));
+
+    let mut expected = String::new();
+    expected.push_str(" {1: 2}\n");
+    expected.push_str("   ^ key must be a string at line 1 column 2\n");
+
+    assert_eq!(expected, render("{1: 2}"));
+}
+
+#[test]
+fn disabled() {
+    super::init();
+
+    let input = "{'name': 1}";
+    let err = serde_json::from_str::<serde_json::Value>(input)
+        .expect_err("input should not be valid JSON");
+
+    let mut err = SerdeError::new(input.to_string(), err);
+    err.set_hints(false);
+
+    let mut expected = String::new();
+    expected.push_str(" {'name': 1}\n");
+    expected.push_str("   ^ key must be a string at line 1 column 2\n");
+
+    assert_eq!(expected, err.to_string());
+}
+
+#[test]
+fn line_comment() {
+    super::init();
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | {\n");
+    expected.push_str(" 2 |   \"name\": 1, // the name\n");
+    expected.push_str("   |              ^ key must be a string at line 2 column 14\n");
+    expected.push_str("   |   \"port\": 2\n");
+    expected.push_str("   = help: JSON does not allow comments; remove it\n");
+
+    assert_eq!(
+        expected,
+        render("{\n  \"name\": 1, // the name\n  \"port\": 2\n}")
+    );
+}
+
+#[test]
+fn block_comment() {
+    super::init();
+
+    let mut expected = String::new();
+    expected.push_str(" [1 /* one */, 2]\n");
+    expected.push_str("    ^ expected `,` or `]` at line 1 column 4\n");
+    expected.push_str("  = help: JSON does not allow comments; remove it\n");
+
+    assert_eq!(expected, render("[1 /* one */, 2]"));
+}
+
+#[test]
+fn comment_without_hints() {
+    super::init();
+
+    let input = "[1, // one\n2]";
+    let err = serde_json::from_str::<serde_json::Value>(input)
+        .expect_err("input should not be valid JSON");
+
+    let mut err = SerdeError::new(input.to_string(), err);
+    err.set_hints(false);
+
+    assert!(!err.to_string().contains("comments"));
+}
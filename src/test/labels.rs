@@ -0,0 +1,72 @@
+use pretty_assertions::assert_eq;
+
+use crate::{
+    Labels,
+    SerdeError,
+    Severity,
+};
+
+fn german() -> Labels {
+    Labels {
+        help: "Hilfe".into(),
+        note: "Hinweis".into(),
+        error: "Fehler".into(),
+        warning: "Warnung".into(),
+        at_line: "in Zeile".into(),
+        of: "von".into(),
+        column: "Spalte".into(),
+        byte: "Byte".into(),
+        document: "Dokument".into(),
+        lines: "Zeilen".into(),
+        raw_message: "Originalmeldung".into(),
+        constructed_at: "erstellt in".into(),
+        lines_omitted: "[\u{2026}]".into(),
+        expected_more: "erwartet mehr".into(),
+    }
+}
+
+/// Every fixed string of the output is taken from the labels.
+#[test]
+fn translated() {
+    super::init();
+
+    let input = "name: app\nserver:\n  host: localhost\n  port: 80\n  limit: x";
+
+    let mut err = SerdeError::new(
+        input.to_string(),
+        (
+            "ungültiger Wert at line 5 column 10".into(),
+            Some(5),
+            Some(9),
+        ),
+    );
+    err.set_labels(german())
+        .set_severity(Severity::Warning)
+        .set_context_lines(0)
+        .set_show_stats(true)
+        .set_show_total_lines(true)
+        .set_show_anchor_line(true)
+        .set_show_column_number(true)
+        .set_show_byte_offset(true)
+        .set_strip_position_suffix(true)
+        .set_show_raw_message(true);
+
+    let mut expected = String::from("\n");
+    expected.push_str("Warnung in Zeile 5 von 5 (Spalte 10), 57 B Dokument\n");
+    expected.push_str("   | server:\n");
+    expected.push_str(" [\u{2026}]\n");
+    expected.push_str(" 5 |   limit: x\n");
+    expected.push_str("   |          ^ (Spalte 10) ungültiger Wert (Byte 56) (von 5 Zeilen)\n");
+    expected.push_str("   = Originalmeldung: ungültiger Wert at line 5 column 10\n");
+
+    assert_eq!(expected, err.to_string());
+}
+
+/// The English labels are used unless other labels are set.
+#[test]
+fn english_by_default() {
+    let err = SerdeError::new(String::new(), ("error".into(), None, None));
+
+    assert_eq!(&Labels::english(), err.get_labels());
+    assert_eq!(Labels::english(), crate::get_default_labels());
+}
@@ -0,0 +1,66 @@
+use pretty_assertions::assert_eq;
+
+use crate::SerdeError;
+
+/// The gutter and the caret line are as wide as the formatted number.
+#[test]
+fn zero_padded() {
+    super::init();
+
+    let input = "name: test\nport: x\nhost: localhost";
+    let mut err = SerdeError::new(input.to_string(), ("invalid type".into(), Some(2), Some(6)));
+    err.set_context_lines(1)
+        .set_line_number_formatter(|line| format!("{line:04}"));
+
+    let mut expected = String::from("\n");
+    expected.push_str("      | name: test\n");
+    expected.push_str(" 0002 | port: x\n");
+    expected.push_str("      |       ^ invalid type\n");
+    expected.push_str("      | host: localhost\n");
+
+    assert_eq!(expected, err.to_string());
+}
+
+#[test]
+fn prefixed() {
+    super::init();
+
+    let mut err = SerdeError::new(
+        "port: x".to_string(),
+        ("invalid type".into(), Some(1), Some(6)),
+    );
+    err.set_line_number_formatter(|line| format!("L{line}"))
+        .set_frame_single_line(true);
+
+    let mut expected = String::from("\n");
+    expected.push_str(" L1 | port: x\n");
+    expected.push_str("    |       ^ invalid type\n");
+
+    assert_eq!(expected, err.to_string());
+}
+
+/// Formatters can capture their surroundings, for example the line the parsed
+/// input starts at in a larger file.
+#[test]
+fn capturing() {
+    super::init();
+
+    let first_line = 40;
+    let mut err = SerdeError::new(
+        "port: x".to_string(),
+        ("invalid type".into(), Some(1), Some(6)),
+    );
+    err.set_line_number_formatter(move |line| (first_line + line).to_string())
+        .set_frame_single_line(true);
+
+    let mut expected = String::from("\n");
+    expected.push_str(" 41 | port: x\n");
+    expected.push_str("    |       ^ invalid type\n");
+
+    assert_eq!(expected, err.to_string());
+    assert_eq!(
+        Some("42".to_string()),
+        err.get_line_number_formatter()
+            .map(|formatter| formatter(2))
+    );
+}
@@ -0,0 +1,22 @@
+use std::collections::HashMap;
+
+use pretty_assertions::assert_eq;
+
+use crate::SerdeError;
+
+#[test]
+fn line_statuses() {
+    super::init();
+
+    let input = "name: test\nport: eighty\nhost: localhost";
+    let mut err = SerdeError::new(input.to_string(), ("invalid type".into(), Some(2), Some(6)));
+    err.set_line_statuses(HashMap::from([(2, '+'), (3, '-')]));
+
+    let mut expected = String::from("\n");
+    expected.push_str("     | name: test\n");
+    expected.push_str(" 2 + | port: eighty\n");
+    expected.push_str("     |       ^ invalid type\n");
+    expected.push_str("   - | host: localhost\n");
+
+    assert_eq!(expected, err.to_string());
+}
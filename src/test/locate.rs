@@ -0,0 +1,199 @@
+use pretty_assertions::assert_eq;
+
+use crate::{
+    locate,
+    Location,
+    SerdeError,
+};
+
+const PRETTY: &str = r#"{
+  "name": "a {weird} [name]",
+  "spec": {
+    "replicas": 3,
+    "ports": [80, {"port": 443}]
+  }
+}"#;
+
+const MINIFIED: &str =
+    r#"{"name":"a {weird} [name]","spec":{"replicas":3,"ports":[80,{"port":443}]}}"#;
+
+fn location(line: usize, column: usize, byte_offset: usize) -> Location {
+    Location {
+        line,
+        column,
+        byte_offset,
+    }
+}
+
+#[test]
+fn json_pointer_pretty() {
+    assert_eq!(Some(location(1, 0, 0)), locate::json_pointer(PRETTY, ""));
+    assert_eq!(
+        Some(location(4, 16, 60)),
+        locate::json_pointer(PRETTY, "/spec/replicas")
+    );
+    assert_eq!(
+        Some(location(5, 27, 90)),
+        locate::json_pointer(PRETTY, "/spec/ports/1/port")
+    );
+    assert_eq!(None, locate::json_pointer(PRETTY, "/spec/missing"));
+    assert_eq!(None, locate::json_pointer(PRETTY, "/spec/ports/2"));
+    assert_eq!(None, locate::json_pointer(PRETTY, "no/leading/slash"));
+}
+
+#[test]
+fn json_pointer_minified() {
+    assert_eq!(
+        Some(location(1, 46, 46)),
+        locate::json_pointer(MINIFIED, "/spec/replicas")
+    );
+    assert_eq!(
+        Some(location(1, 57, 57)),
+        locate::json_pointer(MINIFIED, "/spec/ports/0")
+    );
+}
+
+#[test]
+fn json_pointer_key() {
+    assert_eq!(
+        Some(location(4, 4, 48)),
+        locate::json_pointer_key(PRETTY, "/spec/replicas")
+    );
+    assert_eq!(None, locate::json_pointer_key(PRETTY, "/spec/ports/0"));
+}
+
+#[test]
+fn json_pointer_escaped() {
+    let input = r#"{"a/b": {"c~d": true}}"#;
+
+    assert_eq!(
+        Some(location(1, 16, 16)),
+        locate::json_pointer(input, "/a~1b/c~0d")
+    );
+}
+
+#[cfg(feature = "serde_yaml")]
+mod yaml {
+    use pretty_assertions::assert_eq;
+
+    use super::location;
+    use crate::locate::{
+        self,
+        PathSegment,
+    };
+
+    const BLOCK: &str = r#"name: test
+values:
+  - first
+  - timeout: 30
+    "quoted key": 'value'
+flow: {a: [1, 2], b: x}
+base: &base
+  port: 80
+alias: *base
+merged:
+  <<: *base
+  host: localhost
+"#;
+
+    #[test]
+    fn block() {
+        let path = PathSegment::parse("values.1.timeout");
+
+        assert_eq!(Some(location(4, 13, 42)), locate::yaml_path(BLOCK, &path));
+        assert_eq!(
+            Some(location(3, 4, 23)),
+            locate::yaml_path(BLOCK, &PathSegment::parse("values.0"))
+        );
+    }
+
+    #[test]
+    fn quoted_key() {
+        let path = [
+            PathSegment::Key("values".to_string()),
+            PathSegment::Index(1),
+            PathSegment::Key("quoted key".to_string()),
+        ];
+
+        assert_eq!(Some(location(5, 18, 63)), locate::yaml_path(BLOCK, &path));
+    }
+
+    #[test]
+    fn flow() {
+        assert_eq!(
+            Some(location(6, 14, 85)),
+            locate::yaml_path(BLOCK, &PathSegment::parse("flow.a.1"))
+        );
+        assert_eq!(
+            Some(location(6, 21, 92)),
+            locate::yaml_path(BLOCK, &PathSegment::parse("flow.b"))
+        );
+    }
+
+    #[test]
+    fn missing_alias_and_merge() {
+        assert_eq!(
+            None,
+            locate::yaml_path(BLOCK, &PathSegment::parse("values.5"))
+        );
+        assert_eq!(
+            None,
+            locate::yaml_path(BLOCK, &PathSegment::parse("alias.port"))
+        );
+        assert_eq!(
+            None,
+            locate::yaml_path(BLOCK, &PathSegment::parse("merged.port"))
+        );
+        assert_eq!(None, locate::yaml_path("a: [", &PathSegment::parse("a")));
+        assert!(locate::yaml_path(BLOCK, &PathSegment::parse("merged.host")).is_some());
+        assert!(locate::yaml_path(BLOCK, &PathSegment::parse("base.port")).is_some());
+    }
+}
+
+#[test]
+fn from_line_column() {
+    let input = "a: é\nb: c\n";
+
+    assert_eq!(
+        Some(location(1, 3, 3)),
+        Location::from_line_column(input, 1, 3)
+    );
+    assert_eq!(
+        Some(location(1, 4, 5)),
+        Location::from_line_column(input, 1, 4)
+    );
+    assert_eq!(
+        Some(location(2, 1, 7)),
+        Location::from_line_column(input, 2, 1)
+    );
+    assert_eq!(
+        Some(location(3, 0, 11)),
+        Location::from_line_column(input, 3, 0)
+    );
+    assert_eq!(None, Location::from_line_column(input, 1, 5));
+    assert_eq!(None, Location::from_line_column(input, 4, 0));
+    assert_eq!(None, Location::from_line_column(input, 0, 0));
+}
+
+#[test]
+fn serde_error_at() {
+    let input = "spec:\n  replicas: 0";
+    let location = Location::from_line_column(input, 2, 12).expect("position should exist");
+    let err = SerdeError::at(input.to_string(), "must be at least 1", location);
+
+    assert_eq!(Some(location), err.location());
+    assert_eq!("must be at least 1", err.message());
+}
+
+const CSV: &str = "name,port\n\n\"web, \"\"primary\"\"\",80\r\n\"multi\nline\",443\n";
+
+#[test]
+fn csv_field() {
+    assert_eq!(Some(location(1, 5, 5)), locate::csv_field(CSV, 0, 1));
+    assert_eq!(Some(location(3, 1, 12)), locate::csv_field(CSV, 1, 0));
+    assert_eq!(Some(location(3, 19, 30)), locate::csv_field(CSV, 1, 1));
+    assert_eq!(Some(location(5, 6, 47)), locate::csv_field(CSV, 2, 1));
+
+    assert_eq!(None, locate::csv_field(CSV, 1, 2));
+    assert_eq!(None, locate::csv_field(CSV, 3, 0));
+}
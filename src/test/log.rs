@@ -0,0 +1,96 @@
+use std::sync::Mutex;
+
+use log::{
+    Level,
+    LevelFilter,
+    Log,
+    Metadata,
+    Record,
+};
+use pretty_assertions::assert_eq;
+
+use crate::{
+    LogFormat,
+    SerdeError,
+};
+
+/// Logger that keeps every record so the tests can inspect them.
+struct CapturingLogger {
+    records: Mutex<Vec<(Level, String)>>,
+}
+
+impl Log for CapturingLogger {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        self.records
+            .lock()
+            .expect("logger lock is poisoned")
+            .push((record.level(), record.args().to_string()));
+    }
+
+    fn flush(&self) {}
+}
+
+static LOGGER: CapturingLogger = CapturingLogger {
+    records: Mutex::new(Vec::new()),
+};
+
+/// Records that contain the given marker. The tests use distinct messages as
+/// they run in parallel and share the global logger.
+fn records(marker: &str) -> Vec<(Level, String)> {
+    let _ = log::set_logger(&LOGGER);
+    log::set_max_level(LevelFilter::Trace);
+
+    LOGGER
+        .records
+        .lock()
+        .expect("logger lock is poisoned")
+        .iter()
+        .filter(|(_, message)| message.contains(marker))
+        .cloned()
+        .collect()
+}
+
+fn error(message: &str) -> SerdeError {
+    SerdeError::new(
+        "first line\nsecond line".to_string(),
+        (message.into(), Some(2), Some(7)),
+    )
+}
+
+/// The compact form is a single line with the position in front.
+#[test]
+fn compact() {
+    super::init();
+    records("");
+
+    let mut err = error("compact error at line 2 column 8");
+    err.log(Level::Warn);
+
+    err.set_path("config.yaml");
+    err.log(Level::Error);
+
+    let expected = vec![
+        (Level::Warn, "line 2 column 8: compact error".to_string()),
+        (Level::Error, "config.yaml:2:8: compact error".to_string()),
+    ];
+
+    assert_eq!(expected, records("compact error"));
+}
+
+/// The full form is the same output as the display implementation.
+#[test]
+fn full() {
+    super::init();
+    records("");
+
+    let err = error("full error");
+    err.log_with_format(Level::Info, LogFormat::Full);
+
+    let expected = vec![(Level::Info, err.to_string())];
+
+    assert_eq!(expected, records("full error"));
+}
@@ -0,0 +1,103 @@
+use pretty_assertions::assert_eq;
+
+use super::SerdeError;
+use crate::LongLineMode;
+
+const INPUT: &str = "first: line
+values: [aaaaaaaaaa, bbbbbbbbbb, cccccccccc, dddddddddd, eeeeeeeeee]
+context: [ffffffffff, gggggggggg, hhhhhhhhhh, iiiiiiiiii, jjjjjjjjjj]";
+
+#[test]
+fn wrap() {
+    super::init();
+
+    let mut err = SerdeError::new(
+        INPUT.to_string(),
+        ("invalid value".into(), Some(2), Some(46)),
+    );
+    err.set_long_line_mode(LongLineMode::Wrap)
+        .set_wrap_width(40);
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | first: line\n");
+    expected.push_str(" 2 | values: [aaaaaaaaaa, bbbbbbbbbb, cc\n");
+    expected.push_str(" ↪ | cccccccc, dddddddddd, eeeeeeeeee]\n");
+    expected.push_str("   |            ^ invalid value\n");
+    expected.push_str("   | context: [ffffffffff, gggggggggg, h\n");
+    expected.push_str(" ↪ | hhhhhhhhh, iiiiiiiiii, jjjjjjjjjj]\n");
+
+    assert_eq!(expected, err.to_string());
+}
+
+#[test]
+fn wrap_without_context_lines() {
+    super::init();
+
+    let mut err = SerdeError::new(
+        INPUT.to_string(),
+        ("invalid value".into(), Some(2), Some(46)),
+    );
+    err.set_long_line_mode(LongLineMode::Wrap)
+        .set_wrap_width(40)
+        .set_wrap_context_lines(false);
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | first: line\n");
+    expected.push_str(" 2 | values: [aaaaaaaaaa, bbbbbbbbbb, cc\n");
+    expected.push_str(" ↪ | cccccccc, dddddddddd, eeeeeeeeee]\n");
+    expected.push_str("   |            ^ invalid value\n");
+    expected.push_str("   | context: [ffffffffff, gggggggggg...\n");
+
+    assert_eq!(expected, err.to_string());
+}
+
+#[test]
+fn truncate() {
+    super::init();
+
+    let mut err = SerdeError::new(
+        INPUT.to_string(),
+        ("invalid value".into(), Some(2), Some(46)),
+    );
+    err.set_long_line_mode(LongLineMode::Truncate)
+        .set_wrap_width(40)
+        .set_context_characters(10);
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | first: line\n");
+    expected.push_str(" 2 | ...cccccccc, dddddddddd,...\n");
+    expected.push_str("   |               ^ (column 47) invalid value\n");
+    expected
+        .push_str("   | context: [ffffffffff, gggggggggg, hhhhhhhhhh, iiiiiiiiii, jjjjjjjjjj]\n");
+
+    assert_eq!(expected, err.to_string());
+}
+
+/// Rows are split between grapheme clusters and the caret is placed by
+/// grapheme clusters on the wrapped rows.
+#[cfg(feature = "graphemes_support")]
+#[test]
+fn wrap_graphemes() {
+    super::init();
+
+    let input = format!("first: line\nnames: {}!", "e\u{301}".repeat(30));
+    let mut err = SerdeError::new(input, ("invalid value".into(), Some(2), Some(67)));
+    err.set_long_line_mode(LongLineMode::Wrap)
+        .set_wrap_width(20);
+
+    let e = "e\u{301}";
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | first: line\n");
+    expected.push_str(" 2 | names: ");
+    expected.push_str(&e.repeat(8));
+    expected.push_str("\n ↪ | ");
+    expected.push_str(&e.repeat(15));
+    expected.push_str("\n ↪ | ");
+    expected.push_str(&e.repeat(7));
+    expected.push_str("!\n");
+    expected.push_str("   |        ^\n");
+    expected.push_str("   | invalid value\n");
+
+    assert_eq!(expected, err.to_string());
+}
@@ -0,0 +1,57 @@
+use std::path::PathBuf;
+
+use pretty_assertions::assert_eq;
+
+use crate::{
+    Report,
+    SerdeError,
+};
+
+fn error() -> SerdeError {
+    SerdeError::new(
+        "port: x".to_string(),
+        ("invalid type".into(), Some(1), Some(6)),
+    )
+}
+
+#[test]
+fn map_message() {
+    super::init();
+
+    let err = error().map_message(|message| format!("while loading tenant config: {message}"));
+
+    let mut expected = String::new();
+    expected.push_str(" port: x\n");
+    expected.push_str("       ^ while loading tenant config: invalid type\n");
+
+    assert_eq!(expected, err.to_string());
+}
+
+#[test]
+fn prepend_message() {
+    super::init();
+
+    let mut err = error();
+    err.prepend_message("tenant a: ");
+
+    let mut expected = String::new();
+    expected.push_str(" port: x\n");
+    expected.push_str("       ^ tenant a: invalid type\n");
+
+    assert_eq!(expected, err.to_string());
+}
+
+/// The structured output contains the mapped message.
+#[test]
+fn diagnostics() {
+    let mut report = Report::new();
+    report.add(
+        "config.yaml",
+        error().map_message(|message| message.to_uppercase()),
+    );
+
+    let diagnostics = report.diagnostics();
+
+    assert_eq!(PathBuf::from("config.yaml"), diagnostics[0].path);
+    assert_eq!("INVALID TYPE", diagnostics[0].message);
+}
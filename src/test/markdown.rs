@@ -0,0 +1,49 @@
+use pretty_assertions::assert_eq;
+
+use crate::SerdeError;
+
+#[test]
+fn markdown() {
+    super::init();
+
+    let err = SerdeError::new(
+        "a: b".to_string(),
+        ("invalid type".into(), Some(1), Some(3)),
+    );
+
+    let mut expected = String::from("```\n");
+    expected.push_str(" a: b\n");
+    expected.push_str("    ^ invalid type\n");
+    expected.push_str("```\n");
+
+    assert_eq!(expected, err.to_markdown());
+}
+
+#[test]
+fn backticks_in_input() {
+    super::init();
+
+    let err = SerdeError::new(
+        "command: ````echo````".to_string(),
+        ("invalid type".into(), Some(1), Some(9)),
+    );
+
+    let mut expected = String::from("`````\n");
+    expected.push_str(" command: ````echo````\n");
+    expected.push_str("          ^ invalid type\n");
+    expected.push_str("`````\n");
+
+    assert_eq!(expected, err.to_markdown());
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn language() {
+    super::init();
+
+    let input = "[1, 2,]";
+    let err = serde_json::from_str::<Vec<u32>>(input).expect_err("trailing comma");
+    let err = SerdeError::new(input.to_string(), err);
+
+    assert!(err.to_markdown().starts_with("```json\n"));
+}
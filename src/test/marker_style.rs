@@ -0,0 +1,52 @@
+use pretty_assertions::assert_eq;
+
+use crate::{
+    MarkerStyle,
+    SerdeError,
+};
+
+const INPUT: &str = "name: app\nimage: nginx:lates\nreplicas: 2\n";
+
+fn error() -> SerdeError {
+    let mut err = SerdeError::new(
+        INPUT.to_string(),
+        ("unknown tag `lates`".into(), Some(2), Some(17)),
+    );
+    err.set_context_lines(1)
+        .set_span_start(2, 13)
+        .set_marker_style(MarkerStyle::Box);
+    err
+}
+
+/// The box covers exactly the five characters of the span.
+#[test]
+fn five_characters() {
+    super::init();
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | name: app\n");
+    expected.push_str("   |              ┌───┐\n");
+    expected.push_str(" 2 | image: nginx:lates\n");
+    expected.push_str("   |              └───┘ unknown tag `lates`\n");
+    expected.push_str("   | replicas: 2\n");
+
+    assert_eq!(expected, error().to_string());
+}
+
+/// Errors without a span are still marked with a caret.
+#[test]
+fn without_span() {
+    super::init();
+
+    let mut err = SerdeError::new(
+        INPUT.to_string(),
+        ("unknown tag `lates`".into(), Some(2), Some(13)),
+    );
+    err.set_context_lines(0).set_marker_style(MarkerStyle::Box);
+
+    let mut expected = String::from("\n");
+    expected.push_str(" 2 | image: nginx:lates\n");
+    expected.push_str("   |              ^ unknown tag `lates`\n");
+
+    assert_eq!(expected, err.to_string());
+}
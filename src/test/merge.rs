@@ -0,0 +1,53 @@
+use pretty_assertions::assert_eq;
+
+use crate::SerdeError;
+
+const INPUT: &str = "name: test\nport: eighty\nhost: localhost";
+
+#[test]
+fn same_input() {
+    super::init();
+
+    let strict = SerdeError::new(INPUT.to_string(), ("invalid type".into(), Some(2), Some(6)));
+    let lenient = SerdeError::new(
+        INPUT.to_string(),
+        ("missing field".into(), Some(3), Some(0)),
+    );
+
+    let merged = strict.merge(lenient).expect("inputs are the same");
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | name: test\n");
+    expected.push_str(" 2 | port: eighty\n");
+    expected.push_str("   |       ^ invalid type\n");
+    expected.push_str("   | host: localhost\n");
+    expected.push_str("   | - missing field\n");
+
+    assert_eq!(expected, merged.to_string());
+}
+
+#[test]
+fn without_location() {
+    super::init();
+
+    let strict = SerdeError::new(INPUT.to_string(), ("invalid type".into(), Some(2), Some(6)));
+    let lenient = SerdeError::new(INPUT.to_string(), ("unknown error".into(), None, None));
+
+    let merged = strict.merge(lenient).expect("inputs are the same");
+
+    assert!(merged.to_string().ends_with("= note: unknown error\n"));
+}
+
+#[test]
+fn different_input() {
+    let first = SerdeError::new(INPUT.to_string(), ("invalid type".into(), Some(2), Some(6)));
+    let second = SerdeError::new(
+        "port: 80".to_string(),
+        ("invalid type".into(), Some(1), Some(6)),
+    );
+
+    let (first, second) = first.merge(second).expect_err("inputs are different");
+
+    assert_eq!(first.message, "invalid type");
+    assert_eq!(second.input.text(), "port: 80");
+}
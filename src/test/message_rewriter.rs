@@ -0,0 +1,91 @@
+use std::path::PathBuf;
+
+use pretty_assertions::assert_eq;
+
+use crate::{
+    Diagnostic,
+    ErrorAdapter,
+    RenderStyle,
+    Report,
+    SerdeError,
+};
+
+const MESSAGE: &str = "invalid type: string \"soon\", expected u32";
+const FRIENDLY: &str = "The value for `timeout` must be a number, e.g. 30";
+
+fn error() -> SerdeError {
+    let mut err = SerdeError::new(
+        "timeout: soon".to_string(),
+        (MESSAGE.into(), Some(1), Some(9)),
+    );
+    err.set_message_rewriter(|message| {
+        message
+            .starts_with("invalid type")
+            .then(|| FRIENDLY.to_string())
+    });
+
+    err
+}
+
+/// The replacement is shown in the snippet while the original is kept.
+#[test]
+fn snippet() {
+    super::init();
+
+    let mut err = error();
+    err.set_show_raw_message(true);
+
+    let mut expected = String::new();
+    expected.push_str(" timeout: soon\n");
+    expected.push_str("          ^ ");
+    expected.push_str(FRIENDLY);
+    expected.push_str("\n  = raw message: ");
+    expected.push_str(MESSAGE);
+    expected.push('\n');
+
+    assert_eq!(expected, err.to_string());
+    assert_eq!(MESSAGE, err.message());
+}
+
+/// The replacement is used for the single line output.
+#[test]
+fn minimal() {
+    super::init();
+
+    let mut err = error();
+    err.set_render_style(RenderStyle::Minimal);
+
+    assert_eq!(format!("line 1 column 10: {}\n", FRIENDLY), err.to_string());
+}
+
+/// The replacement is used for the structured output.
+#[test]
+fn diagnostics() {
+    let mut report = Report::new();
+    report.add("config.yaml", error());
+
+    let expected = vec![Diagnostic {
+        path: PathBuf::from("config.yaml"),
+        line: Some(1),
+        column: Some(9),
+        message: FRIENDLY.to_string(),
+        error_kind: ErrorAdapter::Custom,
+    }];
+
+    assert_eq!(expected, report.diagnostics());
+}
+
+/// Messages the rewriter returns nothing for are shown as they are.
+#[test]
+fn unchanged() {
+    super::init();
+
+    let mut err = SerdeError::new("a: b".to_string(), ("other error".into(), Some(1), Some(3)));
+    err.set_message_rewriter(|_| None);
+
+    let mut expected = String::new();
+    expected.push_str(" a: b\n");
+    expected.push_str("    ^ other error\n");
+
+    assert_eq!(expected, err.to_string());
+}
@@ -0,0 +1,90 @@
+use pretty_assertions::assert_eq;
+
+use super::SerdeError;
+
+const INPUT: &str = "name: test\nkind: unknown";
+
+const MESSAGE: &str = "kind: unknown variant `unknown`, expected one of `first`, `second`, `third \
+                       and fourth`, `fifth`";
+
+#[test]
+fn wrap_message() {
+    super::init();
+
+    let mut err = SerdeError::new(INPUT.to_string(), (MESSAGE.into(), Some(2), Some(6)));
+    err.set_max_message_width(30);
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | name: test\n");
+    expected.push_str(" 2 | kind: unknown\n");
+    expected.push_str("   |       ^^^^^^^ kind: unknown variant\n");
+    expected.push_str("   |               `unknown`, expected one of\n");
+    expected.push_str("   |               `first`, `second`,\n");
+    expected.push_str("   |               `third and fourth`, `fifth`\n");
+
+    assert_eq!(expected, err.to_string());
+}
+
+#[test]
+fn short_message() {
+    super::init();
+
+    let mut err = SerdeError::new(INPUT.to_string(), ("invalid".into(), Some(2), Some(6)));
+    err.set_max_message_width(30);
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | name: test\n");
+    expected.push_str(" 2 | kind: unknown\n");
+    expected.push_str("   |       ^ invalid\n");
+
+    assert_eq!(expected, err.to_string());
+}
+
+#[cfg(feature = "unicode_width")]
+#[test]
+fn wide_characters() {
+    super::init();
+
+    let mut err = SerdeError::new(
+        INPUT.to_string(),
+        (
+            "unknown variant `名前`, expected `種類`".into(),
+            Some(2),
+            Some(6),
+        ),
+    );
+    err.set_max_message_width(20);
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | name: test\n");
+    expected.push_str(" 2 | kind: unknown\n");
+    expected.push_str("   |       ^ unknown variant\n");
+    expected.push_str("   |         `名前`, expected\n");
+    expected.push_str("   |         `種類`\n");
+
+    assert_eq!(expected, err.to_string());
+}
+
+#[cfg(not(feature = "terminal_size"))]
+#[test]
+fn terminal_width() {
+    super::init();
+
+    std::env::set_var("COLUMNS", "50");
+
+    let mut err = SerdeError::new(INPUT.to_string(), (MESSAGE.into(), Some(2), Some(6)));
+    err.set_max_message_width_terminal();
+    let got = err.to_string();
+
+    std::env::remove_var("COLUMNS");
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | name: test\n");
+    expected.push_str(" 2 | kind: unknown\n");
+    expected.push_str("   |       ^^^^^^^ kind: unknown variant\n");
+    expected.push_str("   |               `unknown`, expected one of\n");
+    expected.push_str("   |               `first`, `second`,\n");
+    expected.push_str("   |               `third and fourth`, `fifth`\n");
+
+    assert_eq!(expected, got);
+}
@@ -0,0 +1,59 @@
+use pretty_assertions::assert_eq;
+
+use crate::SerdeError;
+
+const INPUT: &str = "name: test\n  port: x\nkind: y";
+
+fn render(line: Option<usize>, column: Option<usize>) -> String {
+    SerdeError::new(INPUT.to_string(), ("invalid".into(), line, column)).to_string()
+}
+
+fn underlined(marks: &str) -> String {
+    let mut expected = String::from("\n");
+    expected.push_str("   | name: test\n");
+    expected.push_str(" 2 |   port: x\n");
+    expected.push_str("   |   ");
+    expected.push_str(marks);
+    expected.push_str(" invalid\n");
+    expected.push_str("   | kind: y\n");
+
+    expected
+}
+
+/// The whole line is underlined if the column is not known.
+#[test]
+fn line_without_column() {
+    super::init();
+
+    assert_eq!(underlined("^^^^^^^"), render(Some(2), None));
+}
+
+/// Column 0 is the first character of the line and not a missing column.
+#[test]
+fn column_zero() {
+    super::init();
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | name: test\n");
+    expected.push_str(" 2 |   port: x\n");
+    expected.push_str("   | ^ invalid\n");
+    expected.push_str("   | kind: y\n");
+
+    assert_eq!(expected, render(Some(2), Some(0)));
+}
+
+#[test]
+fn line_and_column() {
+    super::init();
+
+    assert_eq!(underlined("^"), render(Some(2), Some(2)));
+}
+
+/// A column alone doesn't tell which line to show so only the message is
+/// shown.
+#[test]
+fn column_without_line() {
+    super::init();
+
+    assert_eq!(render(None, None), render(None, Some(5)));
+}
@@ -1,21 +1,127 @@
-#[cfg(feature = "colored")]
+#[cfg(all(
+    feature = "colored",
+    any(feature = "serde_json", feature = "serde_yaml", feature = "toml")
+))]
 use colored::{
     ColoredString,
     Colorize,
 };
 
+mod anchor_line;
+mod ansi;
+#[cfg(feature = "backtrace")]
+mod backtrace;
+mod block_indent;
+#[cfg(feature = "serde_json")]
+mod category;
+#[cfg(any(
+    feature = "serde_json",
+    feature = "serde_yaml",
+    all(feature = "toml", feature = "colored")
+))]
 mod config;
+mod constructed_at;
+mod context_chars_unit;
+mod context_mode;
+mod corrupted_utf8;
+#[cfg(feature = "csv")]
+mod csv;
+mod deep_indentation;
+#[cfg(any(feature = "serde_yaml", feature = "serde_json"))]
+mod deserializer;
+#[cfg(feature = "serde_json")]
+mod duplicate_keys;
+mod dyn_error;
+#[cfg(feature = "serde_json")]
+mod eof_marker;
+mod from_lines;
+mod hints;
+mod hyperlink;
+mod indentation;
+mod inline_message;
+#[cfg(feature = "serde_json")]
+mod integer_range;
+mod io_error;
+#[cfg(feature = "serde_json")]
+mod json_quotes;
+mod labels;
+mod line_number_formatter;
+mod line_statuses;
+mod locate;
+#[cfg(feature = "log")]
+mod log;
+mod long_line_mode;
+mod map_message;
+mod markdown;
+mod marker_style;
+mod merge;
+mod message_rewriter;
+mod message_width;
+mod missing_column;
+mod multiple_windows;
+mod never_panics;
+#[cfg(feature = "serde_json")]
+mod quote_scalar;
+mod raw_message;
+mod reflow;
+mod render;
+mod render_debug;
+#[cfg(feature = "serde_json")]
+mod render_dual;
+mod render_style;
+mod report;
+#[cfg(all(
+    feature = "schemars",
+    any(feature = "serde_json", feature = "serde_yaml", feature = "toml")
+))]
+mod schema;
+mod send_sync;
+mod sequence;
+#[cfg(all(feature = "serde", feature = "serde_json", feature = "toml"))]
+mod serde_settings;
+mod shortened_column;
+mod show_whitespace;
+#[cfg(feature = "serde_json")]
+mod single_line;
+mod source_excerpt;
+mod source_map;
+mod stats;
+mod tab_hint;
+mod text_direction;
+#[cfg(feature = "serde_json")]
+mod trailing_comma;
+mod trailing_newlines;
+#[cfg(feature = "serde_json")]
+mod typed;
+#[cfg(feature = "serde_json")]
+mod unknown_variant;
+mod value_preview;
+#[cfg(feature = "serde_yaml")]
+mod yaml_duplicate_keys;
+mod yaml_scalar;
 
 use crate::SerdeError;
-#[allow(unused_imports)]
+#[cfg(any(
+    feature = "serde_json",
+    feature = "serde_yaml",
+    all(feature = "toml", feature = "colored")
+))]
 use config::Config;
-
-#[cfg(feature = "colored")]
+#[cfg(all(
+    feature = "colored",
+    any(feature = "serde_json", feature = "serde_yaml")
+))]
+use config::NestedConfig;
+
+#[cfg(all(
+    feature = "colored",
+    any(feature = "serde_json", feature = "serde_yaml", feature = "toml")
+))]
 fn separator() -> ColoredString {
     super::SEPARATOR.blue()
 }
 
-#[cfg(feature = "colored")]
+#[cfg(all(feature = "colored", feature = "serde_json"))]
 fn ellipse() -> ColoredString {
     super::ELLIPSE.blue().bold()
 }
@@ -26,6 +132,12 @@ fn init() {
 }
 
 // TODO: Make tests that only use toml feature
+// These tests predate the pedantic lints and keep their original style
+#[allow(
+    clippy::format_push_string,
+    clippy::single_char_add_str,
+    clippy::uninlined_format_args
+)]
 #[cfg(all(feature = "toml", feature = "colored"))]
 mod toml {
     use anyhow::bail;
@@ -104,6 +216,13 @@ mod toml {
 }
 
 // TODO: Make tests that only use serde_yaml feature
+// These tests predate the pedantic lints and keep their original style
+#[allow(
+    clippy::format_push_string,
+    clippy::needless_raw_string_hashes,
+    clippy::single_char_add_str,
+    clippy::uninlined_format_args
+)]
 #[cfg(all(feature = "serde_yaml", feature = "colored"))]
 mod yaml {
     use anyhow::bail;
@@ -112,6 +231,7 @@ mod yaml {
 
     use super::{
         Config,
+        NestedConfig,
         SerdeError,
     };
 
@@ -179,9 +299,169 @@ mod yaml {
 
         Ok(())
     }
+
+    #[test]
+    fn missing_field_nested_relocated() -> Result<(), anyhow::Error> {
+        super::init();
+        let separator = super::separator();
+
+        let input = "other: 1\ninner:\n  name: x\n  extra: 2\n";
+
+        let mut expected = String::new();
+        expected.push('\n');
+        expected.push_str(&format!("  {}{}\n", separator, "other: 1".yellow()));
+        expected.push_str(&format!(
+            " {}{}{}\n",
+            "2".blue().bold(),
+            separator,
+            "inner:"
+        ));
+        expected.push_str(&format!(
+            "  {}{}\n",
+            separator,
+            "^ inner: missing field `values` at line 3 column 7"
+                .red()
+                .bold(),
+        ));
+        expected.push_str(&format!("  {}{}\n", separator, "  name: x".yellow()));
+        expected.push_str(&format!("  {}{}\n", separator, "  extra: 2".yellow()));
+        expected.push_str("   = help: add a `values` key to this object\n");
+
+        let got = match serde_yaml::from_str::<NestedConfig>(input) {
+            Ok(_) => bail!("expecting error got ok"),
+            Err(err) => format!(
+                "{}",
+                SerdeError::new(input.to_string(), err).set_relocate_missing_field(true)
+            ),
+        };
+
+        println!("got:{}", got);
+        println!("expected:{}", expected);
+
+        assert_eq!(expected, got);
+
+        Ok(())
+    }
+
+    #[test]
+    fn mark_key() -> Result<(), anyhow::Error> {
+        super::init();
+
+        let input = "values: {invalid: dont}";
+
+        let mut expected = String::new();
+        expected.push_str(&format!(" {}\n", "values: {invalid: dont}"));
+        expected.push_str(&format!(
+            " {}\n",
+            "        ^ values: invalid type: map, expected a sequence at line 1 column 9"
+                .red()
+                .bold(),
+        ));
+        expected.push_str(&format!(" {}\n", "- key here".blue().bold()));
+
+        let got = match serde_yaml::from_str::<Config>(input) {
+            Ok(_) => bail!("expecting error got ok"),
+            Err(err) => format!(
+                "{}",
+                SerdeError::new(input.to_string(), err).set_mark_key(true)
+            ),
+        };
+
+        println!("got:{}", got);
+        println!("expected:{}", expected);
+
+        assert_eq!(expected, got);
+
+        Ok(())
+    }
+
+    #[test]
+    fn tabs_expanded() -> Result<(), anyhow::Error> {
+        super::init();
+
+        let input = "values:\t['first',\t\t{invalid: dont}]";
+
+        let mut expected = String::new();
+        expected.push_str(&format!(
+            " {}\n",
+            "values:    ['first',        {invalid: dont}]",
+        ));
+
+        expected.push_str(&format!(
+            " {}\n",
+            "                            ^ values[1]: invalid type: map, expected a string at \
+             line 1 column 20"
+                .red()
+                .bold()
+        ));
+
+        let got = match serde_yaml::from_str::<Config>(input) {
+            Ok(_) => bail!("expecting error got ok"),
+            Err(err) => format!(
+                "{}",
+                SerdeError::new(input.to_string(), err)
+                    .set_tab_width(4)
+                    .set_context_characters(99)
+            ),
+        };
+
+        println!("got:{}", got);
+        println!("expected:{}", expected);
+
+        assert_eq!(expected, got);
+
+        Ok(())
+    }
+
+    /// `serde_yaml` columns start at 1 so column 1 is the first character of
+    /// the line and not a column that can't be mapped.
+    #[test]
+    fn column_one() {
+        super::init();
+        let separator = super::separator();
+
+        let input = "values:\n  - 'first'\nb";
+        let err = serde_yaml::from_str::<std::collections::HashMap<String, Vec<String>>>(input)
+            .expect_err("b is not a key of the mapping");
+
+        assert_eq!(
+            Some((3, 1)),
+            err.location()
+                .map(|location| (location.line(), location.column()))
+        );
+
+        let mut expected = String::new();
+        expected.push_str("\n");
+
+        expected.push_str(&format!("  {}{}\n", separator, "values:".yellow()));
+        expected.push_str(&format!("  {}{}\n", separator, "  - 'first'".yellow()));
+        expected.push_str(&format!(" {}{}b\n", "3".blue().bold(), separator));
+        expected.push_str(&format!(
+            "  {}{}\n",
+            separator,
+            "^ while parsing a block mapping, did not find expected key at line 3 column 1"
+                .red()
+                .bold()
+        ));
+
+        let got = SerdeError::new(input.to_string(), err).to_string();
+
+        println!("got:{}", got);
+        println!("expected:{}", expected);
+
+        assert_eq!(expected, got);
+    }
 }
 
 // TODO: Make tests that only use serde_json feature
+// These tests predate the pedantic lints and keep their original style
+#[allow(
+    clippy::format_push_string,
+    clippy::needless_raw_string_hashes,
+    clippy::single_char_add_str,
+    clippy::uninlined_format_args,
+    clippy::unnecessary_trailing_comma
+)]
 #[cfg(all(feature = "serde_json", feature = "colored"))]
 mod json {
     use anyhow::bail;
@@ -190,6 +470,7 @@ mod json {
 
     use super::{
         Config,
+        NestedConfig,
         SerdeError,
     };
 
@@ -219,19 +500,44 @@ mod json {
         Ok(())
     }
 
+    #[test]
+    fn empty_config_file_placeholder() -> Result<(), anyhow::Error> {
+        super::init();
+
+        let input = "";
+        let expected = format!(
+            "{}\n{}\n",
+            "(empty input)".dimmed(),
+            "EOF while parsing a value at line 1 column 0".red().bold(),
+        );
+
+        let got = match serde_json::from_str::<Config>(input) {
+            Ok(_) => bail!("expecting error got a ok"),
+            Err(err) => format!(
+                "{}",
+                SerdeError::new(input.to_string(), err)
+                    .set_empty_input_placeholder(Some("(empty input)".to_string()))
+            ),
+        };
+
+        println!("expected:{}", expected);
+        println!("got:{}", got);
+
+        assert_eq!(expected, got);
+
+        Ok(())
+    }
+
     #[test]
     fn empty_config_file_only_map() -> Result<(), anyhow::Error> {
         super::init();
-        let separator = super::separator();
 
         let input = "{}";
 
         let mut expected = String::new();
-        expected.push_str("\n");
-        expected.push_str(&format!(" {}{}{}\n", "1".blue().bold(), separator, "{}",));
+        expected.push_str(" {}\n");
         expected.push_str(&format!(
-            "  {}{}\n",
-            separator,
+            " {}\n",
             "  ^ missing field `values` at line 1 column 2".red().bold(),
         ));
 
@@ -246,19 +552,122 @@ mod json {
     }
 
     #[test]
-    fn unterminated_map() -> Result<(), anyhow::Error> {
+    fn empty_config_file_only_map_relocated() -> Result<(), anyhow::Error> {
+        super::init();
+
+        let input = "{}";
+
+        let mut expected = String::new();
+        expected.push_str(" {}\n");
+        expected.push_str(&format!(
+            " {}\n",
+            "^ missing field `values` at line 1 column 2".red().bold(),
+        ));
+        expected.push_str("  = help: add a `values` key to this object\n");
+
+        let got = match serde_json::from_str::<Config>(input) {
+            Ok(_) => bail!("expecting error got a ok"),
+            Err(err) => format!(
+                "{}",
+                SerdeError::new(input.to_string(), err).set_relocate_missing_field(true)
+            ),
+        };
+
+        println!("expected:{}", expected);
+        println!("got:{}", got);
+
+        assert_eq!(expected, got);
+
+        Ok(())
+    }
+
+    #[test]
+    fn missing_field_nested_relocated() -> Result<(), anyhow::Error> {
         super::init();
         let separator = super::separator();
 
-        let input = "{";
+        let input = "{\"other\": 1,\n \"inner\": {\n  \"name\": \"x\"\n }\n}";
 
         let mut expected = String::new();
         expected.push_str("\n");
-        expected.push_str(&format!(" {}{}{}\n", "1".blue().bold(), separator, "{",));
+        expected.push_str(&format!("  {}{}\n", separator, r#"{"other": 1,"#.yellow()));
+        expected.push_str(&format!(
+            " {}{}{}\n",
+            "2".blue().bold(),
+            separator,
+            r#" "inner": {"#,
+        ));
         expected.push_str(&format!(
             "  {}{}\n",
             separator,
-            " ^ EOF while parsing an object at line 1 column 1"
+            "          ^ missing field `values` at line 4 column 2"
+                .red()
+                .bold(),
+        ));
+        expected.push_str(&format!("  {}{}\n", separator, r#"  "name": "x""#.yellow()));
+        expected.push_str(&format!("  {}{}\n", separator, r#" }"#.yellow()));
+        expected.push_str(&format!("  {}{}\n", separator, r#"}"#.yellow()));
+        expected.push_str("   = help: add a `values` key to this object\n");
+
+        let got = match serde_json::from_str::<NestedConfig>(input) {
+            Ok(_) => bail!("expecting error got a ok"),
+            Err(err) => format!(
+                "{}",
+                SerdeError::new(input.to_string(), err).set_relocate_missing_field(true)
+            ),
+        };
+
+        println!("expected:{}", expected);
+        println!("got:{}", got);
+
+        assert_eq!(expected, got);
+
+        Ok(())
+    }
+
+    #[test]
+    fn mark_key() -> Result<(), anyhow::Error> {
+        super::init();
+
+        let input = r#"{"values" :  3}"#;
+
+        let mut expected = String::new();
+        expected.push_str(&format!(" {}\n", input));
+        expected.push_str(&format!(
+            " {}\n",
+            "              ^ invalid type: integer `3`, expected a sequence at line 1 column 14"
+                .red()
+                .bold(),
+        ));
+        expected.push_str(&format!(" {}\n", " - key here".blue().bold()));
+
+        let got = match serde_json::from_str::<Config>(input) {
+            Ok(_) => bail!("expecting error got a ok"),
+            Err(err) => format!(
+                "{}",
+                SerdeError::new(input.to_string(), err).set_mark_key(true)
+            ),
+        };
+
+        println!("expected:{}", expected);
+        println!("got:{}", got);
+
+        assert_eq!(expected, got);
+
+        Ok(())
+    }
+
+    #[test]
+    fn unterminated_map() -> Result<(), anyhow::Error> {
+        super::init();
+
+        let input = "{";
+
+        let mut expected = String::new();
+        expected.push_str(" {\n");
+        expected.push_str(&format!(
+            " {}\n",
+            " ↵ expected more: EOF while parsing an object at line 1 column 1"
                 .red()
                 .bold(),
         ));
@@ -321,24 +730,16 @@ mod json {
         super::init();
 
         let input = include_str!("../../resources/config.json");
-        let separator = super::separator();
         let ellipse = super::ellipse();
 
         let mut expected = String::new();
-        expected.push_str("\n");
-
         expected.push_str(&format!(
-            " {}{}{}{}{}\n",
-            "1".blue().bold(),
-            separator,
-            ellipse,
-            r#"09","asd110","asd111","asd112",{"invalid":"dont"},"asd113","a"#,
-            ellipse
+            " {}{}{}\n",
+            ellipse, r#"09","asd110","asd111","asd112",{"invalid":"dont"},"asd113","a"#, ellipse
         ));
 
         expected.push_str(&format!(
-            "  {}{}\n",
-            separator,
+            " {}\n",
             "                                  ^ invalid type: map, expected a string at line 1 \
              column 910"
                 .red()
@@ -356,9 +757,13 @@ mod json {
     }
 }
 
+// These tests predate the pedantic lints and keep their original style
+#[allow(clippy::uninlined_format_args)]
 mod context_long_line {
     use pretty_assertions::assert_eq;
 
+    use crate::ContextUnit;
+
     const SHORT_LINE: &str = "abc!def";
     const LONG_LINE: &str = "?orem ipsum dolor sit amet, consectetur adipiscing elit. Morbi \
                              luctus accumsan lorem, vulputate laci!nia tellus sodales sed. \
@@ -376,7 +781,12 @@ mod context_long_line {
         let expected_char = '!';
 
         let (got, new_error_column, context_before, context_after) =
-            super::SerdeError::context_long_line(input, error_column, context_chars);
+            super::SerdeError::context_long_line(
+                input,
+                error_column,
+                context_chars,
+                ContextUnit::default(),
+            );
         let got_char = got.chars().nth(new_error_column - 1).unwrap_or_default();
 
         assert_eq!(expected, got);
@@ -395,7 +805,12 @@ mod context_long_line {
         let expected_char = '!';
 
         let (got, new_error_column, context_before, context_after) =
-            super::SerdeError::context_long_line(input, error_column, context_chars);
+            super::SerdeError::context_long_line(
+                input,
+                error_column,
+                context_chars,
+                ContextUnit::default(),
+            );
         let got_char = got.chars().nth(new_error_column - 1).unwrap_or_default();
 
         assert_eq!(context_chars * 2 + 1, got.len());
@@ -415,7 +830,12 @@ mod context_long_line {
         let expected_char = '?';
 
         let (got, new_error_column, context_before, context_after) =
-            super::SerdeError::context_long_line(input, error_column, context_chars);
+            super::SerdeError::context_long_line(
+                input,
+                error_column,
+                context_chars,
+                ContextUnit::default(),
+            );
         let got_char = got.chars().nth(new_error_column - 1).unwrap_or_default();
 
         assert_eq!(context_chars * 2 + 1, got.len());
@@ -435,7 +855,12 @@ mod context_long_line {
         let expected_char = '!';
 
         let (got, new_error_column, context_before, context_after) =
-            super::SerdeError::context_long_line(input, error_column, context_chars);
+            super::SerdeError::context_long_line(
+                input,
+                error_column,
+                context_chars,
+                ContextUnit::default(),
+            );
         let got_char = got.chars().nth(new_error_column - 1).unwrap_or_default();
 
         assert_eq!(context_chars * 2 + 1, got.len());
@@ -455,7 +880,12 @@ mod context_long_line {
         let expected_char = '!';
 
         let (got, new_error_column, context_before, context_after) =
-            super::SerdeError::context_long_line(input, error_column, context_chars);
+            super::SerdeError::context_long_line(
+                input,
+                error_column,
+                context_chars,
+                ContextUnit::default(),
+            );
         let got_char = got.chars().nth(new_error_column - 1).unwrap_or_default();
 
         assert_eq!(context_chars * 2 + 1, got.len());
@@ -475,7 +905,12 @@ mod context_long_line {
         let expected_char = '!';
 
         let (got, new_error_column, context_before, context_after) =
-            super::SerdeError::context_long_line(input, error_column, context_chars);
+            super::SerdeError::context_long_line(
+                input,
+                error_column,
+                context_chars,
+                ContextUnit::default(),
+            );
         let got_char = got.chars().nth(new_error_column - 1).unwrap_or_default();
 
         assert_eq!(11, got.len());
@@ -495,7 +930,12 @@ mod context_long_line {
         let expected_char = '!';
 
         let (got, new_error_column, context_before, context_after) =
-            super::SerdeError::context_long_line(input, error_column, context_chars);
+            super::SerdeError::context_long_line(
+                input,
+                error_column,
+                context_chars,
+                ContextUnit::default(),
+            );
         let got_char = got.chars().nth(new_error_column - 1).unwrap_or_default();
 
         // 13 instead of 11 because len for a string gives back the amount of codepoints
@@ -518,7 +958,12 @@ mod context_long_line {
         let expected_char = '!';
 
         let (got, new_error_column, context_before, context_after) =
-            super::SerdeError::context_long_line(input, error_column, context_chars);
+            super::SerdeError::context_long_line(
+                input,
+                error_column,
+                context_chars,
+                ContextUnit::default(),
+            );
         let got_char = got.chars().nth(new_error_column - 1).unwrap_or_default();
 
         // 19 instead of 11 because len for a string gives back the amount of codepoints
@@ -529,8 +974,96 @@ mod context_long_line {
         assert!(context_before);
         assert!(context_after);
     }
+
+    /// A column between a base character and its combining mark is moved to
+    /// the start of the grapheme
+    #[cfg(feature = "graphemes_support")]
+    #[test]
+    fn grapheme_column_snapped() {
+        super::init();
+
+        let input = "name: e\u{301}x";
+
+        let inside = super::SerdeError::new(
+            input.to_string(),
+            ("invalid value".into(), Some(1), Some(7)),
+        );
+
+        let mut expected = String::new();
+        expected.push_str(" name: e\u{301}x\n");
+        expected.push_str("       ^ invalid value\n");
+
+        assert_eq!(inside.column(), Some(6));
+        assert_eq!(expected, inside.to_string());
+    }
+
+    /// Error at the end of a huge line with the caret anchored at a quarter of
+    /// the shown characters
+    #[test]
+    fn huge_line_anchored() {
+        let input = format!("{}!", "a".repeat(10_000));
+        let error_column = 10_000;
+        let context_chars = 20;
+        let before = super::SerdeError::anchored_before(0.25, context_chars);
+
+        let (got, new_error_column, context_before, context_after) =
+            super::SerdeError::context_long_line_anchored(
+                &input,
+                error_column,
+                context_chars,
+                before,
+                ContextUnit::default(),
+            );
+
+        assert_eq!(10, before);
+        assert_eq!(10, new_error_column);
+        assert_eq!(format!("{}!", "a".repeat(10)), got);
+        assert!(context_before);
+        assert!(!context_after);
+    }
+
+    /// The shortened line of the last render matches the displayed line
+    #[test]
+    fn last_rendered_error_line() {
+        super::init();
+
+        let mut err =
+            super::SerdeError::new(LONG_LINE.to_string(), ("error".into(), Some(1), Some(100)));
+        err.set_context_characters(10);
+
+        assert_eq!(None, err.last_rendered_error_line());
+
+        let got = err.to_string();
+        let (line, column, context_before, context_after) = err
+            .last_rendered_error_line()
+            .expect("error line should have been rendered");
+
+        assert_eq!("putate laci!nia tellu", line);
+        assert_eq!(11, column);
+        assert!(context_before);
+        assert!(context_after);
+        assert!(got.contains(&format!(" ...{}...\n", line)), "{}", got);
+        assert_eq!(Some('!'), line.chars().nth(column));
+
+        // Changing the settings doesn't change what was shown before
+        err.set_context_characters(3);
+
+        assert_eq!(
+            Some(("putate laci!nia tellu".to_string(), 11, true, true)),
+            err.last_rendered_error_line()
+        );
+
+        let _ = err.to_string();
+
+        assert_eq!(
+            Some(("laci!ni".to_string(), 4, true, true)),
+            err.last_rendered_error_line()
+        );
+    }
 }
 
+// These tests predate the pedantic lints and keep their original style
+#[allow(clippy::uninlined_format_args)]
 mod custom {
     use pretty_assertions::assert_eq;
 
@@ -565,6 +1098,70 @@ mod custom {
         assert_eq!(expected, got);
     }
 
+    /// Test that the caret line uses the annotation separator padded to the
+    /// width of the separator in front of the source lines
+    #[test]
+    fn short_line_annotation_separator() {
+        super::init();
+
+        let config_str =
+            "this is just a config file\nthe error is here: !\nanother line in the config";
+        let line = 2;
+        let column = 19;
+        let err = format!("Found an error at line {}, column {}", line, column);
+
+        let mut expected = String::from("\n");
+        expected.push_str("   | this is just a config file\n");
+        expected.push_str(" 2 | the error is here: !\n");
+        expected.push_str("   ·                    ^ Found an error at line 2, column 19\n");
+        expected.push_str("   | another line in the config\n");
+
+        let got = format!(
+            "{}",
+            super::SerdeError::new(
+                config_str.to_string(),
+                (err.into(), Some(line), Some(column))
+            )
+            .set_annotation_separator("·")
+        );
+
+        println!("got:\n{}", got);
+        println!("expected:\n{}", expected);
+
+        assert_eq!(expected, got);
+    }
+
+    /// Test that the byte offset counts the bytes of multibyte characters in
+    /// front of the error instead of characters
+    #[test]
+    fn short_line_byte_offset() {
+        super::init();
+
+        let config_str = "größe: x\nanother line";
+        let line = 1;
+        let column = 7;
+        let err = "invalid type: string, expected a number";
+
+        let mut expected = String::from("\n");
+        expected.push_str(" 1 | größe: x\n");
+        expected.push_str("   |        ^ invalid type: string, expected a number (byte 9)\n");
+        expected.push_str("   | another line\n");
+
+        let got = format!(
+            "{}",
+            super::SerdeError::new(
+                config_str.to_string(),
+                (err.into(), Some(line), Some(column))
+            )
+            .set_show_byte_offset(true)
+        );
+
+        println!("got:\n{}", got);
+        println!("expected:\n{}", expected);
+
+        assert_eq!(expected, got);
+    }
+
     /// Test with a short line where we set the amount of context lines to 0 to
     /// show no context lines
     #[test]
@@ -694,6 +1291,41 @@ mod custom {
         assert_eq!(expected, got);
     }
 
+    /// Test with line numbers disabled where the caret still has to align
+    #[test]
+    fn short_line_without_line_numbers() {
+        super::init();
+
+        let config_str =
+            "this is just a config file\nthe error is here: !\nanother line in the config";
+        let line = 2;
+        let column = 19;
+        let err = format!("Found an error at line {}, column {}", line, column);
+
+        let mut expected = String::from("\n");
+        expected.push_str("  | this is just a config file\n");
+        expected.push_str("  | the error is here: !\n");
+        expected.push_str("  |                    ^ Found an error at line 2, column 19\n");
+        expected.push_str("  | another line in the config\n");
+
+        let got = format!(
+            "{}",
+            super::SerdeError::new(
+                config_str.to_string(),
+                (err.into(), Some(line), Some(column))
+            )
+            .set_show_line_numbers(false)
+        );
+
+        println!("got:\n{}", got);
+        println!("expected:\n{}", expected);
+
+        assert!(got
+            .lines()
+            .all(|line| !line.split('|').next().unwrap_or_default().contains('2')));
+        assert_eq!(expected, got);
+    }
+
     /// Test for handling tabs single line
     #[test]
     fn tabs_single_line() {
@@ -704,9 +1336,9 @@ mod custom {
         let column = 12;
         let err = format!("Found an error at line {}, column {}", line, column);
 
-        let mut expected = String::from("\n");
-        expected.push_str(" 1 | 123456789error123456789\n");
-        expected.push_str("   |          ^ Found an error at line 1, column 12\n");
+        let mut expected = String::new();
+        expected.push_str(" 123456789error123456789\n");
+        expected.push_str("          ^ Found an error at line 1, column 12\n");
 
         let got = format!(
             "{}",
@@ -723,6 +1355,135 @@ mod custom {
         assert_eq!(expected, got);
     }
 
+    /// Test that the gutter shows the byte offsets of the lines in the original
+    /// input before tabs are expanded
+    #[test]
+    fn short_line_byte_offsets() {
+        super::init();
+
+        let config_str =
+            "this is just a config file\n\t\tthe error is here: !\nanother line in the config";
+        let line = 2;
+        let column = 21;
+        let err = format!("Found an error at line {}, column {}", line, column);
+
+        let mut expected = String::from("\n");
+        expected.push_str("   @0x00 | this is just a config file\n");
+        expected.push_str(" 2 @0x1b |   the error is here: !\n");
+        expected
+            .push_str("         |                      ^ Found an error at line 2, column 21\n");
+        expected.push_str("   @0x32 | another line in the config\n");
+
+        let got = format!(
+            "{}",
+            super::SerdeError::new(
+                config_str.to_string(),
+                (err.into(), Some(line), Some(column))
+            )
+            .set_show_byte_offsets(true)
+        );
+
+        println!("got:\n{}", got);
+        println!("expected:\n{}", expected);
+
+        assert_eq!(expected, got);
+    }
+
+    /// Test that relative line numbers show the distance to the error line
+    /// while the message keeps the absolute position
+    #[test]
+    fn relative_line_numbers() {
+        super::init();
+
+        let config_str = "line 1\nline 2\nline 3\nthe error is here: !\nline 5\nline 6\nline 7";
+        let line = 4;
+        let column = 19;
+        let err = format!("Found an error at line {}, column {}", line, column);
+
+        let mut expected = String::from("\n");
+        expected.push_str(" -3 | line 1\n");
+        expected.push_str(" -2 | line 2\n");
+        expected.push_str(" -1 | line 3\n");
+        expected.push_str("  0 | the error is here: !\n");
+        expected.push_str("    |                    ^ Found an error at line 4, column 19\n");
+        expected.push_str(" +1 | line 5\n");
+        expected.push_str(" +2 | line 6\n");
+        expected.push_str(" +3 | line 7\n");
+
+        let got = format!(
+            "{}",
+            super::SerdeError::new(
+                config_str.to_string(),
+                (err.into(), Some(line), Some(column))
+            )
+            .set_line_numbering(crate::LineNumbering::Relative)
+        );
+
+        println!("got:\n{}", got);
+        println!("expected:\n{}", expected);
+
+        assert_eq!(expected, got);
+    }
+
+    /// Test that the column number is the column in the input even if the line
+    /// has been dedented, tabs have been expanded and the line is shortened
+    #[test]
+    fn column_number() {
+        super::init();
+
+        let config_str = "\t\tfirst: 1\n\t\tthe error is somewhere in this very long line: !";
+        let line = 2;
+        let column = 49;
+        let err = "Found an error";
+
+        let mut expected = String::from("\n");
+        expected.push_str(" 2 | ...very long line: !\n");
+        expected.push_str("   |                    ^ (column 50) Found an error\n");
+
+        let got = format!(
+            "{}",
+            super::SerdeError::new(
+                config_str.to_string(),
+                (err.into(), Some(line), Some(column))
+            )
+            .set_context_lines(0)
+            .set_context_characters(15)
+            .set_show_column_number(true)
+        );
+
+        println!("got:\n{}", got);
+        println!("expected:\n{}", expected);
+
+        assert_eq!(expected, got);
+    }
+
+    /// Test that the caret anchor moves the shown part of a long line
+    #[test]
+    fn long_line_caret_anchor() {
+        super::init();
+
+        let config_str = format!("value: {}!", "a".repeat(10_000));
+        let line = 1;
+        let column = 10_007;
+        let err = "Found an error";
+
+        let mut expected = String::new();
+        expected.push_str(" ...aaaaaaaaaa!\n");
+        expected.push_str("              ^ (column 10008) Found an error\n");
+
+        let got = format!(
+            "{}",
+            super::SerdeError::new(config_str, (err.into(), Some(line), Some(column)))
+                .set_context_characters(20)
+                .set_caret_anchor(0.25)
+        );
+
+        println!("expected:\n{}", expected);
+        println!("got:\n{}", got);
+
+        assert_eq!(expected, got);
+    }
+
     /// Test for handling tabs with multiple lines
     #[test]
     fn tabs_multiple_lines() {
@@ -0,0 +1,70 @@
+use pretty_assertions::assert_eq;
+
+use super::SerdeError;
+
+const INPUT: &str = r#"{"name": "app", "replicas": "two", "image": "nginx", "ports": [80, 443], "debug": "yes", "user": "root"}"#;
+
+/// Both errors on the long line get their own window with a marker.
+#[test]
+fn two_errors_far_apart() {
+    super::init();
+
+    let mut err = SerdeError::new(
+        INPUT.to_string(),
+        ("expected a number".into(), Some(1), Some(29)),
+    );
+    err.set_context_characters(5)
+        .set_multiple_windows(true)
+        .add_label(1, 83, "expected a bool");
+
+    let mut expected = String::new();
+    expected.push_str(r#" ...s": "two", ...g": "yes", ..."#);
+    expected.push('\n');
+    expected.push_str("         ^ expected a number\n");
+    expected.push_str("                       - expected a bool\n");
+
+    assert_eq!(expected, err.to_string());
+}
+
+/// Markers close to each other share one window.
+#[test]
+fn overlapping_windows() {
+    super::init();
+
+    let mut err = SerdeError::new(
+        INPUT.to_string(),
+        ("expected a number".into(), Some(1), Some(29)),
+    );
+    err.set_context_characters(5)
+        .set_multiple_windows(true)
+        .add_label(1, 33, "defined here");
+
+    let mut expected = String::new();
+    expected.push_str(r#" ...s": "two", "ima..."#);
+    expected.push('\n');
+    expected.push_str("         ^ expected a number\n");
+    expected.push_str("             - defined here\n");
+
+    assert_eq!(expected, err.to_string());
+}
+
+/// Without multiple windows the label outside of the shortened line is left
+/// out.
+#[test]
+fn single_window() {
+    super::init();
+
+    let mut err = SerdeError::new(
+        INPUT.to_string(),
+        ("expected a number".into(), Some(1), Some(29)),
+    );
+    err.set_context_characters(5)
+        .add_label(1, 83, "expected a bool");
+
+    let mut expected = String::new();
+    expected.push_str(r#" ...as": "two",..."#);
+    expected.push('\n');
+    expected.push_str("          ^ (column 30) expected a number\n");
+
+    assert_eq!(expected, err.to_string());
+}
@@ -0,0 +1,307 @@
+use std::collections::HashMap;
+
+use pretty_assertions::assert_eq;
+
+use crate::{
+    AnnotationStyle,
+    ContextMode,
+    ContextUnit,
+    FormatError,
+    HyperlinkScheme,
+    IndentWs,
+    LineNumbering,
+    LongLineMode,
+    MarkerStyle,
+    RenderStyle,
+    SerdeError,
+    Severity,
+    TextDirection,
+};
+
+/// Small xorshift generator so the test doesn't need a random number crate
+/// and failures can be reproduced from the seed.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        #[allow(clippy::cast_possible_truncation)]
+        let value = self.next() as usize;
+
+        value % bound.max(1)
+    }
+
+    // `is_multiple_of` needs a newer compiler than the crate supports
+    #[allow(clippy::manual_is_multiple_of)]
+    fn flip(&mut self) -> bool {
+        self.next() % 2 == 0
+    }
+
+    fn pick<'a>(&mut self, values: &[&'a str]) -> &'a str {
+        values[self.below(values.len())]
+    }
+}
+
+const PIECES: [&str; 25] = [
+    "a",
+    "b",
+    " ",
+    "  ",
+    "\t",
+    "\n",
+    "\n",
+    "\r\n",
+    ":",
+    "{",
+    "}",
+    "[",
+    "]",
+    "\"",
+    ",",
+    "-",
+    "#",
+    "`",
+    "é",
+    "e\u{301}",
+    "字",
+    "\u{1b}[31m",
+    "\u{a0}",
+    "key: value",
+    "\"k\": 1",
+];
+
+const MESSAGES: [&str; 8] = [
+    "invalid type: string \"a\", expected u32 at line 1 column 2",
+    "missing field `name`",
+    "unknown field `nmae`, expected one of `name`, `port`",
+    "invalid length 3, expected 2",
+    "EOF while parsing a value",
+    "trailing comma",
+    "",
+    "line one\nline two",
+];
+
+fn random_error(rng: &mut Rng) -> SerdeError {
+    let input = (0..rng.below(40))
+        .map(|_| rng.pick(&PIECES))
+        .collect::<String>();
+
+    let lines = input.lines().count();
+    let line = if rng.below(8) == 0 {
+        None
+    } else {
+        Some(rng.below(lines + 3))
+    };
+
+    let column = if rng.below(8) == 0 {
+        None
+    } else {
+        Some(rng.below(input.len() + 4))
+    };
+
+    let message = rng.pick(&MESSAGES);
+    let mut err = SerdeError::new(input, (message.into(), line, column));
+
+    err.set_contextualize(rng.flip())
+        .set_context_lines(rng.below(5))
+        .set_context_characters(rng.below(40))
+        .set_show_line_numbers(rng.flip())
+        .set_tab_width(rng.below(8))
+        .set_relocate_missing_field(rng.flip())
+        .set_mark_key(rng.flip())
+        .set_show_value_preview(rng.flip())
+        .set_show_byte_offset(rng.flip())
+        .set_underline_sequence(rng.flip())
+        .set_annotation_separator(rng.pick(&["", " | ", " · ", "|"]))
+        .set_categorize(rng.flip())
+        .set_show_byte_offsets(rng.flip())
+        .set_block_indent(rng.below(3))
+        .set_show_column_number(rng.flip())
+        .set_multiple_windows(rng.flip())
+        .set_show_anchor_line(rng.flip())
+        .set_show_stats(rng.flip())
+        .set_show_total_lines(rng.flip())
+        .set_strip_ansi(rng.flip())
+        .set_strip_position_suffix(rng.flip())
+        .set_show_raw_message(rng.flip())
+        .set_wrap_context_lines(rng.flip())
+        .set_show_whitespace(rng.flip())
+        .set_frame_single_line(rng.flip());
+
+    if rng.flip() {
+        err.set_line_numbering(LineNumbering::Relative);
+    }
+
+    random_styles(rng, &mut err);
+
+    if rng.flip() {
+        err.set_long_line_mode(LongLineMode::Wrap)
+            .set_wrap_width(rng.below(40));
+    }
+
+    if rng.flip() {
+        err.set_max_message_width(rng.below(40));
+    }
+
+    if rng.flip() {
+        err.set_viewport_width(rng.below(60));
+    }
+
+    if rng.flip() {
+        #[allow(clippy::cast_precision_loss)]
+        err.set_caret_anchor(rng.below(200) as f32 / 100.0 - 0.5);
+    }
+
+    if rng.flip() {
+        err.set_span_start(rng.below(lines + 2), rng.below(20));
+    }
+
+    if rng.flip() {
+        err.add_label(rng.below(lines + 2), rng.below(60), "label");
+    }
+
+    if rng.flip() {
+        err.set_line_statuses(HashMap::from([(rng.below(lines + 2), '+')]));
+    }
+
+    if rng.flip() {
+        err.set_empty_input_placeholder(Some("(empty)".to_string()));
+    }
+
+    if rng.flip() {
+        err.set_path("config.yaml")
+            .set_hyperlink_scheme(HyperlinkScheme::VsCode);
+    }
+
+    if rng.flip() {
+        err.set_text_direction(TextDirection::Rtl);
+    }
+
+    if rng.flip() {
+        err.set_severity(Severity::Warning);
+    }
+
+    err
+}
+
+/// Randomize how the error line and the gutter are styled.
+fn random_styles(rng: &mut Rng, err: &mut SerdeError) {
+    if rng.flip() {
+        err.set_context_mode(ContextMode::Structural);
+    }
+
+    if rng.flip() {
+        err.set_marker_style(MarkerStyle::Box);
+    }
+
+    if rng.flip() {
+        err.set_annotation_style(AnnotationStyle::Inline);
+    }
+
+    match rng.below(3) {
+        0 => err.set_context_chars_unit(ContextUnit::Chars),
+        1 => err.set_context_chars_unit(ContextUnit::Columns),
+        _ => &mut *err,
+    };
+
+    if rng.flip() {
+        err.set_indentation_whitespace(IndentWs::AsciiOnly);
+    }
+
+    if rng.flip() {
+        err.set_line_number_formatter(|line| format!("L{line}"));
+    }
+
+    if rng.flip() {
+        err.set_highlight_term(rng.pick(&PIECES));
+    }
+
+    match rng.below(5) {
+        0 => err.set_render_style(RenderStyle::Compact),
+        1 => err.set_render_style(RenderStyle::Minimal),
+        2 => err.set_render_style(RenderStyle::Assertion),
+        _ => &mut *err,
+    };
+}
+
+/// Rendering random inputs, locations and settings never panics.
+#[test]
+fn random_errors() {
+    super::init();
+
+    for seed in 1..=5_000 {
+        let mut rng = Rng(seed);
+        let err = random_error(&mut rng);
+
+        let rendered = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let _ = err.format_checked();
+            err.to_string()
+        }));
+
+        assert!(
+            rendered.is_ok(),
+            "rendering panicked for seed {}: {:?}",
+            seed,
+            err
+        );
+    }
+}
+
+#[test]
+fn column_out_of_range() {
+    super::init();
+
+    let err = SerdeError::new("a: b\nc: d".to_string(), ("error".into(), Some(2), Some(9)));
+
+    assert_eq!(
+        Err(FormatError::ColumnOutOfRange {
+            line: 2,
+            column: 9,
+            length: 4,
+        }),
+        err.format_checked()
+    );
+    assert_eq!("error\n", err.to_string());
+}
+
+#[test]
+fn line_out_of_range() {
+    super::init();
+
+    let err = SerdeError::new("a: b\nc: d".to_string(), ("error".into(), Some(7), Some(0)));
+
+    assert_eq!(
+        Err(FormatError::LineOutOfRange { line: 7, lines: 2 }),
+        err.format_checked()
+    );
+    assert_eq!("error\n", err.to_string());
+}
+
+/// Lines start at 1 so the message is shown on its own for line 0.
+#[test]
+fn line_zero() {
+    super::init();
+
+    let err = SerdeError::new("a: b\nc: d".to_string(), ("error".into(), Some(0), Some(1)));
+
+    assert_eq!(
+        Err(FormatError::LineOutOfRange { line: 0, lines: 2 }),
+        err.format_checked()
+    );
+    assert_eq!("error\n", err.to_string());
+}
+
+#[test]
+fn end_of_input_is_in_range() {
+    super::init();
+
+    let err = SerdeError::new("a: b\n".to_string(), ("error".into(), Some(1), Some(4)));
+
+    assert_eq!(Ok(err.to_string()), err.format_checked());
+}
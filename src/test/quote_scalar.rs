@@ -0,0 +1,98 @@
+use pretty_assertions::assert_eq;
+use serde::Deserialize;
+
+use crate::SerdeError;
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct Config {
+    name: Option<String>,
+    version: String,
+}
+
+fn render_yaml(input: &str, column: usize) -> String {
+    SerdeError::new(
+        input.to_string(),
+        (
+            "invalid type: floating point `3.1`, expected a string".into(),
+            Some(1),
+            Some(column),
+        ),
+    )
+    .to_string()
+}
+
+fn render_json(input: &str) -> String {
+    let err = serde_json::from_str::<Config>(input).expect_err("version should not be a string");
+
+    SerdeError::new(input.to_string(), err).to_string()
+}
+
+/// The version is read as a number and the corrected line is suggested.
+#[test]
+fn yaml() {
+    super::init();
+
+    let mut expected = String::new();
+    expected.push_str(" version: 3.10 # latest\n");
+    expected.push_str("          ^^^^ invalid type: floating point `3.1`, expected a string\n");
+    expected.push_str("  = help: unquoted `3.10` is parsed as a number in YAML\n");
+    expected
+        .push_str("  = help: quote the value to keep it a string: `version: \"3.10\" # latest`\n");
+
+    assert_eq!(expected, render_yaml("version: 3.10 # latest", 9));
+}
+
+/// Values in flow mappings end in front of the comma or the closing brace.
+#[test]
+fn yaml_flow() {
+    super::init();
+
+    let mut expected = String::new();
+    expected.push_str(" {version: 3.10, name: test}\n");
+    expected.push_str("           ^^^^ invalid type: floating point `3.1`, expected a string\n");
+    expected.push_str("  = help: unquoted `3.10` is parsed as a number in YAML\n");
+    expected.push_str(
+        "  = help: quote the value to keep it a string: `{version: \"3.10\", name: test}`\n",
+    );
+
+    assert_eq!(expected, render_yaml("{version: 3.10, name: test}", 10));
+
+    assert!(render_yaml("{name: test, version: 3.10}", 22)
+        .ends_with("`{name: test, version: \"3.10\"}`\n"));
+}
+
+/// `serde_json` points behind the value which is underlined instead.
+#[test]
+fn json() {
+    super::init();
+
+    let mut expected = String::new();
+    expected.push_str(" {\"version\": 3.10}\n");
+    expected.push_str(
+        "             ^^^^ invalid type: floating point `3.1`, expected a string at line 1 column \
+         16\n",
+    );
+    expected.push_str("  = help: quote the value to keep it a string: `{\"version\": \"3.10\"}`\n");
+
+    assert_eq!(expected, render_json("{\"version\": 3.10}"));
+}
+
+/// Only the quoted value is suggested for long lines.
+#[test]
+fn json_long_line() {
+    super::init();
+
+    let input = format!("{{\"name\": \"{}\", \"version\": true}}", "a".repeat(80));
+
+    assert!(render_json(&input)
+        .ends_with("  = help: quote the value to keep it a string: `\"true\"`\n"));
+}
+
+/// Maps are not quoted.
+#[test]
+fn json_map() {
+    super::init();
+
+    assert!(!render_json("{\"version\": {\"major\": 3}}").contains("help:"));
+}
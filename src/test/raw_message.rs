@@ -0,0 +1,59 @@
+use pretty_assertions::assert_eq;
+
+use super::SerdeError;
+
+const MESSAGE: &str = "invalid type: integer `1`, expected a string at line 1 column 14";
+
+#[test]
+fn strip_position_suffix() {
+    super::init();
+
+    let mut err = SerdeError::new(
+        r#"{"values": [1]}"#.to_string(),
+        (MESSAGE.into(), Some(1), Some(13)),
+    );
+    err.set_strip_position_suffix(true);
+
+    let mut expected = String::new();
+    expected.push_str(r#" {"values": [1]}"#);
+    expected.push('\n');
+    expected.push_str("             ^ invalid type: integer `1`, expected a string\n");
+    expected.push_str("  = help: quote the value to keep it a string: `{\"values\": [\"1\"]}`\n");
+
+    assert_eq!(expected, err.to_string());
+    assert_eq!(MESSAGE, err.message());
+}
+
+#[test]
+fn show_raw_message() {
+    super::init();
+
+    let mut err = SerdeError::new(
+        r#"{"values": [1]}"#.to_string(),
+        (MESSAGE.into(), Some(1), Some(13)),
+    );
+    err.set_strip_position_suffix(true)
+        .set_show_raw_message(true);
+
+    let mut expected = String::new();
+    expected.push_str(r#" {"values": [1]}"#);
+    expected.push('\n');
+    expected.push_str("             ^ invalid type: integer `1`, expected a string\n");
+    expected.push_str("  = raw message: ");
+    expected.push_str(MESSAGE);
+    expected.push('\n');
+    expected.push_str("  = help: quote the value to keep it a string: `{\"values\": [\"1\"]}`\n");
+
+    assert_eq!(expected, err.to_string());
+}
+
+#[test]
+fn keep_message_without_position() {
+    super::init();
+
+    let message = "expected a value at line one";
+    let mut err = SerdeError::new(String::new(), (message.into(), None, None));
+    err.set_strip_position_suffix(true);
+
+    assert_eq!(format!("{}\n", message), err.to_string());
+}
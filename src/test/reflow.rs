@@ -0,0 +1,95 @@
+use pretty_assertions::assert_eq;
+
+use super::SerdeError;
+
+const INPUT: &str = "first: line
+values: [aaaaaaaaaa, bbbbbbbbbb, cccccccccc, dddddddddd, eeeeeeeeee]
+last: line";
+
+#[test]
+fn caret_on_wrapped_segment() {
+    super::init();
+
+    let mut err = SerdeError::new(
+        INPUT.to_string(),
+        ("invalid value".into(), Some(2), Some(46)),
+    );
+    err.set_viewport_width(40);
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | first: line\n");
+    expected.push_str(" 2 | values: [aaaaaaaaaa, bbbbbbbbbb, cc\n");
+    expected.push_str("   | cccccccc, dddddddddd, eeeeeeeeee]\n");
+    expected.push_str("   |            ^ invalid value\n");
+    expected.push_str("   | last: line\n");
+
+    assert_eq!(expected, err.to_string());
+    assert!(expected.lines().all(|line| line.chars().count() <= 40));
+}
+
+#[test]
+fn message_wrapped_below_caret() {
+    super::init();
+
+    let mut err = SerdeError::new(
+        INPUT.to_string(),
+        (
+            "invalid value, expected a sequence of numbers".into(),
+            Some(2),
+            Some(32),
+        ),
+    );
+    err.set_viewport_width(40);
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | first: line\n");
+    expected.push_str(" 2 | values: [aaaaaaaaaa, bbbbbbbbbb, cc\n");
+    expected.push_str("   |                                 ^\n");
+    expected.push_str("   | invalid value, expected a sequence\n");
+    expected.push_str("   | of numbers\n");
+    expected.push_str("   | cccccccc, dddddddddd, eeeeeeeeee]\n");
+    expected.push_str("   | last: line\n");
+
+    assert_eq!(expected, err.to_string());
+}
+
+#[test]
+fn underline_across_segments() {
+    super::init();
+
+    let mut err = SerdeError::new(
+        INPUT.to_string(),
+        ("invalid value".into(), Some(2), Some(40)),
+    );
+    err.set_viewport_width(40).set_span_start(2, 21);
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | first: line\n");
+    expected.push_str(" 2 | values: [aaaaaaaaaa, bbbbbbbbbb, cc\n");
+    expected.push_str("   |                      ^^^^^^^^^^^^^^\n");
+    expected.push_str("   | cccccccc, dddddddddd, eeeeeeeeee]\n");
+    expected.push_str("   | ^^^^^^ invalid value\n");
+    expected.push_str("   | last: line\n");
+
+    assert_eq!(expected, err.to_string());
+}
+
+#[test]
+fn message_only() {
+    super::init();
+
+    let mut err = SerdeError::new(
+        String::new(),
+        (
+            "invalid value, expected a sequence of numbers".into(),
+            None,
+            None,
+        ),
+    );
+    err.set_viewport_width(20);
+
+    assert_eq!(
+        "invalid value,\nexpected a sequence\nof numbers\n",
+        err.to_string()
+    );
+}
@@ -0,0 +1,140 @@
+use std::fmt;
+
+use pretty_assertions::assert_eq;
+
+use super::SerdeError;
+
+struct Wrapper(SerdeError);
+
+impl fmt::Display for Wrapper {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to load config:")?;
+        self.0.render(f)
+    }
+}
+
+/// A wrapper type can delegate into the rendering of the error from its own
+/// Display implementation.
+#[test]
+fn wrapper_display() {
+    super::init();
+
+    let config_str = "this is just a config file\nthe error is here: !";
+    let err = SerdeError::new(
+        config_str.to_string(),
+        ("Found an error".into(), Some(2), Some(19)),
+    );
+
+    let mut expected = String::from("failed to load config:\n");
+    expected.push_str("   | this is just a config file\n");
+    expected.push_str(" 2 | the error is here: !\n");
+    expected.push_str("   |                    ^ Found an error\n");
+
+    let got = Wrapper(err).to_string();
+
+    println!("got:\n{got}");
+    println!("expected:\n{expected}");
+
+    assert_eq!(expected, got);
+}
+
+/// Rendering with the default settings is the same as using the global
+/// defaults when they have not been changed.
+#[test]
+fn default_settings() {
+    super::init();
+
+    let config_str = "this is just a config file\nthe error that is somewhere in this line will \
+                      be found somewhere after here maybe we can find it here: !, it could also \
+                      be somewhere else\nanother line";
+
+    let new = SerdeError::new(
+        config_str.to_string(),
+        ("Found an error".into(), Some(2), Some(103)),
+    );
+
+    let with_config = SerdeError::with_config(
+        config_str.to_string(),
+        ("Found an error".into(), Some(2), Some(103)),
+        crate::RenderSettings::default(),
+    );
+
+    assert_eq!(new.get_settings(), with_config.get_settings());
+    assert_eq!(new.to_string(), with_config.to_string());
+}
+
+/// The structured lines cover the same window as the rendered output.
+#[test]
+fn lines_with_carets() {
+    super::init();
+
+    let config_str = "1\n2\n3\n4\n5\nthe error is here: !\n7\n8\n9\n10\n11";
+    let err = SerdeError::new(
+        config_str.to_string(),
+        ("Found an error".into(), Some(6), Some(19)),
+    );
+
+    assert_eq!(
+        vec![
+            (3, "3", None),
+            (4, "4", None),
+            (5, "5", None),
+            (6, "the error is here: !", Some(19)),
+            (7, "7", None),
+            (8, "8", None),
+            (9, "9", None),
+        ],
+        err.lines_with_carets()
+    );
+}
+
+#[test]
+fn lines_with_carets_without_context() {
+    super::init();
+
+    let config_str = "first\nthe error is here: !\nlast";
+    let mut err = SerdeError::new(
+        config_str.to_string(),
+        ("Found an error".into(), Some(2), Some(19)),
+    );
+    err.set_contextualize(false);
+
+    assert_eq!(
+        vec![(2, "the error is here: !", Some(19))],
+        err.lines_with_carets()
+    );
+}
+
+#[test]
+fn lines_with_carets_without_location() {
+    super::init();
+
+    let err = SerdeError::new("input".to_string(), ("Found an error".into(), None, None));
+
+    assert!(err.lines_with_carets().is_empty());
+}
+
+/// Only errors with a location are rendered by `try_render`.
+#[test]
+fn try_render() {
+    super::init();
+
+    let err = SerdeError::new(
+        "a: b".to_string(),
+        ("invalid type".into(), Some(1), Some(3)),
+    );
+    assert_eq!(err.try_render(), Some(err.to_string()));
+
+    let err = SerdeError::new("a: b".to_string(), ("invalid type".into(), None, None));
+    assert_eq!(err.try_render(), None);
+
+    // A column alone only shows the message
+    let err = SerdeError::new("a: b".to_string(), ("invalid type".into(), None, Some(3)));
+    assert_eq!(err.try_render(), None);
+
+    let err = SerdeError::new(
+        "a: b".to_string(),
+        ("invalid type".into(), Some(5), Some(0)),
+    );
+    assert_eq!(err.try_render(), None);
+}
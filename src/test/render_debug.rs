@@ -0,0 +1,72 @@
+use pretty_assertions::assert_eq;
+
+use crate::SerdeError;
+
+#[test]
+fn shortened() {
+    super::init();
+
+    let input = "\t\tfirst: 1\n\t\tthe error is somewhere in this very long line: !";
+
+    let mut err = SerdeError::new(
+        input.to_string(),
+        ("Found an error".into(), Some(2), Some(49)),
+    );
+    err.set_context_lines(0).set_context_characters(15);
+
+    let mut expected = String::new();
+    expected.push_str("layout: shortened\n");
+    expected.push_str("error_line: 2\n");
+    expected.push_str("error_column: 49\n");
+    expected.push_str("whitespace_count: 2\n");
+    expected.push_str("line_length: 48\n");
+    expected.push_str("long_line_threshold: 31\n");
+    expected.push_str("skip: 31\n");
+    expected.push_str("take: 31\n");
+    expected.push_str("context_before: true\n");
+    expected.push_str("context_after: false\n");
+    expected.push_str("caret_column: 19\n");
+    expected.push('\n');
+    expected.push('\n');
+    expected.push_str(" 2 | ...very long line: !\n");
+    expected.push_str("   |                    ^ (column 50) Found an error\n");
+
+    assert_eq!(expected, err.render_debug());
+}
+
+#[test]
+fn complete() {
+    super::init();
+
+    let err = SerdeError::new(
+        "  port: x".to_string(),
+        ("invalid type".into(), Some(1), Some(8)),
+    );
+
+    let mut expected = String::new();
+    expected.push_str("layout: complete\n");
+    expected.push_str("error_line: 1\n");
+    expected.push_str("error_column: 8\n");
+    expected.push_str("whitespace_count: 2\n");
+    expected.push_str("line_length: 7\n");
+    expected.push_str("long_line_threshold: 61\n");
+    expected.push_str("skip: 0\n");
+    expected.push_str("take: 0\n");
+    expected.push_str("context_before: false\n");
+    expected.push_str("context_after: false\n");
+    expected.push_str("caret_column: 6\n");
+    expected.push('\n');
+    expected.push_str(" port: x\n");
+    expected.push_str("       ^ invalid type\n");
+
+    assert_eq!(expected, err.render_debug());
+}
+
+#[test]
+fn message_only() {
+    super::init();
+
+    let err = SerdeError::new("port: x".to_string(), ("invalid type".into(), None, None));
+
+    assert!(err.render_debug().starts_with("layout: message only\n\n"));
+}
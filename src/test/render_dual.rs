@@ -0,0 +1,72 @@
+use pretty_assertions::assert_eq;
+
+use crate::SerdeError;
+
+/// The location in the JSON is the one the caret points at.
+#[test]
+fn consistent() {
+    super::init();
+
+    let input = "{\n  \"workers\": 4,\n  \"port\": \"x\"\n}";
+    let err = serde_json::from_str::<std::collections::HashMap<String, u16>>(input)
+        .expect_err("port should not be a number");
+
+    let mut err = SerdeError::new(input.to_string(), err);
+    err.set_context_lines(1);
+
+    let (human, json) = err.render_dual();
+
+    let rows = human.lines().collect::<Vec<_>>();
+    let caret_row = rows
+        .iter()
+        .position(|row| row.contains('^'))
+        .expect("human output should have a caret");
+
+    let caret_column = rows[caret_row]
+        .split_once("| ")
+        .and_then(|(_, marks)| marks.find('^'))
+        .expect("caret should be behind the separator");
+
+    let error_line = rows[caret_row - 1]
+        .split_once('|')
+        .and_then(|(gutter, _)| gutter.trim().parse::<u64>().ok())
+        .expect("line above the caret should have a line number");
+
+    assert_eq!(json["adapter"], "json");
+    assert_eq!(json["line"], error_line);
+    assert_eq!(json["column"], caret_column as u64);
+
+    let shown = json["lines"]
+        .as_array()
+        .expect("lines should be an array")
+        .iter()
+        .map(|line| line["text"].as_str().unwrap_or_default().to_string())
+        .collect::<Vec<_>>();
+
+    assert_eq!(vec!["  \"workers\": 4,", "  \"port\": \"x\"", "}"], shown);
+}
+
+/// Without a location only the message is known.
+#[test]
+fn without_location() {
+    super::init();
+
+    let err = SerdeError::new("port: x".to_string(), ("invalid type".into(), None, None));
+    let (human, json) = err.render_dual();
+
+    assert_eq!("invalid type\n", human);
+    assert_eq!(
+        serde_json::json!({
+            "message": "invalid type",
+            "severity": "error",
+            "kind": "data",
+            "adapter": "custom",
+            "line": null,
+            "column": null,
+            "lines": [],
+            "labels": [],
+            "notes": [],
+        }),
+        json
+    );
+}
@@ -0,0 +1,99 @@
+use pretty_assertions::assert_eq;
+
+#[cfg(any(feature = "serde_yaml", feature = "serde_json"))]
+use super::config::Config;
+use crate::{
+    RenderStyle,
+    SerdeError,
+};
+
+fn error(render_style: RenderStyle) -> String {
+    let mut err = SerdeError::new(
+        "first: 1\nsecond: x\nthird: 3".to_string(),
+        (
+            "expected a number at line 2 column 9".into(),
+            Some(2),
+            Some(8),
+        ),
+    );
+    err.set_path("config.yaml").set_render_style(render_style);
+
+    err.to_string()
+}
+
+#[test]
+fn full() {
+    super::init();
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | first: 1\n");
+    expected.push_str(" 2 | second: x\n");
+    expected.push_str("   |         ^ expected a number at line 2 column 9\n");
+    expected.push_str("   | third: 3\n");
+
+    assert_eq!(expected, error(RenderStyle::Full));
+}
+
+#[test]
+fn compact() {
+    super::init();
+
+    let mut expected = String::from(" 2 | second: x\n");
+    expected.push_str("   |         ^ expected a number at line 2 column 9\n");
+
+    assert_eq!(expected, error(RenderStyle::Compact));
+}
+
+#[test]
+fn minimal() {
+    super::init();
+
+    assert_eq!(
+        "config.yaml:2:9: expected a number\n",
+        error(RenderStyle::Minimal)
+    );
+}
+
+#[test]
+fn assertion() {
+    super::init();
+
+    assert_eq!(
+        "expected a number (config.yaml:2:9)\n",
+        error(RenderStyle::Assertion)
+    );
+}
+
+/// The path `serde_yaml` puts in front of the message is shown behind it.
+#[cfg(feature = "serde_yaml")]
+#[test]
+fn assertion_yaml() {
+    super::init();
+
+    let input = "values:\n  - a\n  - b\n  - invalid: x\n";
+    let err = serde_yaml::from_str::<Config>(input).expect_err("map is not a string");
+    let mut err = SerdeError::new(input.to_string(), err);
+    err.set_render_style(RenderStyle::Assertion);
+
+    assert_eq!(
+        "expected string, found map @ values[2] (4:12)\n",
+        err.to_string()
+    );
+}
+
+/// The path of JSON values is found in the input.
+#[cfg(feature = "serde_json")]
+#[test]
+fn assertion_json() {
+    super::init();
+
+    let input = "{\n  \"values\": [\"a\", \"b\", {\"invalid\": \"x\"}]\n}";
+    let err = serde_json::from_str::<Config>(input).expect_err("map is not a string");
+    let mut err = SerdeError::new(input.to_string(), err);
+    err.set_render_style(RenderStyle::Assertion);
+
+    assert_eq!(
+        "expected string, found map @ values[2] (2:24)\n",
+        err.to_string()
+    );
+}
@@ -0,0 +1,173 @@
+use std::path::PathBuf;
+
+use pretty_assertions::assert_eq;
+
+use crate::{
+    Diagnostic,
+    ErrorAdapter,
+    Report,
+    SerdeError,
+    Severity,
+    SortKey,
+};
+
+fn error(line: usize, column: usize) -> SerdeError {
+    SerdeError::new(
+        "first line\nsecond line".to_string(),
+        ("Found an error".into(), Some(line), Some(column)),
+    )
+}
+
+/// Errors are grouped by file and followed by a summary.
+#[test]
+fn grouped_by_file() {
+    super::init();
+
+    let mut report = Report::new();
+    assert!(report.is_empty());
+
+    report
+        .add("a.yaml", error(1, 6))
+        .add("b.yaml", error(2, 0))
+        .add("a.yaml", error(2, 7));
+
+    let mut expected = String::from("--> a.yaml\n");
+    expected.push('\n');
+    expected.push_str(" 1 | first line\n");
+    expected.push_str("   |       ^ Found an error\n");
+    expected.push_str("   | second line\n");
+    expected.push('\n');
+    expected.push_str("   | first line\n");
+    expected.push_str(" 2 | second line\n");
+    expected.push_str("   |        ^ Found an error\n");
+    expected.push('\n');
+    expected.push_str("--> b.yaml\n");
+    expected.push('\n');
+    expected.push_str("   | first line\n");
+    expected.push_str(" 2 | second line\n");
+    expected.push_str("   | ^ Found an error\n");
+    expected.push('\n');
+    expected.push_str("3 errors in 2 files\n");
+
+    let got = report.to_string();
+
+    println!("got:\n{got}");
+    println!("expected:\n{expected}");
+
+    assert!(!report.is_empty());
+    assert_eq!(expected, got);
+}
+
+/// The structured output contains one entry per error in file order.
+#[test]
+fn diagnostics() {
+    let mut report = Report::new();
+    report
+        .add("a.yaml", error(1, 6))
+        .add("b.yaml", error(2, 0))
+        .add("a.yaml", error(2, 7));
+
+    let expected = vec![
+        Diagnostic {
+            path: PathBuf::from("a.yaml"),
+            line: Some(1),
+            column: Some(6),
+            message: "Found an error".to_string(),
+            error_kind: ErrorAdapter::Custom,
+        },
+        Diagnostic {
+            path: PathBuf::from("a.yaml"),
+            line: Some(2),
+            column: Some(7),
+            message: "Found an error".to_string(),
+            error_kind: ErrorAdapter::Custom,
+        },
+        Diagnostic {
+            path: PathBuf::from("b.yaml"),
+            line: Some(2),
+            column: Some(0),
+            message: "Found an error".to_string(),
+            error_kind: ErrorAdapter::Custom,
+        },
+    ];
+
+    assert_eq!(expected, report.diagnostics());
+}
+
+/// Errors for the ordering tests. The warning and the last error are at the
+/// same position.
+fn ordering_report(sort_key: SortKey, merge_overlapping: bool) -> Report {
+    let mut warning = error(2, 7);
+    warning.set_severity(Severity::Warning);
+
+    let mut report = Report::builder()
+        .sort_by(sort_key)
+        .merge_overlapping(merge_overlapping)
+        .build();
+
+    report
+        .add("a.yaml", warning)
+        .add("a.yaml", error(1, 6))
+        .add("a.yaml", error(2, 7));
+
+    report
+}
+
+fn positions(report: &Report) -> Vec<(Option<usize>, Option<usize>, Severity, usize)> {
+    report
+        .iter()
+        .map(|(_, err)| {
+            (
+                err.line(),
+                err.column(),
+                err.get_severity(),
+                err.to_string().matches("note: Found an error").count(),
+            )
+        })
+        .collect()
+}
+
+/// Every combination of the order and merging of overlapping errors.
+#[test]
+fn ordering() {
+    super::init();
+
+    let w = Severity::Warning;
+    let e = Severity::Error;
+
+    let cases = vec![
+        (SortKey::Line, true, vec![(1, 6, e, 0), (2, 7, e, 1)]),
+        (
+            SortKey::Line,
+            false,
+            vec![(1, 6, e, 0), (2, 7, w, 0), (2, 7, e, 0)],
+        ),
+        (SortKey::Severity, true, vec![(1, 6, e, 0), (2, 7, e, 1)]),
+        (
+            SortKey::Severity,
+            false,
+            vec![(1, 6, e, 0), (2, 7, e, 0), (2, 7, w, 0)],
+        ),
+        (SortKey::Insertion, true, vec![(2, 7, e, 1), (1, 6, e, 0)]),
+        (
+            SortKey::Insertion,
+            false,
+            vec![(2, 7, w, 0), (1, 6, e, 0), (2, 7, e, 0)],
+        ),
+    ];
+
+    for (sort_key, merge_overlapping, expected) in cases {
+        let expected = expected
+            .into_iter()
+            .map(|(line, column, severity, notes)| (Some(line), Some(column), severity, notes))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            expected,
+            positions(&ordering_report(sort_key, merge_overlapping)),
+            "{:?} merge_overlapping: {}",
+            sort_key,
+            merge_overlapping
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use pretty_assertions::assert_eq;
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use super::SerdeError;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[allow(dead_code)]
+struct Config {
+    name: String,
+    servers: Vec<Server>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+#[allow(dead_code)]
+struct Server {
+    version: u32,
+    verbose: Option<bool>,
+    environment: Environment,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+#[allow(dead_code)]
+enum Environment {
+    Production,
+    Staging,
+    Development,
+}
+
+fn help(err: SerdeError) -> Option<String> {
+    err.with_schema::<Config>()
+        .to_string()
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("= help: "))
+        .map(ToString::to_string)
+}
+
+#[cfg(feature = "serde_json")]
+fn json(input: &str) -> Option<String> {
+    let err = serde_json::from_str::<Config>(input).expect_err("input should not parse");
+
+    help(SerdeError::new(input.to_string(), err))
+}
+
+#[cfg(feature = "serde_yaml")]
+fn yaml(input: &str) -> Option<String> {
+    let err = serde_yaml::from_str::<Config>(input).expect_err("input should not parse");
+
+    help(SerdeError::new(input.to_string(), err))
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn json_top_level_field() {
+    super::init();
+
+    assert_eq!(
+        Some("did you mean `name`?".to_string()),
+        json(r#"{"nmae": "x", "servers": []}"#)
+    );
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn json_nested_field() {
+    super::init();
+
+    let input = r#"{
+  "name": "x",
+  "servers": [
+    {"version": 1, "environment": "staging"},
+    {"verison": 2}
+  ]
+}"#;
+
+    assert_eq!(Some("did you mean `version`?".to_string()), json(input));
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn json_several_close_matches() {
+    super::init();
+
+    let input = r#"{"name": "x", "servers": [{"versoe": 1}]}"#;
+
+    assert_eq!(
+        Some("did you mean one of `verbose`, `version`?".to_string()),
+        json(input)
+    );
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn json_nested_variant() {
+    super::init();
+
+    let input = r#"{"name": "x", "servers": [{"version": 1, "environment": "stagign"}]}"#;
+
+    assert_eq!(Some("did you mean `staging`?".to_string()), json(input));
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn json_no_close_match() {
+    super::init();
+
+    let input = r#"{"name": "x", "servers": [{"version": 1, "environment": "prod"}]}"#;
+
+    assert_eq!(
+        Some("valid variants here are `production`, `staging`, `development`".to_string()),
+        json(input)
+    );
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn json_other_error() {
+    super::init();
+
+    assert_eq!(None, json(r#"{"name": "x", "servers": 1}"#));
+}
+
+#[cfg(feature = "serde_yaml")]
+#[test]
+fn yaml_nested_field() {
+    super::init();
+
+    let input = "name: x\nservers:\n  - version: 1\n    environmnet: staging\n";
+
+    assert_eq!(Some("did you mean `environment`?".to_string()), yaml(input));
+}
+
+#[cfg(feature = "serde_yaml")]
+#[test]
+fn yaml_top_level_field() {
+    super::init();
+
+    assert_eq!(
+        Some("did you mean `servers`?".to_string()),
+        yaml("name: x\nserver: []\n")
+    );
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn toml_array_of_tables_variant() {
+    super::init();
+
+    let input = "name = \"x\"\n\n[[servers]]\nversion = 1\nenvironment = \"developmnet\"\n";
+    let err = toml::from_str::<Config>(input).expect_err("input should not parse");
+
+    assert_eq!(
+        Some("did you mean `development`?".to_string()),
+        help(SerdeError::new(input.to_string(), err))
+    );
+}
@@ -0,0 +1,21 @@
+use pretty_assertions::assert_eq;
+
+use crate::SerdeError;
+
+#[test]
+fn from_custom_crosses_threads() {
+    super::init();
+
+    let error: Box<dyn std::error::Error + Send + Sync> = "invalid port".into();
+    let err = SerdeError::from_custom("port: x".to_string(), error, Some(1), Some(6));
+
+    let rendered = std::thread::spawn(move || err.to_string())
+        .join()
+        .expect("thread should not panic");
+
+    let mut expected = String::new();
+    expected.push_str(" port: x\n");
+    expected.push_str("       ^ invalid port\n");
+
+    assert_eq!(expected, rendered);
+}
@@ -0,0 +1,164 @@
+use pretty_assertions::assert_eq;
+
+use super::SerdeError;
+
+#[cfg(any(feature = "serde_json", feature = "serde_yaml"))]
+#[derive(Debug, serde::Deserialize)]
+#[allow(dead_code)]
+struct Matrix {
+    matrix: Vec<[u32; 3]>,
+}
+
+#[cfg(feature = "serde_json")]
+fn json(input: &str) -> SerdeError {
+    let err = serde_json::from_str::<Matrix>(input).expect_err("input should not parse");
+    SerdeError::new(input.to_string(), err)
+}
+
+#[cfg(feature = "serde_yaml")]
+fn yaml(input: &str) -> SerdeError {
+    let err = serde_yaml::from_str::<Matrix>(input).expect_err("input should not parse");
+    SerdeError::new(input.to_string(), err)
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn json_single_line() {
+    super::init();
+
+    let input = r#"{"matrix": [[1, 2, 3], [4, 5]]}"#;
+
+    let mut expected = String::new();
+    expected.push_str(r#" {"matrix": [[1, 2, 3], [4, 5]]}"#);
+    expected.push('\n');
+    expected.push_str(
+        "                        ^^^^^^ invalid length 2, expected an array of length 3 at line 1 \
+         column 29\n",
+    );
+
+    assert_eq!(
+        expected,
+        json(input).set_underline_sequence(true).to_string()
+    );
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn json_multiple_lines() {
+    super::init();
+
+    let input = "{\"matrix\": [\n  [1, 2, 3],\n  [\n    4,\n    5\n  ]\n]}";
+
+    let mut expected = String::from("\n");
+    expected.push_str("   |   [\n");
+    expected.push_str("   |   ^\n");
+    expected.push_str("   |     4,\n");
+    expected.push_str("   |     ^^\n");
+    expected.push_str("   |     5\n");
+    expected.push_str("   |     ^\n");
+    expected.push_str(" 6 |   ]\n");
+    expected
+        .push_str("   |   ^ invalid length 2, expected an array of length 3 at line 6 column 3\n");
+    expected.push_str("   | ]}\n");
+
+    assert_eq!(
+        expected,
+        json(input).set_underline_sequence(true).to_string()
+    );
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn json_outside_of_context() {
+    super::init();
+
+    let input = "{\"matrix\": [\n  [1, 2, 3],\n  [\n    4,\n    5\n  ]\n]}";
+
+    let mut expected = String::from("\n");
+    expected.push_str("   |     5\n");
+    expected.push_str("   |     ^\n");
+    expected.push_str(" 6 |   ]\n");
+    expected
+        .push_str("   |   ^ invalid length 2, expected an array of length 3 at line 6 column 3\n");
+    expected.push_str("   | ]}\n");
+    expected.push_str("   = note: the array spans lines 3–6\n");
+
+    assert_eq!(
+        expected,
+        json(input)
+            .set_underline_sequence(true)
+            .set_context_lines(1)
+            .to_string()
+    );
+}
+
+#[cfg(feature = "serde_yaml")]
+#[test]
+fn yaml_flow_sequence() {
+    super::init();
+
+    let input = "matrix:\n  - [1, 2, 3]\n  - [4, 5]\n";
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | matrix:\n");
+    expected.push_str("   |   - [1, 2, 3]\n");
+    expected.push_str(" 3 |   - [4, 5]\n");
+    expected.push_str(
+        "   |     ^^^^^^ matrix[1]: invalid length 2, expected an array of length 3 at line 3 \
+         column 5\n",
+    );
+
+    assert_eq!(
+        expected,
+        yaml(input).set_underline_sequence(true).to_string()
+    );
+}
+
+#[cfg(feature = "serde_yaml")]
+#[test]
+fn yaml_block_sequence() {
+    super::init();
+
+    let input = "matrix:\n  - - 1\n    - 2\n    - 3\n    - 4\n  - [1, 2, 3]\n";
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | matrix:\n");
+    expected.push_str(" 2 |   - - 1\n");
+    expected.push_str(
+        "   |     ^^^ matrix[0]: invalid length 4, expected sequence of 3 elements at line 2 \
+         column 5\n",
+    );
+    expected.push_str("   |     - 2\n");
+    expected.push_str("   |     ^^^\n");
+    expected.push_str("   |     - 3\n");
+    expected.push_str("   |     ^^^\n");
+    expected.push_str("   |     - 4\n");
+    expected.push_str("   |     ^^^\n");
+    expected.push_str("   |   - [1, 2, 3]\n");
+
+    assert_eq!(
+        expected,
+        yaml(input).set_underline_sequence(true).to_string()
+    );
+}
+
+#[test]
+fn explicit_span() {
+    super::init();
+
+    let input = "ports = [80, 443, 80]";
+
+    let mut expected = String::new();
+    expected.push_str(" ports = [80, 443, 80]\n");
+    expected.push_str("         ^^^^^^^^^^^^^ ports must be unique\n");
+
+    assert_eq!(
+        expected,
+        SerdeError::new(
+            input.to_string(),
+            ("ports must be unique".into(), Some(1), Some(20))
+        )
+        .set_span_start(1, 8)
+        .to_string()
+    );
+}
@@ -0,0 +1,72 @@
+use pretty_assertions::assert_eq;
+
+use crate::{
+    HyperlinkScheme,
+    LongLineMode,
+    MaxMessageWidth,
+    RenderSettings,
+    SerdeError,
+};
+
+const CONFIG: &str = r#"
+context_lines = 1
+show_line_numbers = false
+long_line_mode = "wrap"
+hyperlink_scheme = "vs_code"
+max_message_width = { fixed = 40 }
+
+[labels]
+help = "Hilfe"
+"#;
+
+/// Settings are loaded from a config file with defaults for missing fields.
+#[test]
+fn from_toml() {
+    super::init();
+
+    let settings: RenderSettings = toml::from_str(CONFIG).expect("settings should be valid");
+
+    assert_eq!(1, settings.context_lines);
+    assert!(!settings.show_line_numbers);
+    assert_eq!(LongLineMode::Wrap, settings.long_line_mode);
+    assert_eq!(HyperlinkScheme::VsCode, settings.hyperlink_scheme);
+    assert_eq!(Some(MaxMessageWidth::Fixed(40)), settings.max_message_width);
+    assert_eq!("Hilfe", settings.labels.help);
+    assert_eq!("note", settings.labels.note);
+    assert_eq!(
+        RenderSettings::default().context_characters,
+        settings.context_characters
+    );
+
+    let err = SerdeError::with_config(
+        "a: 1\nb: 2\nc: 3\nd: 4".to_string(),
+        ("invalid".into(), Some(3), Some(3)),
+        settings,
+    );
+
+    let mut expected = String::from("\n");
+    expected.push_str("  | b: 2\n");
+    expected.push_str("  | c: 3\n");
+    expected.push_str("  |    ^ invalid\n");
+    expected.push_str("  | d: 4\n");
+
+    assert_eq!(expected, err.to_string());
+}
+
+/// Serialized settings are read back unchanged.
+#[test]
+fn round_trip() {
+    let settings = RenderSettings {
+        context_lines: 5,
+        max_message_width: Some(MaxMessageWidth::Terminal),
+        caret_anchor: Some(0.25),
+        highlight_term: Some("port".to_string()),
+        ..RenderSettings::default()
+    };
+
+    let serialized = serde_json::to_string(&settings).expect("settings should serialize");
+    let deserialized: RenderSettings =
+        serde_json::from_str(&serialized).expect("settings should deserialize");
+
+    assert_eq!(settings, deserialized);
+}
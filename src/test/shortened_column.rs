@@ -0,0 +1,70 @@
+use pretty_assertions::assert_eq;
+
+use crate::SerdeError;
+
+fn input() -> String {
+    format!("key: {}!", "x".repeat(1000))
+}
+
+#[test]
+fn column_in_front_of_message() {
+    super::init();
+
+    let mut err = SerdeError::new(input(), ("invalid type".into(), Some(1), Some(909)));
+    err.set_context_characters(5);
+
+    let mut expected = String::new();
+    expected.push_str(" ...xxxxxxxxxxx...\n");
+    expected.push_str("          ^ (column 910) invalid type\n");
+
+    assert_eq!(expected, err.to_string());
+    assert_eq!(Some(909), err.column());
+}
+
+/// The column is not repeated if the message already tells it.
+#[test]
+fn column_in_message() {
+    super::init();
+
+    let message = "invalid type at line 1 column 910";
+    let mut err = SerdeError::new(input(), (message.into(), Some(1), Some(909)));
+    err.set_context_characters(5);
+
+    let mut expected = String::new();
+    expected.push_str(" ...xxxxxxxxxxx...\n");
+    expected.push_str("          ^ ");
+    expected.push_str(message);
+    expected.push('\n');
+
+    assert_eq!(expected, err.to_string());
+}
+
+#[test]
+fn show_column_number() {
+    super::init();
+
+    let mut err = SerdeError::new(input(), ("invalid type".into(), Some(1), Some(909)));
+    err.set_context_characters(5).set_show_column_number(true);
+
+    let mut expected = String::new();
+    expected.push_str(" ...xxxxxxxxxxx...\n");
+    expected.push_str("          ^ (column 910) invalid type\n");
+
+    assert_eq!(expected, err.to_string());
+}
+
+/// The caret is still at the column of the error if only the end of the line
+/// is cut off.
+#[test]
+fn shortened_after() {
+    super::init();
+
+    let mut err = SerdeError::new(input(), ("invalid type".into(), Some(1), Some(5)));
+    err.set_context_characters(5);
+
+    let mut expected = String::new();
+    expected.push_str(" key: xxxxxx...\n");
+    expected.push_str("      ^ invalid type\n");
+
+    assert_eq!(expected, err.to_string());
+}
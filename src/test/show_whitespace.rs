@@ -0,0 +1,57 @@
+use pretty_assertions::assert_eq;
+
+use crate::SerdeError;
+
+/// A non-breaking space in the indentation is shown as its code point and the
+/// caret still points at the value.
+#[test]
+fn non_breaking_space() {
+    super::init();
+
+    let input = "server:\n  \u{a0} port: x\n";
+    let mut err = SerdeError::new(
+        input.to_string(),
+        ("invalid type".into(), Some(2), Some(10)),
+    );
+    err.set_context_lines(1).set_show_whitespace(true);
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | server:\n");
+    expected.push_str(" 2 | ··⟨U+00A0⟩·port:·x\n");
+    expected.push_str("   |                  ^ invalid type\n");
+
+    assert_eq!(expected, err.to_string());
+}
+
+/// Tabs are shown as arrows filled up to the tab width.
+#[test]
+fn tabs() {
+    super::init();
+
+    let input = "\tport: x ";
+    let mut err = SerdeError::new(input.to_string(), ("invalid type".into(), Some(1), Some(7)));
+    err.set_tab_width(4).set_show_whitespace(true);
+
+    let mut expected = String::new();
+    expected.push_str(" →   port:·x·\n");
+    expected.push_str("           ^ invalid type\n");
+
+    assert_eq!(expected, err.to_string());
+}
+
+/// Without a column the line is underlined from the first character that is
+/// not whitespace.
+#[test]
+fn without_column() {
+    super::init();
+
+    let input = "  port: x";
+    let mut err = SerdeError::new(input.to_string(), ("invalid type".into(), Some(1), None));
+    err.set_show_whitespace(true);
+
+    let mut expected = String::new();
+    expected.push_str(" ··port:·x\n");
+    expected.push_str("   ^^^^^^^ invalid type\n");
+
+    assert_eq!(expected, err.to_string());
+}
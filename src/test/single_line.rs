@@ -0,0 +1,56 @@
+use pretty_assertions::assert_eq;
+
+use crate::SerdeError;
+
+fn error(input: &str) -> SerdeError {
+    let err = serde_json::from_str::<std::collections::HashMap<String, u16>>(input)
+        .expect_err("port should not be a number");
+
+    SerdeError::new(input.to_string(), err)
+}
+
+/// A single line is shown without the empty line in front and the gutter.
+#[test]
+fn unframed() {
+    super::init();
+
+    let mut expected = String::new();
+    expected.push_str(" {\"port\": \"x\"}\n");
+    expected.push_str("             ^ ");
+    expected.push_str("invalid type: string \"x\", expected u16 at line 1 column 12\n");
+
+    assert_eq!(expected, error(r#"{"port": "x"}"#).to_string());
+}
+
+/// Long single lines are still shortened around the error.
+#[test]
+fn shortened() {
+    super::init();
+
+    let keys = "\"workers\": 4, ".repeat(20);
+    let input = format!(r#"{{{keys}"port": "x"}}"#);
+    let mut err = error(&input);
+    err.set_context_characters(10);
+
+    let mut expected = String::new();
+    expected.push_str(" ...\"port\": \"x\"}\n");
+    expected.push_str("               ^ ");
+    expected.push_str("invalid type: string \"x\", expected u16 at line 1 column 292\n");
+
+    assert_eq!(expected, err.to_string());
+}
+
+#[test]
+fn framed() {
+    super::init();
+
+    let mut err = error(r#"{"port": "x"}"#);
+    err.set_frame_single_line(true);
+
+    let mut expected = String::from("\n");
+    expected.push_str(" 1 | {\"port\": \"x\"}\n");
+    expected.push_str("   |             ^ ");
+    expected.push_str("invalid type: string \"x\", expected u16 at line 1 column 12\n");
+
+    assert_eq!(expected, err.to_string());
+}
@@ -0,0 +1,53 @@
+use pretty_assertions::assert_eq;
+
+use crate::SerdeError;
+
+fn input() -> String {
+    (1..=12)
+        .map(|line| format!("key_{line}: {line}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Line numbers are aligned to the widest one and the render settings are
+/// ignored.
+#[test]
+fn around_error() {
+    super::init();
+
+    let mut err = SerdeError::new(input(), ("invalid value".into(), Some(9), Some(7)));
+    err.set_context_lines(0).set_contextualize(false);
+
+    let mut expected = String::new();
+    expected.push_str(" 7 | key_7: 7\n");
+    expected.push_str(" 8 | key_8: 8\n");
+    expected.push_str(" 9 | key_9: 9\n");
+    expected.push_str("10 | key_10: 10\n");
+    expected.push_str("11 | key_11: 11\n");
+
+    assert_eq!(expected, err.source_excerpt(2));
+}
+
+/// The excerpt stops at the end of the input and shows more lines after the
+/// error line at the start of it.
+#[test]
+fn at_edges() {
+    super::init();
+
+    let first = SerdeError::new(input(), ("invalid value".into(), Some(1), Some(7)));
+    assert_eq!(
+        "1 | key_1: 1\n2 | key_2: 2\n3 | key_3: 3\n",
+        first.source_excerpt(1)
+    );
+
+    let last = SerdeError::new(input(), ("invalid value".into(), Some(12), Some(8)));
+    assert_eq!("11 | key_11: 11\n12 | key_12: 12\n", last.source_excerpt(1));
+}
+
+#[test]
+fn without_line() {
+    super::init();
+
+    let err = SerdeError::new(input(), ("invalid value".into(), None, None));
+    assert_eq!("", err.source_excerpt(3));
+}
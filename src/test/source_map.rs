@@ -0,0 +1,48 @@
+use pretty_assertions::assert_eq;
+
+use super::SerdeError;
+
+const INPUT: &str = "included: true\nalso: included\nvalue: oops\nother: 1";
+
+fn error() -> SerdeError {
+    SerdeError::new(
+        INPUT.to_string(),
+        ("invalid value".into(), Some(3), Some(7)),
+    )
+}
+
+#[test]
+fn mapped_line() {
+    super::init();
+
+    let mut expected = String::from("\n");
+    expected.push_str("   --> templates/config.yaml.j2:10\n");
+    expected.push_str("    | included: true\n");
+    expected.push_str("    | also: included\n");
+    expected.push_str(" 10 | value: oops\n");
+    expected.push_str("    |        ^ invalid value\n");
+    expected.push_str("    | other: 1\n");
+
+    let got = error()
+        .set_source_map(|line| (line > 2).then(|| ("templates/config.yaml.j2".into(), line + 7)))
+        .to_string();
+
+    assert_eq!(expected, got);
+}
+
+#[test]
+fn generated_line() {
+    super::init();
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | included: true\n");
+    expected.push_str("   | also: included\n");
+    expected.push_str(" 3 | value: oops\n");
+    expected.push_str("   |        ^ invalid value\n");
+    expected.push_str("   | other: 1\n");
+    expected.push_str("   = note: line 3 is part of generated text that has no source\n");
+
+    let got = error().set_source_map(|_| None).to_string();
+
+    assert_eq!(expected, got);
+}
@@ -0,0 +1,73 @@
+use pretty_assertions::assert_eq;
+
+use crate::{
+    SerdeError,
+    Severity,
+};
+
+/// Large documents show the position with thousands separators.
+#[test]
+fn large_document() {
+    super::init();
+
+    let input = "key: value\n".repeat(1500);
+    let mut err = SerdeError::new(input, ("invalid value".into(), Some(1200), Some(5)));
+    err.set_show_stats(true);
+
+    let got = err.to_string();
+
+    assert!(
+        got.starts_with("\nerror at line 1,200 of 1,500 (column 6), 16 KiB document\n"),
+        "{}",
+        got
+    );
+}
+
+/// The header is shown before the header of the source map.
+#[test]
+fn with_source_map() {
+    super::init();
+
+    let mut err = SerdeError::new(
+        "first: 1\nsecond: x".to_string(),
+        ("expected a number".into(), Some(2), None),
+    );
+    err.set_show_stats(true)
+        .set_context_lines(0)
+        .set_severity(Severity::Warning)
+        .set_source_map(|line| Some(("config.yaml.j2".into(), line + 10)));
+
+    let mut expected = String::from("\n");
+    expected.push_str("warning at line 2 of 2, 18 B document\n");
+    expected.push_str("   --> config.yaml.j2:12\n");
+    expected.push_str(" 12 | second: x\n");
+    expected.push_str("    | ^^^^^^^^^ expected a number\n");
+
+    assert_eq!(expected, err.to_string());
+}
+
+/// Without a position only the size is shown.
+#[test]
+fn without_position() {
+    super::init();
+
+    let mut err = SerdeError::new("x".repeat(3000), ("invalid input".into(), None, None));
+    err.set_show_stats(true);
+
+    assert_eq!("2.9 KiB document\ninvalid input\n", err.to_string());
+}
+
+#[test]
+fn total_lines() {
+    super::init();
+
+    let input = "name: test\nport: eighty\nhost: localhost\nuser: admin";
+    let mut err = SerdeError::new(input.to_string(), ("invalid type".into(), Some(2), Some(6)));
+    err.set_show_total_lines(true).set_context_lines(0);
+
+    let mut expected = String::from("\n");
+    expected.push_str(" 2 | port: eighty\n");
+    expected.push_str("   |       ^ invalid type (of 4 lines)\n");
+
+    assert_eq!(expected, err.to_string());
+}
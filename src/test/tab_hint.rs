@@ -0,0 +1,46 @@
+use pretty_assertions::assert_eq;
+
+use crate::SerdeError;
+
+const MESSAGE: &str = "found character that cannot start any token";
+
+fn error(input: &str, column: usize) -> SerdeError {
+    let mut err = SerdeError::new(input.to_string(), (MESSAGE.into(), Some(2), Some(column)));
+    err.set_context_lines(1);
+    err
+}
+
+/// A tab in the indentation of the error line is explained and marked.
+#[test]
+fn tab_indentation() {
+    super::init();
+
+    let mut err = error("server:\n  \tport: 8080\n", 3);
+    err.set_show_whitespace(true);
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | server:\n");
+    expected.push_str(" 2 | ··→port:·8080\n");
+    expected.push_str("   |    ^ found character that cannot start any token\n");
+    expected.push_str("   |   - tab\n");
+    expected.push_str(
+        "   = help: YAML does not allow tabs for indentation; replace the tab with spaces\n",
+    );
+
+    assert_eq!(expected, err.to_string());
+}
+
+/// The hint is not shown if the line has no tab or the hints are disabled.
+#[test]
+fn without_tab() {
+    super::init();
+
+    let without_tab = error("server:\n  port: @\n", 8).to_string();
+    assert!(!without_tab.contains("help:"), "{}", without_tab);
+
+    let mut disabled = error("server:\n\tport: 8080\n", 0);
+    disabled.set_hints(false);
+    let disabled = disabled.to_string();
+    assert!(!disabled.contains("help:"), "{}", disabled);
+    assert!(!disabled.contains("- tab"), "{}", disabled);
+}
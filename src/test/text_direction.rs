@@ -0,0 +1,42 @@
+use pretty_assertions::assert_eq;
+
+use crate::{
+    SerdeError,
+    TextDirection,
+};
+
+#[test]
+fn rtl() {
+    super::init();
+
+    let input = "name: test\ntimeout_seconds: \"eighty\"\nhost: localhost";
+    let mut err = SerdeError::new(
+        input.to_string(),
+        ("invalid type".into(), Some(2), Some(17)),
+    );
+    err.set_text_direction(TextDirection::Rtl);
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | name: test\n");
+    expected.push_str(" 2 | timeout_seconds: \"eighty\"\n");
+    expected.push_str("   |     invalid type ^\n");
+    expected.push_str("   | host: localhost\n");
+
+    assert_eq!(expected, err.to_string());
+}
+
+#[test]
+fn rtl_does_not_fit() {
+    super::init();
+
+    let input = "port: \"eighty\"";
+    let mut err = SerdeError::new(input.to_string(), ("invalid type".into(), Some(1), Some(6)));
+    err.set_text_direction(TextDirection::Rtl);
+
+    let mut expected = String::new();
+    expected.push_str(" port: \"eighty\"\n");
+    expected.push_str("       ^\n");
+    expected.push_str(" invalid type\n");
+
+    assert_eq!(expected, err.to_string());
+}
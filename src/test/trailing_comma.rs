@@ -0,0 +1,108 @@
+use pretty_assertions::assert_eq;
+
+use crate::SerdeError;
+
+const HELP: &str = "= help: JSON does not allow a comma after the last element; remove it\n";
+
+fn render(input: &str) -> String {
+    let err = serde_json::from_str::<serde_json::Value>(input)
+        .expect_err("input should have a trailing comma");
+
+    let mut err = SerdeError::new(input.to_string(), err);
+    err.set_context_lines(1);
+    err.to_string()
+}
+
+#[test]
+fn array() {
+    super::init();
+
+    let mut expected = String::new();
+    expected.push_str(" [1, 2,]\n");
+    expected.push_str("      ^ trailing comma at line 1 column 7\n");
+    expected.push_str("  ");
+    expected.push_str(HELP);
+
+    assert_eq!(expected, render("[1, 2,]"));
+}
+
+#[test]
+fn object() {
+    super::init();
+
+    let mut expected = String::new();
+    expected.push_str(" {\"a\": 1,}\n");
+    expected.push_str("        ^ trailing comma at line 1 column 9\n");
+    expected.push_str("  ");
+    expected.push_str(HELP);
+
+    assert_eq!(expected, render("{\"a\": 1,}"));
+}
+
+#[test]
+fn array_pretty() {
+    super::init();
+
+    let mut expected = String::from("\n");
+    expected.push_str("   |   1,\n");
+    expected.push_str(" 3 |   2,\n");
+    expected.push_str("   |    ^ trailing comma at line 4 column 1\n");
+    expected.push_str("   | ]\n");
+    expected.push_str("   ");
+    expected.push_str(HELP);
+
+    assert_eq!(expected, render("[\n  1,\n  2,\n]"));
+}
+
+/// Empty lines between the comma and the closing brace are skipped.
+#[test]
+fn object_pretty() {
+    super::init();
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | {\n");
+    expected.push_str(" 2 |   \"a\": 1,\n");
+    expected.push_str("   |         ^ trailing comma at line 4 column 1\n");
+    expected.push_str("   | \n");
+    expected.push_str("   ");
+    expected.push_str(HELP);
+
+    assert_eq!(expected, render("{\n  \"a\": 1,\n\n}"));
+}
+
+/// The location is kept and no hint is shown with the hints disabled.
+#[test]
+fn disabled() {
+    super::init();
+
+    let input = "[1, 2,]";
+    let err = serde_json::from_str::<serde_json::Value>(input)
+        .expect_err("input should have a trailing comma");
+
+    let mut err = SerdeError::new(input.to_string(), err);
+    err.set_hints(false);
+
+    let mut expected = String::new();
+    expected.push_str(" [1, 2,]\n");
+    expected.push_str("        ^ trailing comma at line 1 column 7\n");
+
+    assert_eq!(expected, err.to_string());
+}
+
+/// The hint is specific to JSON so other errors about trailing commas are left
+/// unchanged.
+#[test]
+fn custom() {
+    super::init();
+
+    let err = SerdeError::new(
+        "[1, 2,]".to_string(),
+        ("trailing comma".into(), Some(1), Some(6)),
+    );
+
+    let mut expected = String::new();
+    expected.push_str(" [1, 2,]\n");
+    expected.push_str("       ^ trailing comma\n");
+
+    assert_eq!(expected, err.to_string());
+}
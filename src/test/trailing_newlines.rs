@@ -0,0 +1,63 @@
+use pretty_assertions::assert_eq;
+
+use crate::SerdeError;
+
+/// Blank lines at the end of the input are shown as context if they are part
+/// of the window after the error and no line is added behind the input.
+#[test]
+fn context_after_error() {
+    super::init();
+
+    for (input, blank_lines) in [
+        ("a\nx", 0),
+        ("a\nx\n", 0),
+        ("a\nx\n\n", 1),
+        ("a\nx\n\n\n", 2),
+    ] {
+        let err = SerdeError::new(input.to_string(), ("invalid".into(), Some(2), Some(0)));
+
+        let mut expected = String::from("\n");
+        expected.push_str("   | a\n");
+        expected.push_str(" 2 | x\n");
+        expected.push_str("   | ^ invalid\n");
+        expected.push_str(&"   | \n".repeat(blank_lines));
+
+        assert_eq!(expected, err.to_string(), "{:?}", input);
+    }
+}
+
+/// Blank lines behind the window are not shown.
+#[test]
+fn context_lines_limit() {
+    super::init();
+
+    let mut err = SerdeError::new(
+        "a\nx\n\n\n\n".to_string(),
+        ("invalid".into(), Some(2), Some(0)),
+    );
+    err.set_context_lines(1);
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | a\n");
+    expected.push_str(" 2 | x\n");
+    expected.push_str("   | ^ invalid\n");
+    expected.push_str("   | \n");
+
+    assert_eq!(expected, err.to_string());
+}
+
+/// Errors on the line behind a final line break are shown behind the last
+/// line instead of being dropped.
+#[test]
+fn behind_last_line() {
+    super::init();
+
+    let err = SerdeError::new("a\nx\n".to_string(), ("invalid".into(), Some(3), Some(0)));
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | a\n");
+    expected.push_str(" 2 | x\n");
+    expected.push_str("   |  ^ invalid\n");
+
+    assert_eq!(expected, err.to_string());
+}
@@ -0,0 +1,63 @@
+use std::error::Error;
+
+use pretty_assertions::assert_eq;
+
+use crate::{
+    Category,
+    SerdeError,
+    SerdeErrorOf,
+};
+
+const INPUT: &str = r#"{
+  "name": "test",
+  "port": "eighty"
+}"#;
+
+#[derive(Debug, serde::Deserialize)]
+struct Config {
+    #[allow(dead_code)]
+    port: u16,
+}
+
+fn parse() -> serde_json::Error {
+    serde_json::from_str::<Config>(INPUT).expect_err("port is not a number")
+}
+
+#[test]
+fn renders_like_serde_error() {
+    super::init();
+
+    let typed = SerdeErrorOf::new(INPUT.to_string(), parse());
+    let untyped = SerdeError::new(INPUT.to_string(), parse());
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | {\n");
+    expected.push_str("   |   \"name\": \"test\",\n");
+    expected.push_str(" 3 |   \"port\": \"eighty\"\n");
+    expected.push_str(
+        "   |                   ^ invalid type: string \"eighty\", expected u16 at line 3 column \
+         18\n",
+    );
+    expected.push_str("   | }\n");
+
+    assert_eq!(expected, typed.to_string());
+    assert_eq!(untyped.to_string(), typed.to_string());
+    assert_eq!(typed.category(), Some(Category::Data));
+}
+
+#[test]
+fn original_error() {
+    super::init();
+
+    let mut err = SerdeErrorOf::new(INPUT.to_string(), parse());
+    err.set_context_lines(0);
+
+    let source = err
+        .source()
+        .and_then(|source| source.downcast_ref::<serde_json::Error>())
+        .expect("source is the serde_json error");
+    assert!(source.is_data());
+
+    assert!(!err.to_string().contains("\"name\""));
+    assert!(err.into_inner().is_data());
+}
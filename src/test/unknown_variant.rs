@@ -0,0 +1,89 @@
+use pretty_assertions::assert_eq;
+use serde::Deserialize;
+
+use crate::SerdeError;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Environment {
+    Production,
+    Staging,
+    Development,
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct Config {
+    env: Environment,
+}
+
+fn render(env: &str) -> String {
+    let input = format!("{{\n  \"env\": \"{env}\"\n}}");
+    let err = serde_json::from_str::<Config>(&input).expect_err("variant should be unknown");
+
+    SerdeError::new(input, err).to_string()
+}
+
+/// The variant starting with the unknown one is suggested.
+#[test]
+fn prefix() {
+    super::init();
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | {\n");
+    expected.push_str(" 2 |   \"env\": \"prod\"\n");
+    expected.push_str(
+        "   |           ^^^^ unknown variant `prod`, expected one of `production`, `staging`, \
+         `development` at line 2 column 15\n",
+    );
+    expected.push_str("   | }\n");
+    expected.push_str("   = help: did you mean `production`?\n");
+
+    assert_eq!(expected, render("prod"));
+}
+
+#[test]
+fn typo() {
+    super::init();
+
+    let rendered = render("stagign");
+    assert!(
+        rendered.ends_with("= help: did you mean `staging`?\n"),
+        "{}",
+        rendered
+    );
+}
+
+/// Nothing is suggested if no variant is close.
+#[test]
+fn unrelated() {
+    super::init();
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | {\n");
+    expected.push_str(" 2 |   \"env\": \"qa\"\n");
+    expected.push_str(
+        "   |           ^^ unknown variant `qa`, expected one of `production`, `staging`, \
+         `development` at line 2 column 13\n",
+    );
+    expected.push_str("   | }\n");
+
+    assert_eq!(expected, render("qa"));
+}
+
+/// Variants that are equally close are not suggested.
+#[test]
+fn ambiguous() {
+    super::init();
+
+    let err = SerdeError::new(
+        "mode: bat\n".to_string(),
+        (
+            "unknown variant `bat`, expected `bar` or `baz`".into(),
+            Some(1),
+            Some(6),
+        ),
+    );
+
+    assert!(!err.to_string().contains("help:"), "{}", err);
+}
@@ -0,0 +1,86 @@
+use pretty_assertions::assert_eq;
+
+use super::SerdeError;
+
+const MESSAGE: &str = "invalid type: map, expected a string";
+
+fn note(input: &str, line: usize, column: usize) -> Option<String> {
+    let got = SerdeError::new(
+        input.to_string(),
+        (MESSAGE.into(), Some(line), Some(column)),
+    )
+    .set_show_value_preview(true)
+    .to_string();
+
+    got.lines()
+        .find_map(|line| line.trim().strip_prefix("= note: "))
+        .map(ToString::to_string)
+}
+
+#[test]
+fn map_at_start() {
+    super::init();
+
+    assert_eq!(
+        Some("the value here is `{invalid: dont}`".to_string()),
+        note("values: {invalid: dont}", 1, 8)
+    );
+}
+
+#[test]
+fn multi_line_map() {
+    super::init();
+
+    let input = "[\n  \"first\",\n  {\n    \"invalid\": \"dont\"\n  }\n]";
+
+    assert_eq!(
+        Some(r#"the value here is `{ "invalid": "dont" }`"#.to_string()),
+        note(input, 3, 2)
+    );
+}
+
+#[test]
+fn after_scalar() {
+    super::init();
+
+    assert_eq!(
+        Some("the value here is `3`".to_string()),
+        note(r#"{"values" :  3}"#, 1, 14)
+    );
+    assert_eq!(
+        Some(r#"the value here is `"x"`"#.to_string()),
+        note(r#"{"values": "x"}"#, 1, 14)
+    );
+}
+
+#[test]
+fn yaml_block_mapping() {
+    super::init();
+
+    assert_eq!(
+        Some("the value here is `invalid: 'dont'`".to_string()),
+        note("values:\n  - invalid: 'dont'", 2, 11)
+    );
+}
+
+#[test]
+fn long_value_is_shortened() {
+    super::init();
+
+    let input = format!("values: [{}]", "1, ".repeat(30));
+
+    assert_eq!(
+        Some(format!("the value here is `[{}...`", "1, ".repeat(12))),
+        note(&input, 1, 8)
+    );
+}
+
+#[test]
+fn malformed_input() {
+    super::init();
+
+    assert_eq!(None, note("values: {invalid: dont", 1, 8));
+    assert_eq!(None, note("values: \"unterminated", 1, 8));
+    assert_eq!(None, note("values:", 1, 99));
+    assert_eq!(None, note("values:", 5, 0));
+}
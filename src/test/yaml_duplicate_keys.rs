@@ -0,0 +1,85 @@
+use pretty_assertions::assert_eq;
+use serde::Deserialize;
+
+use crate::SerdeError;
+
+const HELP: &str = "= help: later occurrences override earlier ones in some parsers; remove one\n";
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct Server {
+    host: String,
+    timeout: u16,
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(dead_code)]
+struct Config {
+    server: Server,
+}
+
+fn error(input: &str) -> SerdeError {
+    let err = serde_yaml::from_str::<Config>(input).expect_err("input should have a duplicate key");
+
+    SerdeError::new(input.to_string(), err)
+}
+
+/// The first definition is far outside of the context and shown on its own.
+#[test]
+fn far_apart() {
+    super::init();
+
+    let mut expected = String::from("\n");
+    expected.push_str("    | timeout: 30\n");
+    expected.push_str("    | - first defined here\n");
+    expected.push_str("  ...\n");
+    expected.push_str("    | option_37: 37\n");
+    expected.push_str("    | option_38: 38\n");
+    expected.push_str("    | option_39: 39\n");
+    expected.push_str(" 40 | timeout: 60\n");
+    expected.push_str("    | ^ server: duplicate field `timeout` at line 2 column 7\n");
+    expected.push_str("    | tls: false\n");
+    expected.push_str("    ");
+    expected.push_str(HELP);
+
+    assert_eq!(
+        expected,
+        error(include_str!("../../resources/duplicate_key.yaml")).to_string()
+    );
+}
+
+/// Duplicates of the same key in other mappings are not mistaken for the one
+/// the error is about.
+#[test]
+fn other_mapping() {
+    super::init();
+
+    let input = "ignored:\n  host: a\n  host: b\nserver:\n  host: c\n  timeout: 1\n  host: d\n";
+    let mut err = error(input);
+    err.set_context_lines(1);
+
+    let mut expected = String::from("\n");
+    expected.push_str("   | host: c\n");
+    expected.push_str("   | - first defined here\n");
+    expected.push_str("   | timeout: 1\n");
+    expected.push_str(" 7 | host: d\n");
+    expected.push_str("   | ^ server: duplicate field `host` at line 5 column 7\n");
+    expected.push_str("   ");
+    expected.push_str(HELP);
+
+    assert_eq!(expected, err.to_string());
+}
+
+/// Without the hints the error is shown where the parser reported it.
+#[test]
+fn disabled() {
+    super::init();
+
+    let input = "server:\n  host: a\n  timeout: 1\n  host: b\n";
+    let mut err = error(input);
+    err.set_hints(false);
+
+    let rendered = err.to_string();
+    assert!(!rendered.contains("first defined here"), "{}", rendered);
+    assert!(!rendered.contains("help:"), "{}", rendered);
+}
@@ -0,0 +1,97 @@
+use pretty_assertions::assert_eq;
+
+use crate::SerdeError;
+
+/// The country code of Norway is read as a boolean.
+#[test]
+fn norway() {
+    super::init();
+
+    let input = "country: NO\n";
+    let err = SerdeError::new(
+        input.to_string(),
+        (
+            "invalid type: boolean `false`, expected a string".into(),
+            Some(1),
+            Some(9),
+        ),
+    );
+
+    let mut expected = String::new();
+    expected.push_str(" country: NO\n");
+    expected.push_str("          ^^ invalid type: boolean `false`, expected a string\n");
+    expected.push_str("  = help: unquoted `NO` is parsed as a boolean in YAML\n");
+    expected.push_str("  = help: quote the value to keep it a string: `country: \"NO\"`\n");
+
+    assert_eq!(expected, err.to_string());
+}
+
+/// Zero padded numbers are read as octal numbers.
+#[test]
+fn octal() {
+    super::init();
+
+    let input = "mode: 012 # owner only\n";
+    let err = SerdeError::new(
+        input.to_string(),
+        (
+            "invalid type: integer `10`, expected a string".into(),
+            Some(1),
+            Some(6),
+        ),
+    );
+
+    assert!(err.to_string().ends_with(
+        "  = help: unquoted `012` is parsed as an octal number in YAML\n  = help: quote the value \
+         to keep it a string: `mode: \"012\" # owner only`\n"
+    ));
+}
+
+/// Version strings are read as numbers.
+#[test]
+fn version() {
+    super::init();
+
+    let input = "version: 1.10";
+    let err = SerdeError::new(
+        input.to_string(),
+        (
+            "invalid type: floating point `1.1`, expected a string".into(),
+            Some(1),
+            Some(9),
+        ),
+    );
+
+    assert!(err.to_string().ends_with(
+        "  = help: unquoted `1.10` is parsed as a number in YAML\n  = help: quote the value to \
+         keep it a string: `version: \"1.10\"`\n"
+    ));
+}
+
+/// No hint about YAML 1.1 is shown for quoted values, other values or with
+/// the hints disabled.
+#[test]
+fn no_hint() {
+    super::init();
+
+    let render = |input: &str, hints: bool| {
+        let mut err = SerdeError::new(
+            input.to_string(),
+            (
+                "invalid type: boolean `false`, expected a string".into(),
+                Some(1),
+                Some(9),
+            ),
+        );
+        err.set_hints(hints);
+        err.to_string()
+    };
+
+    for rendered in [
+        render("country: \"NO\"", true),
+        render("country: false", true),
+        render("country: NO", false),
+    ] {
+        assert!(!rendered.contains("is parsed as"), "{}", rendered);
+    }
+}
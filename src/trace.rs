@@ -0,0 +1,144 @@
+//! Decisions made while rendering the error line. See
+//! [`SerdeError::render_debug`](crate::SerdeError::render_debug).
+
+use std::fmt;
+
+use crate::RenderedErrorLine;
+
+/// How the error line was shown.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum Layout {
+    /// The error line was not rendered, for example because the error has no
+    /// location.
+    #[default]
+    MessageOnly,
+    /// The error line was shown completely.
+    Complete,
+    /// Only the part of the error line around the error was shown.
+    Shortened,
+    /// The error line was shortened to multiple windows around the error and
+    /// the labels.
+    Windows,
+    /// The error line was wrapped into multiple rows.
+    Wrapped,
+    /// The message was shown behind the error line.
+    Inline,
+}
+
+impl fmt::Display for Layout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::MessageOnly => "message only",
+            Self::Complete => "complete",
+            Self::Shortened => "shortened",
+            Self::Windows => "windows",
+            Self::Wrapped => "wrapped",
+            Self::Inline => "inline",
+        })
+    }
+}
+
+/// Values computed while rendering the error line. Columns are in characters
+/// starting at 0 and relative to the line after tabs have been expanded.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RenderTrace {
+    pub(crate) layout: Layout,
+    pub(crate) error_line: usize,
+    pub(crate) error_column: usize,
+    pub(crate) whitespace_count: usize,
+    /// Length of the dedented error line in bytes.
+    pub(crate) line_length: usize,
+    /// Lines that are longer than this are shortened.
+    pub(crate) long_line_threshold: usize,
+    /// Units skipped in front of the shown part of the dedented error line.
+    pub(crate) skip: usize,
+    /// Units taken for the shown part of the error line.
+    pub(crate) take: usize,
+    pub(crate) context_before: bool,
+    pub(crate) context_after: bool,
+    /// Column the caret is printed at behind the separator.
+    pub(crate) caret_column: usize,
+}
+
+impl RenderTrace {
+    /// Start the trace of an error line that is shown completely until other
+    /// decisions are recorded.
+    pub(crate) fn new(error_line: usize, error_column: usize, whitespace_count: usize) -> Self {
+        Self {
+            layout: Layout::Complete,
+            error_line,
+            error_column,
+            whitespace_count,
+            ..Self::default()
+        }
+    }
+}
+
+/// Collects the decisions of a single render. The decisions are only
+/// recorded if they are asked for, normal renders pass [`Tracer::off`] and
+/// only keep the shown error line.
+#[derive(Debug)]
+pub(crate) struct Tracer {
+    trace: Option<RenderTrace>,
+    shown: Option<RenderedErrorLine>,
+}
+
+impl Tracer {
+    pub(crate) fn off() -> Self {
+        Self {
+            trace: None,
+            shown: None,
+        }
+    }
+
+    pub(crate) fn on() -> Self {
+        Self {
+            trace: Some(RenderTrace::default()),
+            shown: None,
+        }
+    }
+
+    /// Update the recorded decisions if they are collected.
+    pub(crate) fn record(&mut self, update: impl FnOnce(&mut RenderTrace)) {
+        if let Some(trace) = &mut self.trace {
+            update(trace);
+        }
+    }
+
+    /// Remember the shown part of the error line. See
+    /// [`SerdeError::last_rendered_error_line`](crate::SerdeError::last_rendered_error_line).
+    pub(crate) fn record_shown(&mut self, shown: RenderedErrorLine) {
+        self.shown = Some(shown);
+    }
+
+    /// Take the shown part of the error line out of the tracer.
+    pub(crate) fn take_shown(&mut self) -> Option<RenderedErrorLine> {
+        self.shown.take()
+    }
+
+    /// Decisions recorded during the render.
+    pub(crate) fn finish(self) -> RenderTrace {
+        self.trace.unwrap_or_default()
+    }
+}
+
+impl fmt::Display for RenderTrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "layout: {}", self.layout)?;
+
+        if self.layout == Layout::MessageOnly {
+            return Ok(());
+        }
+
+        writeln!(f, "error_line: {}", self.error_line)?;
+        writeln!(f, "error_column: {}", self.error_column)?;
+        writeln!(f, "whitespace_count: {}", self.whitespace_count)?;
+        writeln!(f, "line_length: {}", self.line_length)?;
+        writeln!(f, "long_line_threshold: {}", self.long_line_threshold)?;
+        writeln!(f, "skip: {}", self.skip)?;
+        writeln!(f, "take: {}", self.take)?;
+        writeln!(f, "context_before: {}", self.context_before)?;
+        writeln!(f, "context_after: {}", self.context_after)?;
+        writeln!(f, "caret_column: {}", self.caret_column)
+    }
+}
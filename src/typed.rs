@@ -0,0 +1,202 @@
+//! Wrapper that keeps the concrete type of the original error. See
+//! [`SerdeErrorOf`].
+
+use std::{
+    fmt,
+    ops::{
+        Deref,
+        DerefMut,
+    },
+};
+
+use crate::{
+    Category,
+    ErrorAdapter,
+    RenderSettings,
+    SerdeError,
+};
+
+/// Errors that know where in the input they occurred. Implemented for the
+/// errors of the supported formats so they can be wrapped in a
+/// [`SerdeErrorOf`]. Implement it for your own errors to render them the same
+/// way.
+pub trait ErrorLocation: fmt::Display {
+    /// Line starting at 1 and column starting at 0 the error occurred at if
+    /// they are known.
+    fn location(&self) -> (Option<usize>, Option<usize>);
+
+    /// Category of the error if the format provides one. Not known by default.
+    fn category(&self) -> Option<Category> {
+        None
+    }
+
+    /// Name of the format like `json` used as the language of code blocks.
+    /// Not known by default.
+    fn language(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Adapter that produced the error, [`ErrorAdapter::Custom`] by default.
+    fn adapter(&self) -> ErrorAdapter {
+        ErrorAdapter::Custom
+    }
+}
+
+#[cfg(feature = "serde_json")]
+impl ErrorLocation for serde_json::Error {
+    fn location(&self) -> (Option<usize>, Option<usize>) {
+        (Some(self.line()), Some(self.column()))
+    }
+
+    fn category(&self) -> Option<Category> {
+        Some(Category::from(self.classify()))
+    }
+
+    fn language(&self) -> Option<&'static str> {
+        Some("json")
+    }
+
+    fn adapter(&self) -> ErrorAdapter {
+        ErrorAdapter::Json
+    }
+}
+
+#[cfg(feature = "serde_yaml")]
+impl ErrorLocation for serde_yaml::Error {
+    fn location(&self) -> (Option<usize>, Option<usize>) {
+        match self.location() {
+            // Don't set line/column if we don't have a location
+            None => (None, None),
+
+            // serde_yaml columns start at 1. A column of 0 can't be mapped so
+            // only the line is used in that case.
+            Some(location) => (Some(location.line()), location.column().checked_sub(1)),
+        }
+    }
+
+    fn language(&self) -> Option<&'static str> {
+        Some("yaml")
+    }
+
+    fn adapter(&self) -> ErrorAdapter {
+        ErrorAdapter::Yaml
+    }
+}
+
+#[cfg(feature = "toml")]
+impl ErrorLocation for toml::de::Error {
+    fn location(&self) -> (Option<usize>, Option<usize>) {
+        match self.line_col() {
+            // Don't set line/column if we do not have the values
+            None => (None, None),
+
+            Some((line, column)) => (Some(line + 1), Some(column)),
+        }
+    }
+
+    fn language(&self) -> Option<&'static str> {
+        Some("toml")
+    }
+
+    fn adapter(&self) -> ErrorAdapter {
+        ErrorAdapter::Toml
+    }
+}
+
+/// [`SerdeError`] that keeps the original error with its concrete type. Use
+/// it in public APIs instead of [`SerdeError`] if callers need to inspect the
+/// original error. All methods of [`SerdeError`] are available through
+/// [`Deref`].
+///
+/// ```rust
+/// # #[cfg(feature = "serde_json")]
+/// # {
+/// use format_serde_error::SerdeErrorOf;
+///
+/// fn parse(input: &str) -> Result<u32, SerdeErrorOf<serde_json::Error>> {
+///     serde_json::from_str(input).map_err(|err| SerdeErrorOf::new(input.to_string(), err))
+/// }
+///
+/// let err = parse("\"one\"").unwrap_err();
+///
+/// assert!(err.to_string().contains("invalid type"));
+/// assert!(err.into_inner().is_data());
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct SerdeErrorOf<E> {
+    error: SerdeError,
+    inner: E,
+}
+
+impl<E: ErrorLocation> SerdeErrorOf<E> {
+    /// Create a new [`SerdeErrorOf`] that is rendered like
+    /// [`SerdeError::new`] using the current global defaults.
+    #[track_caller]
+    pub fn new(input: String, err: E) -> Self {
+        Self {
+            error: SerdeError::from_error(input, &err, RenderSettings::from_global_defaults()),
+            inner: err,
+        }
+    }
+
+    /// Create a new [`SerdeErrorOf`] that is rendered using the given settings
+    /// instead of the global defaults like [`SerdeError::with_config`].
+    #[track_caller]
+    pub fn with_config(input: String, err: E, settings: RenderSettings) -> Self {
+        Self {
+            error: SerdeError::from_error(input, &err, settings),
+            inner: err,
+        }
+    }
+}
+
+impl<E> SerdeErrorOf<E> {
+    /// Get the original error.
+    #[must_use]
+    pub fn inner(&self) -> &E {
+        &self.inner
+    }
+
+    /// Return the original error and drop the rendering information.
+    pub fn into_inner(self) -> E {
+        self.inner
+    }
+
+    /// Return the rendering information and drop the original error.
+    pub fn into_serde_error(self) -> SerdeError {
+        self.error
+    }
+}
+
+impl<E> Deref for SerdeErrorOf<E> {
+    type Target = SerdeError;
+
+    fn deref(&self) -> &Self::Target {
+        &self.error
+    }
+}
+
+impl<E> DerefMut for SerdeErrorOf<E> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.error
+    }
+}
+
+impl<E> fmt::Display for SerdeErrorOf<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.error.render(f)
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for SerdeErrorOf<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.inner)
+    }
+}
+
+impl<E> From<SerdeErrorOf<E>> for SerdeError {
+    fn from(err: SerdeErrorOf<E>) -> Self {
+        err.error
+    }
+}
@@ -0,0 +1,29 @@
+//! Changes the default message rewriter so it runs in its own process instead
+//! of together with the unit tests.
+#![cfg(feature = "serde_json")]
+
+use format_serde_error::{
+    remove_default_message_rewriter,
+    set_default_message_rewriter,
+    RenderSettings,
+    SerdeError,
+    SerdeErrorOf,
+};
+
+#[test]
+fn with_config_uses_default_message_rewriter() {
+    let input = r#"{"name": }"#;
+    let err = || serde_json::from_str::<serde_json::Value>(input).unwrap_err();
+
+    set_default_message_rewriter(|message| Some(format!("rewritten: {message}")));
+
+    let new = SerdeError::new(input.to_string(), err());
+    let with_config = SerdeError::with_config(input.to_string(), err(), RenderSettings::default());
+    let typed = SerdeErrorOf::with_config(input.to_string(), err(), RenderSettings::default());
+
+    remove_default_message_rewriter();
+
+    assert!(new.to_string().contains("rewritten: expected value"));
+    assert_eq!(new.to_string(), with_config.to_string());
+    assert_eq!(new.to_string(), typed.to_string());
+}
@@ -0,0 +1,76 @@
+//! Changes the global defaults so it runs in its own process instead of
+//! together with the unit tests.
+
+use std::panic;
+
+use format_serde_error::{
+    get_default_context_characters,
+    get_default_context_lines,
+    push_default_context_characters,
+    push_default_context_lines,
+    push_default_contextualize,
+    set_default_context_lines,
+    CONTEXT_CHARACTERS_DEFAULT,
+    CONTEXT_LINES_DEFAULT,
+};
+
+#[test]
+fn defaults_guard() {
+    assert_eq!(set_default_context_lines(5), CONTEXT_LINES_DEFAULT);
+    assert_eq!(set_default_context_lines(CONTEXT_LINES_DEFAULT), 5);
+
+    // Nested guards restore in reverse order
+    {
+        let _outer = push_default_context_lines(1);
+        let _characters = push_default_context_characters(10);
+        let _contextualize = push_default_contextualize(false);
+
+        {
+            let _inner = push_default_context_lines(2);
+            assert_eq!(get_default_context_lines(), 2);
+        }
+
+        assert_eq!(get_default_context_lines(), 1);
+        assert_eq!(get_default_context_characters(), 10);
+    }
+
+    assert_eq!(get_default_context_lines(), CONTEXT_LINES_DEFAULT);
+    assert_eq!(get_default_context_characters(), CONTEXT_CHARACTERS_DEFAULT);
+
+    // Panics unwind through the guard
+    let result = panic::catch_unwind(|| {
+        let _guard = push_default_context_lines(7);
+        panic!("restores anyway");
+    });
+
+    assert!(result.is_err());
+    assert_eq!(get_default_context_lines(), CONTEXT_LINES_DEFAULT);
+}
+
+#[cfg(feature = "colored")]
+#[test]
+fn coloring_mode_guard() {
+    use format_serde_error::{
+        push_coloring_mode,
+        set_coloring_mode,
+        ColoringMode,
+    };
+
+    assert_eq!(
+        set_coloring_mode(&ColoringMode::NeverColor),
+        ColoringMode::UseEnvironment
+    );
+
+    {
+        let _guard = push_coloring_mode(&ColoringMode::AlwaysColor);
+        assert_eq!(
+            set_coloring_mode(&ColoringMode::AlwaysColor),
+            ColoringMode::AlwaysColor
+        );
+    }
+
+    assert_eq!(
+        set_coloring_mode(&ColoringMode::UseEnvironment),
+        ColoringMode::NeverColor
+    );
+}
@@ -0,0 +1,62 @@
+//! Builds and tests the crate with every pair of the main features so cfg
+//! combinations that are not part of the other CI runs don't break unnoticed.
+//! Warnings are treated as errors. This takes a while so it only runs when
+//! asked for, the CI does so in its own step:
+//!
+//! ```text
+//! cargo test --test feature_matrix -- --ignored
+//! ```
+
+use std::process::Command;
+
+const FEATURES: [&str; 6] = [
+    "serde_yaml",
+    "serde_json",
+    "colored",
+    "graphemes_support",
+    "toml",
+    "schemars",
+];
+
+/// Every feature on its own and every pair of features.
+fn combinations() -> Vec<String> {
+    let mut combinations = FEATURES
+        .iter()
+        .map(|feature| (*feature).to_string())
+        .collect::<Vec<_>>();
+
+    for (index, first) in FEATURES.iter().enumerate() {
+        for second in FEATURES.iter().skip(index + 1) {
+            combinations.push(format!("{first},{second}"));
+        }
+    }
+
+    combinations
+}
+
+fn cargo(command: &str, features: &str) -> bool {
+    Command::new(env!("CARGO"))
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .args([command, "--lib", "--no-default-features", "--features", features])
+        // A separate target directory avoids waiting on the lock of the cargo
+        // invocation that runs this test
+        .args(["--target-dir", "target/feature_matrix"])
+        .env("RUSTFLAGS", "-D warnings")
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+#[test]
+#[ignore = "builds the crate once for every combination of features"]
+fn feature_matrix() {
+    let failed = combinations()
+        .into_iter()
+        .filter(|features| !cargo("build", features) || !cargo("test", features))
+        .collect::<Vec<_>>();
+
+    assert!(
+        failed.is_empty(),
+        "failed feature combinations: {:?}",
+        failed
+    );
+}
@@ -0,0 +1,41 @@
+//! Highlighting is only shown with colors so this changes the global coloring
+//! mode and runs in its own process instead of together with the unit tests.
+#![cfg(feature = "colored")]
+
+use format_serde_error::{
+    always_color,
+    SerdeError,
+};
+
+/// Every occurrence in the context and error lines is highlighted.
+#[test]
+fn highlight_term() {
+    always_color();
+
+    let input = "name: a\nport: 1\nname: b\n";
+    let mut err = SerdeError::new(
+        input.to_string(),
+        ("duplicate field `name`".into(), Some(3), Some(0)),
+    );
+    err.set_highlight_term("name");
+
+    let rendered = err.to_string();
+    let highlighted = "\u{1b}[4;35mname\u{1b}[0m";
+
+    assert_eq!(2, rendered.matches(highlighted).count(), "{}", rendered);
+    assert!(rendered.contains("\u{1b}[33m: a\u{1b}[0m"), "{}", rendered);
+}
+
+/// Occurrences that are cut off by shortening the line are not highlighted.
+#[test]
+fn cut_off() {
+    always_color();
+
+    let input = "values: [value, other, value]";
+    let mut err = SerdeError::new(input.to_string(), ("invalid".into(), Some(1), Some(17)));
+    err.set_highlight_term("value").set_context_characters(3);
+
+    let rendered = err.to_string();
+
+    assert!(!rendered.contains("\u{1b}[4;35m"), "{}", rendered);
+}
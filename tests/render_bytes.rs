@@ -0,0 +1,31 @@
+//! Changes the global coloring mode to check that it is not used so it runs
+//! in its own process instead of together with the unit tests.
+#![cfg(feature = "colored")]
+
+use format_serde_error::SerdeError;
+
+#[test]
+fn render_bytes() {
+    let err = SerdeError::new("a: b".to_string(), ("error".into(), Some(1), Some(3)));
+
+    let plain = err.render_bytes(false);
+    let colored = err.render_bytes(true);
+
+    assert!(!plain.contains(&0x1b));
+    assert!(colored.contains(&0x1b));
+    assert_eq!(b" a: b\n    ^ error\n".to_vec(), plain);
+}
+
+/// The global coloring mode is neither used nor changed.
+#[test]
+fn coloring_mode() {
+    let err = SerdeError::new("a: b".to_string(), ("error".into(), Some(1), Some(3)));
+
+    format_serde_error::never_color();
+    assert!(err.render_bytes(true).contains(&0x1b));
+    assert!(!err.to_string().contains('\x1b'));
+
+    format_serde_error::always_color();
+    assert!(!err.render_bytes(false).contains(&0x1b));
+    assert!(err.to_string().contains('\x1b'));
+}
@@ -0,0 +1,29 @@
+//! Changes the global coloring mode so it runs in its own process instead of
+//! together with the unit tests.
+#![cfg(all(feature = "testing", feature = "colored"))]
+
+use format_serde_error::{
+    always_color,
+    never_color,
+    SerdeError,
+};
+
+#[test]
+fn render_normalized() {
+    let err = SerdeError::new(
+        "name: test  \nport: eighty\n".to_string(),
+        ("invalid type".into(), Some(2), Some(6)),
+    );
+
+    always_color();
+    let colored = err.render_normalized();
+    assert!(err.to_string().contains('\u{1b}'));
+
+    never_color();
+    let plain = err.render_normalized();
+
+    assert_eq!(colored, plain);
+    assert!(!plain.contains('\u{1b}'));
+    assert!(plain.lines().all(|line| line == line.trim_end()));
+    assert!(plain.contains("   | name: test\n"));
+}
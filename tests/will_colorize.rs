@@ -0,0 +1,22 @@
+//! Changes the global coloring mode so it runs in its own process instead of
+//! together with the unit tests.
+#![cfg(feature = "colored")]
+
+use format_serde_error::{
+    always_color,
+    never_color,
+    SerdeError,
+};
+
+#[test]
+fn will_colorize() {
+    let err = SerdeError::new("a: b".to_string(), ("error".into(), Some(1), Some(3)));
+
+    always_color();
+    assert!(err.will_colorize());
+    assert!(err.to_string().contains('\u{1b}'));
+
+    never_color();
+    assert!(!err.will_colorize());
+    assert!(!err.to_string().contains('\u{1b}'));
+}